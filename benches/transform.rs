@@ -0,0 +1,103 @@
+//! Benchmarks of `resolver` and the plugin on representative inputs.
+//!
+//! Parsing happens in the setup of each iteration, so only the passes are
+//! measured. Run with `cargo bench`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use swc_core::{
+    common::{sync::Lrc, FileName, Globals, Mark, SourceMap, GLOBALS},
+    ecma::{
+        ast::{EsVersion, Program},
+        parser::{parse_file_as_program, EsSyntax, Syntax},
+        transforms::base::resolver,
+        visit::visit_mut_pass,
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::{AsyncToNgGeneratorVisitor, Config};
+
+/// A typical small service file.
+const SMALL: &str = include_str!("../tests/fixture/async-class-method/input.js");
+
+/// A file with `count` async functions, cycling through declarations,
+/// arrows, object methods and class methods.
+fn many_functions(count: usize) -> String {
+    (0..count)
+        .map(|i| match i % 4 {
+            0 => format!(
+                "async function load{i}(id) {{\n    const res = await fetch('/items/' + id);\n    return res.json();\n}}\n"
+            ),
+            1 => format!(
+                "const refresh{i} = async (id) => {{\n    return await this.http.get(id, arguments);\n}};\n"
+            ),
+            2 => format!(
+                "export const store{i} = {{\n    async get(id) {{\n        return await load{}(id);\n    }},\n}};\n",
+                i - 2
+            ),
+            _ => format!(
+                "class Service{i} {{\n    async save(item) {{\n        await this.store.put(item);\n        return item;\n    }}\n}}\n"
+            ),
+        })
+        .collect()
+}
+
+/// A file nesting `depth` levels of async object methods and async arrows
+/// inside one class method.
+fn nested_methods(depth: usize) -> String {
+    let mut body = String::from("return await this.work(arguments.length);");
+    for level in (0..depth).rev() {
+        body = if level % 2 == 0 {
+            format!("const step{level} = async () => {{ {body} }};\nreturn await step{level}();")
+        } else {
+            format!(
+                "const obj{level} = {{ async run() {{ {body} }} }};\nreturn await obj{level}.run();"
+            )
+        };
+    }
+    format!("class Nested {{\n    async run() {{\n{body}\n    }}\n}}\n")
+}
+
+/// Parse `source` and run `resolver` on it.
+fn parse(source: &str, marks: (Mark, Mark)) -> Program {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+    let program = parse_file_as_program(
+        &fm,
+        Syntax::Es(EsSyntax::default()),
+        EsVersion::latest(),
+        None,
+        &mut vec![],
+    )
+    .expect("benchmark source is valid JavaScript");
+    program.apply(resolver(marks.0, marks.1, false))
+}
+
+/// Benchmark the plugin with the default config on `source`.
+fn bench_source(c: &mut Criterion, name: &str, source: &str) {
+    GLOBALS.set(&Globals::new(), || {
+        c.bench_function(name, |b| {
+            b.iter_batched(
+                || {
+                    let marks = (Mark::new(), Mark::new());
+                    (parse(source, marks), marks)
+                },
+                |(program, marks)| {
+                    program.apply(visit_mut_pass(AsyncToNgGeneratorVisitor::with_marks(
+                        Config::default(),
+                        marks.0,
+                        marks.1,
+                    )))
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    });
+}
+
+fn transform(c: &mut Criterion) {
+    bench_source(c, "small file", SMALL);
+    bench_source(c, "1k async functions", &many_functions(1000));
+    bench_source(c, "deeply nested methods", &nested_methods(40));
+}
+
+criterion_group!(benches, transform);
+criterion_main!(benches);
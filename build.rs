@@ -0,0 +1,7 @@
+fn main() {
+    // Link flags for the native Node addon
+    #[cfg(feature = "napi")]
+    if std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() != Ok("wasm32") {
+        napi_build::setup();
+    }
+}
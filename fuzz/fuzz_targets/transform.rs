@@ -0,0 +1,115 @@
+//! Runs `resolver`, the plugin, `hygiene` and `fixer` on arbitrary source
+//! and checks that the transform never hits an internal error (panics abort
+//! the fuzzer) and that its output parses again.
+//!
+//! The first byte of the input picks the options, so the variants of the
+//! output are covered too; the rest is the source.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use swc_core::{
+    common::{sync::Lrc, FileName, Globals, Mark, SourceMap, GLOBALS},
+    ecma::{
+        ast::{EsVersion, Program},
+        codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter},
+        parser::{parse_file_as_program, EsSyntax, Syntax},
+        transforms::base::{fixer::fixer, hygiene::hygiene, resolver},
+        visit::VisitMutWith,
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::{
+    AsyncToNgGeneratorVisitor, AwaitlessFunctions, Config, FileSkipReason, HoistPosition,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&flags, source)) = data.split_first() else {
+        return;
+    };
+    let Ok(source) = std::str::from_utf8(source) else {
+        return;
+    };
+
+    GLOBALS.set(&Globals::new(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let Some(program) = parse(&cm, source) else {
+            return;
+        };
+
+        let unresolved_mark = Mark::new();
+        let top_level_mark = Mark::new();
+        let mut program = program.apply(resolver(unresolved_mark, top_level_mark, false));
+        let mut visitor =
+            AsyncToNgGeneratorVisitor::with_marks(config(flags), unresolved_mark, top_level_mark);
+        program.visit_mut_with(&mut visitor);
+        assert_ne!(
+            visitor.report().skipped_file,
+            Some(FileSkipReason::InternalError),
+            "internal error on:\n{}",
+            source
+        );
+
+        let program = program.apply((hygiene(), fixer(None)));
+        let output = print(&cm, &program);
+        assert!(
+            parse(&cm, &output).is_some(),
+            "the output does not parse:\n{}\n\ninput:\n{}",
+            output,
+            source
+        );
+    });
+});
+
+/// Options selected by the bits of `flags`.
+fn config(flags: u8) -> Config {
+    let bit = |n: u8| flags & (1 << n) != 0;
+    Config::builder()
+        .loose(bit(0))
+        .lazy_refs(bit(1))
+        .esnext_output(bit(2))
+        .eager_helpers(bit(3))
+        .simplify_return_await(bit(4))
+        .awaitless_functions(if bit(5) {
+            AwaitlessFunctions::Promise
+        } else {
+            AwaitlessFunctions::Wrap
+        })
+        .hoist_position(if bit(6) {
+            HoistPosition::Top
+        } else {
+            HoistPosition::AfterDecls
+        })
+        .named_generators(bit(7))
+        .build()
+        .expect("the options are valid")
+}
+
+/// Parse `source` as JavaScript with JSX and decorators, rejecting sources
+/// the parser had to recover from.
+fn parse(cm: &Lrc<SourceMap>, source: &str) -> Option<Program> {
+    let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+    let syntax = Syntax::Es(EsSyntax {
+        jsx: true,
+        decorators: true,
+        ..Default::default()
+    });
+    let mut errors = vec![];
+    let program =
+        parse_file_as_program(&fm, syntax, EsVersion::latest(), None, &mut errors).ok()?;
+    errors.is_empty().then_some(program)
+}
+
+/// Print `program` as JavaScript.
+fn print(cm: &Lrc<SourceMap>, program: &Program) -> String {
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: CodegenConfig::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
+        emitter.emit_program(program).unwrap();
+    }
+    String::from_utf8(buf).unwrap()
+}
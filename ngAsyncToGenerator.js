@@ -0,0 +1,142 @@
+// Runtime helper for swc-plugin-transform-async-to-ng-generator.
+//
+// Drives the generators emitted by the plugin. Promises are created with
+// AngularJS's `$q` once it is registered, so every resumption runs inside a
+// digest cycle:
+//
+//     app.run(["$q", function ($q) { _ngAsyncToGenerator.$q = $q; }]);
+//
+// Until then native promises are used.
+//
+// With the plugin's `zone` option the generated code passes a second
+// argument, `getZone`, called once per invocation. Every resumption of the
+// generator then runs through `zone.run(...)`, which re-enters a Zone.js zone
+// (`Zone.current`) or an Angular `NgZone` after each `await`.
+//
+// `_ngUsingCtx` backs the `using` / `await using` declarations of transformed
+// bodies and is only needed by files that have some.
+
+function asyncGeneratorStep(gen, resolve, reject, _next, _throw, key, arg) {
+    try {
+        var info = gen[key](arg);
+        var value = info.value;
+    } catch (error) {
+        reject(error);
+        return;
+    }
+    if (info.done) {
+        resolve(value);
+    } else {
+        var $q = _ngAsyncToGenerator.$q;
+        ($q ? $q.resolve(value) : Promise.resolve(value)).then(_next, _throw);
+    }
+}
+
+function _ngAsyncToGenerator(fn, getZone) {
+    return function () {
+        var self = this, args = arguments;
+        var $q = _ngAsyncToGenerator.$q;
+        var zone = getZone && getZone();
+        function executor(resolve, reject) {
+            var gen = fn.apply(self, args);
+            function step(key, arg) {
+                var stepArgs = [gen, resolve, reject, _next, _throw, key, arg];
+                if (zone) {
+                    zone.run(asyncGeneratorStep, null, stepArgs);
+                } else {
+                    asyncGeneratorStep.apply(null, stepArgs);
+                }
+            }
+            function _next(value) {
+                step("next", value);
+            }
+            function _throw(err) {
+                step("throw", err);
+            }
+            _next(undefined);
+        }
+        return $q ? $q(executor) : new Promise(executor);
+    };
+}
+
+function _ngUsingCtx() {
+    var empty = {};
+    var stack = [];
+    var hasAsync = false;
+    function register(value, isAwait) {
+        if (value == null) {
+            // `await using x = null` still awaits once on disposal
+            if (isAwait) {
+                stack.push({ a: true });
+                hasAsync = true;
+            }
+            return value;
+        }
+        if (Object(value) !== value) {
+            throw new TypeError("using declarations can only be used with objects, functions, null, or undefined.");
+        }
+        var dispose;
+        if (isAwait) {
+            dispose = value[Symbol.asyncDispose || Symbol.for("Symbol.asyncDispose")];
+        }
+        if (dispose == null) {
+            dispose = value[Symbol.dispose || Symbol.for("Symbol.dispose")];
+        }
+        if (typeof dispose !== "function") {
+            throw new TypeError("Object is not disposable.");
+        }
+        stack.push({ v: value, d: dispose, a: isAwait });
+        hasAsync = hasAsync || isAwait;
+        return value;
+    }
+    var ctx = {
+        e: empty,
+        u: function (value) {
+            return register(value, false);
+        },
+        a: function (value) {
+            return register(value, true);
+        },
+        d: function () {
+            var error = ctx.e;
+            function fail(err) {
+                if (error === empty) {
+                    error = err;
+                } else if (typeof SuppressedError === "function") {
+                    error = new SuppressedError(err, error, "An error was suppressed during disposal.");
+                } else {
+                    var suppressed = new Error("An error was suppressed during disposal.");
+                    suppressed.name = "SuppressedError";
+                    suppressed.error = err;
+                    suppressed.suppressed = error;
+                    error = suppressed;
+                }
+            }
+            function next() {
+                while (stack.length) {
+                    var resource = stack.pop();
+                    try {
+                        var result = resource.d && resource.d.call(resource.v);
+                        if (resource.a) {
+                            return Promise.resolve(result).then(next, function (err) {
+                                fail(err);
+                                return next();
+                            });
+                        }
+                    } catch (err) {
+                        fail(err);
+                    }
+                }
+                if (error !== empty) {
+                    throw error;
+                }
+            }
+            // Disposal settles as a promise once anything was registered
+            // with `await using`, even if a sync resource throws first
+            return hasAsync ? new Promise(function (resolve) {
+                resolve(next());
+            }) : next();
+        }
+    };
+    return ctx;
+}
@@ -1,28 +1,120 @@
 //! AST node builder utilities.
 //!
 //! This module provides helper functions for creating common AST nodes
-//! used throughout the transformation process.
+//! used throughout the transformation process. It is public for other SWC
+//! plugins that generate the same shapes (IIFEs, delegating `apply` calls,
+//! self-replacing helpers, `_ngAsyncToGenerator` calls):
+//!
+//! ```rust,ignore
+//! use swc_plugin_transform_async_to_ng_generator::ast_builders::*;
+//!
+//! // var _ref = _ngAsyncToGenerator(function* () { ... });
+//! let generator = fn_expr(None, vec![], body, true);
+//! let wrapped = ng_async_wrapper(generator, NG_ASYNC_WRAPPER, hygiene);
+//! let decl = var_decl_with_kind(VarDeclKind::Var, hygiene.private_ident("_ref"), wrapped);
+//! ```
+//!
+//! The builders follow semver like the rest of the crate: their signatures
+//! and the code they build only change in a major release, while new
+//! builders may come in minor ones. Generated nodes have `DUMMY_SP` spans
+//! and need the `fixer` pass before printing, like any generated code.
+//!
+//! Fixed shapes (delegating calls, self-replacing helpers) are written as
+//! `quote!` templates, so the generated code can be read off the source;
+//! the builders for shapes with varying parts construct nodes directly.
+//!
+//! Builders take names as `impl Into<Atom>`: fixed names are passed as
+//! static atoms (`atom!("apply")`) and names already held as atoms are
+//! passed as they are, so building a wrapper does not intern its strings
+//! again for every transformed function.
 
 use swc_core::{
-    common::{SyntaxContext, DUMMY_SP},
-    ecma::{ast::*, atoms::Atom},
+    common::{Mark, Span, SyntaxContext, DUMMY_SP},
+    ecma::{
+        ast::*,
+        atoms::{atom, Atom},
+    },
+    quote,
 };
 
-/// Create an identifier with the given name.
-pub fn ident(name: &str) -> Ident {
+/// Default name of the runtime helper that drives generated generators.
+pub const NG_ASYNC_WRAPPER: &str = "_ngAsyncToGenerator";
+
+/// Default name of the runtime helper that disposes `using` declarations.
+pub const NG_USING_CTX: &str = "_ngUsingCtx";
+
+/// Name of the module-local helper of `sharedArrowWrapper`.
+pub const NG_WRAP: &str = "_ngWrap";
+
+/// Syntax contexts of identifiers created by the transform.
+///
+/// Every binding the transform introduces (`_ref`, `_this`, `_foo`, ...) is
+/// minted under a fresh `Mark`, so a later `hygiene()` pass can tell it apart
+/// from a user binding with the same name and rename it instead of letting
+/// one shadow the other.
+///
+/// References to globals (`arguments`, runtime helpers) use the context of
+/// the `resolver` pass's unresolved mark, matching what `resolver` itself
+/// would have produced. Without a resolver it is simply empty.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hygiene {
+    /// Context of generated bindings and the references to them.
+    pub generated: SyntaxContext,
+    /// Context of references to globals.
+    pub unresolved: SyntaxContext,
+}
+
+impl Hygiene {
+    /// Create contexts with a fresh mark under `parent`. Must run inside
+    /// swc's `GLOBALS`.
+    pub fn fresh(parent: Mark, unresolved: SyntaxContext) -> Self {
+        Self {
+            generated: SyntaxContext::empty().apply_mark(Mark::fresh(parent)),
+            unresolved,
+        }
+    }
+
+    /// Create an identifier for a generated binding.
+    pub fn private_ident(&self, name: impl Into<Atom>) -> Ident {
+        Ident {
+            ctxt: self.generated,
+            ..ident(name)
+        }
+    }
+
+    /// Create a reference to a global such as `arguments`.
+    pub fn global_ident(&self, name: impl Into<Atom>) -> Ident {
+        Ident {
+            ctxt: self.unresolved,
+            ..ident(name)
+        }
+    }
+}
+
+/// Create an identifier with the given name and an empty syntax context.
+///
+/// Generated bindings go through [`Hygiene::private_ident`] and globals
+/// through [`Hygiene::global_ident`] instead.
+pub fn ident(name: impl Into<Atom>) -> Ident {
     Ident {
         span: DUMMY_SP,
         ctxt: SyntaxContext::empty(),
-        sym: Atom::from(name),
+        sym: name.into(),
         optional: false,
     }
 }
 
 /// Create a binding identifier (used in variable declarations and parameters).
-pub fn binding_ident(name: &str) -> BindingIdent {
-    BindingIdent {
-        id: ident(name),
-        type_ann: None,
+pub fn binding_ident(id: Ident) -> BindingIdent {
+    BindingIdent { id, type_ann: None }
+}
+
+/// Create a plain function parameter: `name`
+pub fn param(id: Ident) -> Param {
+    Param {
+        span: DUMMY_SP,
+        decorators: vec![],
+        pat: Pat::Ident(binding_ident(id)),
     }
 }
 
@@ -51,29 +143,62 @@ pub fn expr_stmt(expr: Expr) -> Stmt {
     })
 }
 
-/// Create: `var name = init;`
-pub fn var_decl(name: &str, init: Expr) -> Stmt {
+/// Create: `<kind> name = init;`
+pub fn var_decl_with_kind(kind: VarDeclKind, id: Ident, init: Expr) -> Stmt {
     Stmt::Decl(Decl::Var(Box::new(VarDecl {
         span: DUMMY_SP,
         ctxt: SyntaxContext::empty(),
-        kind: VarDeclKind::Var,
+        kind,
         declare: false,
         decls: vec![VarDeclarator {
             span: DUMMY_SP,
-            name: Pat::Ident(binding_ident(name)),
+            name: Pat::Ident(binding_ident(id)),
             init: Some(Box::new(init)),
             definite: false,
         }],
     })))
 }
 
+/// Create: `<kind> a, b;`
+pub fn uninitialized_var_decl(kind: VarDeclKind, ids: Vec<Ident>) -> Stmt {
+    Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        span: DUMMY_SP,
+        ctxt: SyntaxContext::empty(),
+        kind,
+        declare: false,
+        decls: ids
+            .into_iter()
+            .map(|id| VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(binding_ident(id)),
+                init: None,
+                definite: false,
+            })
+            .collect(),
+    })))
+}
+
 /// Create: `var _this = this;`
-pub fn this_capture() -> Stmt {
-    var_decl("_this", Expr::This(ThisExpr { span: DUMMY_SP }))
+pub fn this_capture(kind: VarDeclKind, this: Ident) -> Stmt {
+    var_decl_with_kind(kind, this, Expr::This(ThisExpr { span: DUMMY_SP }))
+}
+
+/// Create: `var _arguments = arguments;`
+pub fn arguments_capture(kind: VarDeclKind, arguments: Ident, hygiene: Hygiene) -> Stmt {
+    var_decl_with_kind(
+        kind,
+        arguments,
+        Expr::Ident(hygiene.global_ident(atom!("arguments"))),
+    )
 }
 
 /// Create a function expression.
-pub fn fn_expr(name: Option<Ident>, params: Vec<Param>, body: BlockStmt, is_generator: bool) -> Expr {
+pub fn fn_expr(
+    name: Option<Ident>,
+    params: Vec<Param>,
+    body: BlockStmt,
+    is_generator: bool,
+) -> Expr {
     Expr::Fn(FnExpr {
         ident: name,
         function: Box::new(Function {
@@ -90,9 +215,33 @@ pub fn fn_expr(name: Option<Ident>, params: Vec<Param>, body: BlockStmt, is_gene
     })
 }
 
-/// Create a generator function expression: `function* () { ... }`
-pub fn generator_fn_expr(params: Vec<Param>, body: BlockStmt) -> Expr {
-    fn_expr(None, params, body, true)
+/// Create a function expression from an existing function.
+pub fn function_expr(name: Option<Ident>, function: Function) -> Expr {
+    Expr::Fn(FnExpr {
+        ident: name,
+        function: Box::new(function),
+    })
+}
+
+/// Attach a TypeScript signature (type params and return type) to a
+/// function or arrow expression. Other expressions are returned unchanged.
+pub fn with_ts_signature(
+    mut expr: Expr,
+    type_params: Option<Box<TsTypeParamDecl>>,
+    return_type: Option<Box<TsTypeAnn>>,
+) -> Expr {
+    match &mut expr {
+        Expr::Fn(fn_expr) => {
+            fn_expr.function.type_params = type_params;
+            fn_expr.function.return_type = return_type;
+        }
+        Expr::Arrow(arrow) => {
+            arrow.type_params = type_params;
+            arrow.return_type = return_type;
+        }
+        _ => {}
+    }
+    expr
 }
 
 /// Create a regular function expression: `function () { ... }`
@@ -100,23 +249,96 @@ pub fn regular_fn_expr(name: Option<Ident>, body: BlockStmt) -> Expr {
     fn_expr(name, vec![], body, false)
 }
 
-/// Create a function declaration.
-pub fn fn_decl(name: &str, body: BlockStmt) -> FnDecl {
-    FnDecl {
-        ident: ident(name),
-        declare: false,
-        function: Box::new(Function {
-            params: vec![],
-            decorators: vec![],
-            span: DUMMY_SP,
-            ctxt: SyntaxContext::empty(),
-            body: Some(body),
-            is_generator: false,
-            is_async: false,
-            type_params: None,
-            return_type: None,
-        }),
-    }
+/// Create an arrow function with a block body: `() => { ... }`
+pub fn arrow_fn_expr(body: BlockStmt) -> Expr {
+    Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: SyntaxContext::empty(),
+        params: vec![],
+        body: Box::new(BlockStmtOrExpr::BlockStmt(body)),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    })
+}
+
+/// Create a promise-settling `try` around `result`:
+/// `try { return Promise.resolve(result); } catch (e) { return Promise.reject(e); }`
+pub fn promise_settling_try(result: Expr, error: Ident, hygiene: Hygiene) -> Stmt {
+    quote!(
+        "try {
+            return $promise.resolve($result);
+        } catch ($error) {
+            return $promise.reject($error);
+        }" as Stmt,
+        promise = hygiene.global_ident(atom!("Promise")),
+        result: Expr = result,
+        error = error
+    )
+}
+
+/// Create a helper that replaces itself with the `wrapped` generator on its
+/// first call and then calls it through `delegate`:
+///
+/// ```javascript
+/// function _foo() {
+///     _foo = _ngAsyncToGenerator(function* () { ... });
+///     return _foo.apply(this, arguments);
+/// }
+/// ```
+pub fn self_replacing_fn_decl(helper: Ident, wrapped: Expr, delegate: Expr) -> Stmt {
+    quote!(
+        "function $helper() {
+            $helper = $wrapped;
+            return $delegate;
+        }" as Stmt,
+        helper = helper,
+        wrapped: Expr = wrapped,
+        delegate: Expr = delegate
+    )
+}
+
+/// Create the anonymous form of [`self_replacing_fn_decl`], replacing the
+/// `binding` it is stored in:
+/// `function () { _ref = _ngAsyncToGenerator(...); return _ref.apply(this, arguments); }`
+pub fn self_replacing_fn_expr(binding: Ident, wrapped: Expr, delegate: Expr) -> Expr {
+    quote!(
+        "function () {
+            $binding = $wrapped;
+            return $delegate;
+        }" as Expr,
+        binding = binding,
+        wrapped: Expr = wrapped,
+        delegate: Expr = delegate
+    )
+}
+
+/// Create the helper of `sharedArrowWrapper`, which makes the wrapper an
+/// async arrow returns: `function _ngWrap(ref, self) { return function () {
+/// return ref.apply(self, arguments); }; }`
+pub fn shared_wrapper_decl(hygiene: Hygiene) -> Stmt {
+    quote!(
+        "function $wrap($target, $receiver) {
+            return function () {
+                return $target.apply($receiver, $arguments);
+            };
+        }" as Stmt,
+        wrap = hygiene.private_ident(NG_WRAP),
+        target = hygiene.private_ident(atom!("ref")),
+        receiver = hygiene.private_ident(atom!("self")),
+        arguments = hygiene.global_ident(atom!("arguments"))
+    )
+}
+
+/// Create: `_ngWrap(_ref)`, or `_ngWrap(_ref, _this)` for an arrow that
+/// reads `this`.
+pub fn shared_wrapper_call(target: Ident, this: Option<Ident>, hygiene: Hygiene) -> Expr {
+    let args = std::iter::once(target)
+        .chain(this)
+        .map(Expr::Ident)
+        .collect();
+    call_expr(Expr::Ident(hygiene.private_ident(NG_WRAP)), args)
 }
 
 /// Create: `callee(args...)`
@@ -137,40 +359,141 @@ pub fn call_expr(callee: Expr, args: Vec<Expr>) -> Expr {
 }
 
 /// Create: `obj.method`
-pub fn member_expr(obj: Expr, method: &str) -> Expr {
+pub fn member_expr(obj: Expr, method: impl Into<Atom>) -> Expr {
     Expr::Member(MemberExpr {
         span: DUMMY_SP,
         obj: Box::new(obj),
         prop: MemberProp::Ident(IdentName {
             span: DUMMY_SP,
-            sym: Atom::from(method),
+            sym: method.into(),
         }),
     })
 }
 
 /// Create: `wrapper.apply(this, arguments)`
-pub fn apply_call(wrapper: Expr) -> Expr {
-    call_expr(
-        member_expr(wrapper, "apply"),
-        vec![
-            Expr::This(ThisExpr { span: DUMMY_SP }),
-            Expr::Ident(ident("arguments")),
-        ],
+pub fn apply_call(wrapper: Expr, hygiene: Hygiene) -> Expr {
+    quote!(
+        "$wrapper.apply(this, $arguments)" as Expr,
+        wrapper: Expr = wrapper,
+        arguments = hygiene.global_ident(atom!("arguments"))
     )
 }
 
+/// Create an arrow that forwards its arguments to `wrapper`:
+/// `(_x, ...args) => wrapper(_x, ...args)`
+///
+/// The named `params` keep the arrow's `length`; the rest param is left out
+/// when `rest` is false.
+pub fn arrow_delegate(wrapper: Expr, params: Vec<Param>, rest: bool, hygiene: Hygiene) -> Expr {
+    let mut pats: Vec<Pat> = params.into_iter().map(|param| param.pat).collect();
+    let mut args: Vec<ExprOrSpread> = pats
+        .iter()
+        .filter_map(|pat| pat.as_ident())
+        .map(|binding| ExprOrSpread {
+            spread: None,
+            expr: Box::new(Expr::Ident(binding.id.clone())),
+        })
+        .collect();
+
+    if rest {
+        let rest_ident = hygiene.private_ident(atom!("args"));
+        pats.push(Pat::Rest(RestPat {
+            span: DUMMY_SP,
+            dot3_token: DUMMY_SP,
+            arg: Box::new(Pat::Ident(binding_ident(rest_ident.clone()))),
+            type_ann: None,
+        }));
+        args.push(ExprOrSpread {
+            spread: Some(DUMMY_SP),
+            expr: Box::new(Expr::Ident(rest_ident)),
+        });
+    }
+
+    let call = Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        ctxt: SyntaxContext::empty(),
+        callee: Callee::Expr(Box::new(wrapper)),
+        args,
+        type_args: None,
+    });
+    Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: SyntaxContext::empty(),
+        params: pats,
+        body: Box::new(BlockStmtOrExpr::Expr(Box::new(call))),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    })
+}
+
+/// Create: `wrapper.call(this)`
+pub fn call_with_this(wrapper: Expr, this: Expr) -> Expr {
+    quote!("$wrapper.call($this)" as Expr, wrapper: Expr = wrapper, this: Expr = this)
+}
+
+/// Create: `wrapper.call(this, _x, _x2, ...args)`, passing the arguments of a
+/// delegating wrapper explicitly; `rest` is spread after the `args`
+pub fn call_with_args(wrapper: Expr, this: Expr, args: Vec<Ident>, rest: Option<Ident>) -> Expr {
+    let args = args.into_iter().map(|arg| (None, arg));
+    let rest = rest.map(|rest| (Some(DUMMY_SP), rest));
+    let args = std::iter::once(ExprOrSpread {
+        spread: None,
+        expr: Box::new(this),
+    })
+    .chain(args.chain(rest).map(|(spread, arg)| ExprOrSpread {
+        spread,
+        expr: Box::new(Expr::Ident(arg)),
+    }));
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        ctxt: SyntaxContext::empty(),
+        callee: Callee::Expr(Box::new(member_expr(wrapper, atom!("call")))),
+        args: args.collect(),
+        type_args: None,
+    })
+}
+
+/// Create `wrapper.apply(this, arguments)`, or `wrapper.call(this)` when
+/// `arguments` need not be forwarded.
+pub fn delegate_call(wrapper: Expr, forward_arguments: bool, hygiene: Hygiene) -> Expr {
+    if forward_arguments {
+        apply_call(wrapper, hygiene)
+    } else {
+        call_with_this(wrapper, Expr::This(ThisExpr { span: DUMMY_SP }))
+    }
+}
+
 /// Create: `wrapper()`
 pub fn immediate_call(wrapper: Expr) -> Expr {
     call_expr(wrapper, vec![])
 }
 
-/// Create: `_ngAsyncToGenerator(function* () { ... })`
-pub fn ng_async_wrapper(generator_fn: Expr) -> Expr {
-    call_expr(Expr::Ident(ident("_ngAsyncToGenerator")), vec![generator_fn])
+/// Create: `_ngAsyncToGenerator(function* () { ... })`, calling the helper
+/// named `wrapper`
+pub fn ng_async_wrapper(generator_fn: Expr, wrapper: &str, hygiene: Hygiene) -> Expr {
+    quote!(
+        "$wrapper($generator_fn)" as Expr,
+        wrapper = hygiene.global_ident(wrapper),
+        generator_fn: Expr = generator_fn
+    )
+}
+
+/// Give a generated function, arrow or call the span of the code it
+/// replaces, so the comments attached there are printed with it.
+pub fn with_span(mut expr: Expr, span: Span) -> Expr {
+    match &mut expr {
+        Expr::Fn(fn_expr) => fn_expr.function.span = span,
+        Expr::Arrow(arrow) => arrow.span = span,
+        Expr::Call(call) => call.span = span,
+        _ => {}
+    }
+    expr
 }
 
 /// Create: `left = right`
-pub fn assign_expr(left: &str, right: Expr) -> Expr {
+pub fn assign_expr(left: Ident, right: Expr) -> Expr {
     Expr::Assign(AssignExpr {
         span: DUMMY_SP,
         op: AssignOp::Assign,
@@ -180,25 +503,26 @@ pub fn assign_expr(left: &str, right: Expr) -> Expr {
 }
 
 /// Create an IIFE (Immediately Invoked Function Expression):
-/// `(function() { ...stmts })()
+/// `(function() { ...stmts })()`
 pub fn iife(stmts: Vec<Stmt>) -> Expr {
     immediate_call(regular_fn_expr(None, block(stmts)))
 }
 
-/// Create an IIFE with `this` captured as a parameter:
-/// `(function(_this) { ...stmts })(this)`
+/// Create an IIFE with captured values passed as parameters:
+/// `(function(_this, _arguments) { ...stmts })(this, arguments)`
 ///
-/// This is used for arrow functions that use `this`, to capture the lexical `this`
-/// at the definition site.
-pub fn iife_with_this_param(stmts: Vec<Stmt>) -> Expr {
+/// This is used for arrow functions that use `this` or `arguments`, to capture
+/// the lexical bindings at the definition site.
+pub fn iife_with_captures(captures: Vec<(Ident, Expr)>, stmts: Vec<Stmt>) -> Expr {
+    let (params, args): (Vec<Param>, Vec<Expr>) = captures
+        .into_iter()
+        .map(|(id, value)| (param(id), value))
+        .unzip();
+
     let func = Expr::Fn(FnExpr {
         ident: None,
         function: Box::new(Function {
-            params: vec![Param {
-                span: DUMMY_SP,
-                decorators: vec![],
-                pat: Pat::Ident(binding_ident("_this")),
-            }],
+            params,
             decorators: vec![],
             span: DUMMY_SP,
             ctxt: SyntaxContext::empty(),
@@ -210,17 +534,18 @@ pub fn iife_with_this_param(stmts: Vec<Stmt>) -> Expr {
         }),
     });
 
-    // Call with `this` as argument: (function(_this) { ... })(this)
-    call_expr(func, vec![Expr::This(ThisExpr { span: DUMMY_SP })])
+    // Call with the captured values: (function(_this) { ... })(this)
+    call_expr(func, args)
 }
 
 /// Create: `wrapper.apply(_this, arguments)` - for arrow functions with captured this
-pub fn apply_call_with_captured_this(wrapper: Expr) -> Expr {
-    call_expr(
-        member_expr(wrapper, "apply"),
-        vec![
-            Expr::Ident(ident("_this")),
-            Expr::Ident(ident("arguments")),
-        ],
+///
+/// `this` is the `_this` param of an enclosing [`iife_with_captures`].
+pub fn apply_call_with_captured_this(wrapper: Expr, this: Ident, hygiene: Hygiene) -> Expr {
+    quote!(
+        "$wrapper.apply($this, $arguments)" as Expr,
+        wrapper: Expr = wrapper,
+        this = this,
+        arguments = hygiene.global_ident(atom!("arguments"))
     )
 }
@@ -1,13 +1,1203 @@
 //! Plugin configuration module.
 
-use serde::Deserialize;
+use std::{fmt, sync::Arc};
+
+use regex_lite::Regex;
+use serde::{de, Deserialize};
+use serde_json::{Map, Value};
+
+use crate::ast_builders::{NG_ASYNC_WRAPPER, NG_USING_CTX};
+use crate::emitter::WrapperEmitter;
+use crate::filter::{FunctionFilter, FunctionInfo};
+use crate::glob;
+use crate::targets::{self, Targets};
+
+/// Module and helper of `compat: "tsc"`.
+pub(crate) const TSLIB: &str = "tslib";
+pub(crate) const TSLIB_AWAITER: &str = "__awaiter";
+
+/// Runtime helpers the output calls, by their default names, with the
+/// option naming each: the keys of the `helperNames` JSON option.
+pub const HELPER_OPTIONS: &[(&str, &str)] = &[
+    (NG_ASYNC_WRAPPER, "wrapperFunctionName"),
+    (NG_USING_CTX, "usingHelperName"),
+];
+
+/// Globs of the files skipped by `skip_test_files`.
+pub const TEST_FILE_PATTERNS: &[&str] = &["*.spec.*", "*.test.*", "__tests__/**"];
+
+/// Names of the methods `lifecycle_hooks_only` transforms: the Angular
+/// lifecycle hooks and the AngularJS component hooks.
+pub const LIFECYCLE_HOOKS: &[&str] = &[
+    "ngOnChanges",
+    "ngOnInit",
+    "ngDoCheck",
+    "ngAfterContentInit",
+    "ngAfterContentChecked",
+    "ngAfterViewInit",
+    "ngAfterViewChecked",
+    "ngOnDestroy",
+    "$onInit",
+    "$onChanges",
+    "$doCheck",
+    "$onDestroy",
+    "$postLink",
+];
 
 /// Plugin configuration.
 ///
-/// Currently empty, reserved for future options like:
-/// - Custom wrapper function name (default: `_ngAsyncToGenerator`)
-/// - Whether to transform arrow functions
-/// - Whether to capture `this` in methods
-#[derive(Debug, Default, Clone, Deserialize)]
+/// Deserialized from the options object passed to the plugin in the SWC
+/// config. Every field is optional; missing fields fall back to
+/// [`Config::default`].
+///
+/// Options are added regularly, so Rust code outside this crate cannot
+/// build a `Config` with a struct expression. Use [`Config::builder`], or
+/// assign fields of a default config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[non_exhaustive]
+pub struct Config {
+    /// Keep TypeScript annotations on generated code (default: `false`).
+    ///
+    /// Generators keep the original type params, delegating wrappers keep
+    /// the original type params and return type, and arity placeholders keep
+    /// the original param annotations, after the `this` param if there is
+    /// one. This lets the plugin run before type stripping or emit
+    /// `.ts`-compatible output.
+    pub typescript: bool,
+
+    /// Rewrite `return await expr;` into `return expr;` inside generated
+    /// generators (default: `false`).
+    ///
+    /// Returns anywhere in a `try` statement, including its `catch` and
+    /// `finally`, are kept, since dropping the `await` there changes which
+    /// errors are caught and when `finally` runs.
+    pub simplify_return_await: bool,
+
+    /// Transform async functions whose bodies use direct `eval` or `with`
+    /// (default: `false`).
+    ///
+    /// Such code can observe the generated `_this`/`_ref` bindings and the
+    /// changed scope shape, so by default those functions are left native
+    /// and a warning points at the offending construct.
+    pub allow_eval_and_with: bool,
+
+    /// Fail the compilation instead of warning when a function is left
+    /// native because the transform does not support it: async generators,
+    /// `super` in async methods, direct `eval` / `with` and top-level
+    /// `await` (default: `false`).
+    pub error_on_unsupported: bool,
+
+    /// Deepest nesting of expressions and statements the transform walks
+    /// (default: `500`).
+    ///
+    /// Machine-generated code can nest deep enough to overflow the stack of
+    /// the recursive visitors, which would abort the host compiler. Files
+    /// nesting deeper are left untransformed with a warning instead.
+    pub max_nesting_depth: usize,
+
+    /// Largest async function the transform wraps, in bytes of source
+    /// (default: none).
+    ///
+    /// Wrapping very large functions (state machines, generated code) can
+    /// blow up the output and its compile time. Larger functions are left
+    /// native with a warning, for targets that support async functions and
+    /// only need the plugin for zones.
+    pub max_function_size: Option<u32>,
+
+    /// Explain what happens to every async function with a note at the
+    /// function: transformed and how, or left native and why
+    /// (default: `false`).
+    ///
+    /// Builds with the `debug` cargo feature log the same as `tracing`
+    /// events.
+    pub debug: bool,
+
+    /// Transform async arrow functions (default: `true`).
+    ///
+    /// Turn off to leave arrows native, e.g. when only methods need to be
+    /// downleveled for zone patching.
+    pub transform_arrow_functions: bool,
+
+    /// What to do with async functions that contain no `await`
+    /// (default: `"wrap"`).
+    pub awaitless_functions: AwaitlessFunctions,
+
+    /// Transform files that already contain the plugin's own output
+    /// (default: `false`).
+    ///
+    /// A `wrapper(function* () { ... })` call marks a file as
+    /// already compiled (prebuilt libraries, plugin configured twice), and
+    /// such files are skipped unless this is set.
+    pub force_reprocess: bool,
+    /// Runtime helper used to lower `using` / `await using` declarations
+    /// inside transformed bodies (default: `_ngUsingCtx`).
+    pub using_helper_name: String,
+
+    /// Create the generator wrappers of async arrows and function
+    /// expressions on their first call instead of where they are defined
+    /// (default: `false`).
+    ///
+    /// Saves the setup cost for functions that are rarely or never called.
+    pub lazy_refs: bool,
+
+    /// Have async arrows return a wrapper made by one module-local helper,
+    /// `_ngWrap(_ref)`, instead of a `function () { return _ref.apply(this,
+    /// arguments); }` of their own (default: `false`).
+    ///
+    /// Much smaller output for files with many async arrows, for one more
+    /// call per invocation. The wrappers keep neither the `name` nor the
+    /// `length` of the arrows. Cannot be combined with `lazy_refs`, whose
+    /// refs replace themselves after the helper took them.
+    pub shared_arrow_wrapper: bool,
+
+    /// Call generated wrappers with `.call(this)` instead of
+    /// `.apply(this, arguments)` when the function has no params and its
+    /// body never reads `arguments` (default: `false`).
+    pub loose: bool,
+
+    /// Conditions the code is guaranteed to meet, which allow smaller
+    /// output, like Babel's `assumptions` (default: none assumed).
+    pub assumptions: Assumptions,
+
+    /// How wrappers pass their `this` and arguments on to the generator
+    /// wrapper (default: [`CallStyle::Apply`]).
+    ///
+    /// Wrappers that need not forward arguments (see `loose`) always use
+    /// `.call(this)`.
+    pub call_style: CallStyle,
+
+    /// Give delegating wrappers the names of the original params instead of
+    /// `_x`, `_x2`, ... (default: `false`).
+    ///
+    /// `function load(id, options)` rather than `function load(_x, _x2)`
+    /// shows the signature in IDE tooltips and `toString()`. Only plain
+    /// params are named: defaults and rest params are still left out, and
+    /// destructured params keep a placeholder, since the wrapper must not
+    /// throw where the async function would reject.
+    pub preserve_wrapper_params: bool,
+
+    /// Start generated functions with `"use strict"` where their scope is
+    /// sloppy (default: `false`).
+    ///
+    /// Helpers hoisted out of a class or a strict function into script code
+    /// would otherwise run in sloppy mode, where `this` of a plain call is
+    /// the global object and `arguments` aliases the params. Modules, and
+    /// functions whose params are not plain identifiers (which cannot hold
+    /// a directive), are left as they are.
+    pub strict_generated_code: bool,
+
+    /// Emit ES2015+ output (default: `false`).
+    ///
+    /// Generated bindings use `const` (or `let`) instead of `var`, and the
+    /// wrappers returned for async arrows are arrows themselves:
+    /// `(_x, ...args) => _ref(_x, ...args)`. Function declarations,
+    /// function expressions and methods keep `function` wrappers since they
+    /// need their own `this`.
+    pub esnext_output: bool,
+
+    /// Compile the emitted generators to ES5 with regenerator in the same
+    /// run (default: `false`).
+    ///
+    /// The regenerator runtime is inlined into each changed file. Programs
+    /// given without the mark of `resolver` go through `resolver` first.
+    /// Cannot be combined with `strict_generated_code`,
+    /// `coverage_ignore_hint` or `marker_comment`.
+    pub regenerator: bool,
+
+    /// Kind of the generated `_ref` / `_this` / `_arguments` declarations
+    /// (default: `"var"`, or `"const"` with `esnext_output`).
+    ///
+    /// Bindings that are reassigned later (lazy `_ref` stubs) use `let`
+    /// when `"const"` is asked for.
+    pub declaration_kind: Option<DeclarationKind>,
+
+    /// Zone to re-enter whenever a transformed function resumes after an
+    /// `await`, as a dotted path such as `"Zone.current"` (default: none).
+    ///
+    /// The path is read each time the async function is called and handed
+    /// to the runtime helper as a getter, whose `run` method then drives
+    /// every step of the generator. Any object with a Zone.js-compatible
+    /// `run(fn, applyThis, applyArgs)` works, including Angular's `NgZone`.
+    pub zone: Option<String>,
+
+    /// Name the emitted generators after their original function, e.g.
+    /// `function* _load$()`, so stack traces are readable without source
+    /// maps (default: on when SWC runs in development mode).
+    pub named_generators: Option<bool>,
+
+    /// Keep the output measurable by coverage tools such as istanbul / nyc
+    /// (default: `false`).
+    ///
+    /// Each generator takes the span of the function body it runs, so
+    /// instrumenting the output and remapping through source maps counts
+    /// the original function instead of an unmapped generated one.
+    pub coverage: bool,
+
+    /// Comment placed before generated functions that contain no code of
+    /// the original file, e.g. `"istanbul ignore next"` or
+    /// `"c8 ignore next"` (default: none).
+    ///
+    /// Tags delegating wrappers, zone getters and the inlined runtime helper,
+    /// so they are not reported as uncovered. Needs the program's comments.
+    pub coverage_ignore_hint: Option<String>,
+
+    /// Comment placed before every statement the transform generated, e.g.
+    /// `"ng-async-generated"` for `/* ng-async-generated */` (default: none).
+    ///
+    /// Lets reviews, lint exemptions and coverage filters pick out the
+    /// helpers, wrappers and references of the plugin. Needs the program's
+    /// comments.
+    pub marker_comment: Option<String>,
+
+    /// Name of the variable that captures `this` for methods and arrows
+    /// (default: `_this`).
+    pub this_var_name: String,
+
+    /// Name of the variable that captures `arguments` for methods and
+    /// arrows (default: `_arguments`).
+    pub arguments_var_name: String,
+
+    /// Prefix of the generator references created for arrows and function
+    /// expressions (default: `_ref`, giving `_ref`, `_ref1`, ...).
+    pub ref_prefix: String,
+
+    /// Name generator references after a short hash of the name of their
+    /// function, or of its innermost named enclosing function, and of their
+    /// position among the references of that name (default: `false`).
+    ///
+    /// `_ref_1a2b3c` instead of `_ref1`: adding or removing an async
+    /// function only renames the references of the functions of the same
+    /// name after it, rather than every reference later in the file, which
+    /// keeps diffs of committed output small.
+    pub hashed_refs: bool,
+
+    /// Prefix of the hoisted helper created for a function declaration
+    /// (default: `_`, giving `_foo` for `foo`).
+    pub helper_prefix: String,
+
+    /// Where hoisted helpers land in their statement list
+    /// (default: `"afterDecls"`).
+    pub hoist_position: HoistPosition,
+
+    /// Create the generator wrapper of a function declaration where its
+    /// helper is placed, `var _foo = _ngAsyncToGenerator(...)`, instead of
+    /// in a helper function that replaces itself on the first call
+    /// (default: `false`).
+    ///
+    /// Unlike the function, the variable is only assigned once its
+    /// statement runs, so calls made before that fail. Combine with
+    /// `hoistPosition: "top"` when functions are called early.
+    pub eager_helpers: bool,
+
+    /// Shape of the transformed functions (default: `"ngAsync"`).
+    ///
+    /// `"tsc"` emits what TypeScript emits for ES2015+ targets: the body
+    /// becomes a call to tslib's `__awaiter`, so code compiled by tsc and by
+    /// this plugin share one runtime. The helper is imported from `"tslib"`
+    /// (or `import_helper`), and the options shaping the `_ref` / `_foo`
+    /// wrappers do not apply.
+    pub compat: Compat,
+
+    /// Runtime helper that generated generators are passed to
+    /// (default: `_ngAsyncToGenerator`).
+    ///
+    /// Lets projects that ship their own helper (e.g. `__zoneAwait`) target
+    /// it directly.
+    pub wrapper_function_name: String,
+
+    /// Import the runtime helper into every file the plugin changed
+    /// (default: none, the helper must exist as a global).
+    ///
+    /// Modules get `import { name as wrapper } from "module"`, scripts get
+    /// `var wrapper = require("module").name`.
+    pub import_helper: Option<ImportHelper>,
+
+    /// How the imported helper is bound (default: by the kind of file,
+    /// `import` in modules and `require` in scripts).
+    ///
+    /// `Cjs` requires the helper in modules too, for output that another
+    /// tool already turns into CommonJS without rewriting imports; `Global`
+    /// imports nothing and uses the global helper, as tslib's script build
+    /// provides for `compat: "tsc"`. Scripts cannot `import`, so `Esm` still
+    /// requires the helper there.
+    pub import_style: Option<ImportStyle>,
+
+    /// Emit the runtime helper itself at the top of every file the plugin
+    /// changed (default: `false`).
+    ///
+    /// For bundles where neither a global nor an import is possible. Takes
+    /// precedence over `import_helper`.
+    pub inline_helper: bool,
+
+    /// Check at the top of every file the plugin changed that the runtime
+    /// helper exists, when it is neither imported nor inlined (default:
+    /// `false`).
+    ///
+    /// A missing global helper then throws an error naming the runtime
+    /// module to provide, instead of a `ReferenceError` on the first call
+    /// of an async function. Meant for development builds, e.g. under
+    /// `env.development`.
+    pub assert_helper: bool,
+
+    /// Only transform files whose name matches one of these globs
+    /// (default: empty, every file).
+    pub include: Vec<String>,
+
+    /// Never transform files whose name matches one of these globs
+    /// (default: empty). Wins over `include`.
+    pub exclude: Vec<String>,
+
+    /// Never transform unit test files (default: `false`).
+    ///
+    /// Matches [`TEST_FILE_PATTERNS`], so tests keep native async functions
+    /// and their stack traces under Jest or Karma while app code is
+    /// transformed.
+    pub skip_test_files: bool,
+
+    /// Only transform files with a `/* @ng-async */` comment before their
+    /// first statement (default: `false`).
+    ///
+    /// Lets a codebase migrate file by file.
+    pub require_pragma: bool,
+
+    /// Only transform async functions inside classes decorated with one of
+    /// these decorators, e.g. `["Component", "Injectable"]` (default: empty,
+    /// every function).
+    ///
+    /// Both `@Component` and `@Component({...})` match. Functions outside
+    /// such classes are left native.
+    pub only_decorated_classes: Vec<String>,
+
+    /// Only transform async lifecycle hooks (`ngOnInit`, `$onInit`, ... see
+    /// [`LIFECYCLE_HOOKS`]) and the async functions written inside them
+    /// (default: `false`).
+    ///
+    /// Hooks are the methods where awaiting inside the digest or the zone
+    /// matters most; every other async function stays native. Combine with
+    /// `only_decorated_classes` to only consider the hooks of components.
+    pub lifecycle_hooks_only: bool,
+
+    /// Regular expressions on function names, e.g. `["^test", "Mock$"]`;
+    /// async functions whose name matches one stay native in every file
+    /// (default: none).
+    ///
+    /// For test helpers and generated stubs. Declarations and function
+    /// expressions go by their name, methods by their identifier key, and
+    /// anonymous functions by the name inferred from their binding.
+    pub exclude_function_names: Vec<String>,
+
+    /// Environments the output runs in, e.g. `{ "chrome": "61" }`
+    /// (default: none).
+    ///
+    /// When every target runs async functions natively the plugin leaves
+    /// files untouched. Unknown environments keep the transform enabled.
+    pub targets: Option<Targets>,
+
+    /// Builds the wrapper around each generator instead of the runtime
+    /// helper call (default: none).
+    ///
+    /// Only available to Rust embedders: it is not read from the JSON
+    /// options. See [`WrapperEmitter`].
+    #[serde(skip)]
+    pub wrapper_emitter: Option<Arc<dyn WrapperEmitter>>,
+
+    /// Decides, per async function, whether it is transformed (default:
+    /// none, every function the other options select).
+    ///
+    /// Only available to Rust embedders, for policies the static options
+    /// cannot express. See [`FunctionFilter`].
+    #[serde(skip)]
+    pub function_filter: Option<FunctionFilter>,
+
+    /// Leave a file untransformed, with a warning, when the transform
+    /// panics on it instead of passing the panic on (default: false).
+    ///
+    /// Only available to Rust embedders: the wasm plugin aborts on panics,
+    /// so they cannot be caught there. Each program with async code is
+    /// copied before the transform to restore it from.
+    #[serde(skip)]
+    pub catch_panics: bool,
+}
+
+/// Output shape of `compat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Config {}
+pub enum Compat {
+    /// Generators passed to the runtime helper of `wrapper_function_name`,
+    /// through hoisted wrappers.
+    #[default]
+    NgAsync,
+    /// `return __awaiter(this, void 0, void 0, function* () { ... })` in
+    /// place of the body, like tsc with tslib.
+    Tsc,
+}
+
+/// How wrappers delegate, see [`Config::call_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CallStyle {
+    /// `_foo.apply(this, arguments)`.
+    #[default]
+    Apply,
+    /// `_foo.call(this, _x, _x2)`, passing the wrapper's params. Falls back
+    /// to `.apply` when they do not stand in for every param (defaults,
+    /// rest params) or the function reads `arguments`.
+    Call,
+    /// `function foo(_x, ...args) { return _foo.call(this, _x, ...args); }`,
+    /// adding a rest param to the wrapper; its `length` is unchanged.
+    Spread,
+}
+
+/// Guarantees about the compiled code, see [`Config::assumptions`].
+///
+/// Named after the Babel assumptions that apply to this transform. The
+/// others do not change its output: `noNewArrows` is what the wrappers of
+/// async arrows already assume, and `noDocumentAll` concerns `null` checks
+/// the output never makes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Assumptions {
+    /// Nothing reads the `length` of async functions, so delegating
+    /// wrappers get no `_x` placeholder params. They keep the params
+    /// `preserve_wrapper_params` or `callStyle: "call"` need.
+    pub ignore_function_length: bool,
+}
+
+/// Handling of async functions without `await`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AwaitlessFunctions {
+    /// Wrap them like any other async function, so they keep returning a
+    /// promise and report exceptions as rejections.
+    #[default]
+    Wrap,
+    /// Only remove the `async` keyword. Smaller, but callers get a plain
+    /// value instead of a promise and exceptions are thrown synchronously.
+    Drop,
+    /// Settle a promise with `Promise.resolve` / `Promise.reject` around the
+    /// unchanged body. Keeps promise semantics with much less code than a
+    /// generator, but the promise is a native one rather than `$q`.
+    Promise,
+}
+
+/// Placement of the hoisted `_foo` helpers of async function declarations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HoistPosition {
+    /// Right after the original function, keeping each helper next to its
+    /// wrapper.
+    Adjacent,
+    /// After the last function declaration of the statement list.
+    #[default]
+    AfterDecls,
+    /// At the start of the statement list.
+    Top,
+}
+
+/// Kind of generated variable declarations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeclarationKind {
+    Var,
+    Let,
+    Const,
+}
+
+/// Binding of the imported helper, see [`Config::import_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportStyle {
+    /// `import { name as wrapper } from "module"`.
+    Esm,
+    /// `var wrapper = require("module").name`.
+    Cjs,
+    /// Nothing; the helper is a global.
+    Global,
+}
+
+/// Where to import the runtime helper from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportHelper {
+    /// Module specifier, e.g. `"@app/ng-async"`.
+    pub module: String,
+    /// Exported name of the helper. Defaults to `wrapperFunctionName`.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            typescript: false,
+            simplify_return_await: false,
+            allow_eval_and_with: false,
+            error_on_unsupported: false,
+            max_nesting_depth: 500,
+            max_function_size: None,
+            debug: false,
+            transform_arrow_functions: true,
+            awaitless_functions: AwaitlessFunctions::Wrap,
+            force_reprocess: false,
+            using_helper_name: NG_USING_CTX.to_string(),
+            lazy_refs: false,
+            shared_arrow_wrapper: false,
+            loose: false,
+            assumptions: Assumptions::default(),
+            call_style: CallStyle::Apply,
+            preserve_wrapper_params: false,
+            strict_generated_code: false,
+            esnext_output: false,
+            regenerator: false,
+            declaration_kind: None,
+            zone: None,
+            named_generators: None,
+            coverage: false,
+            coverage_ignore_hint: None,
+            marker_comment: None,
+            this_var_name: "_this".to_string(),
+            arguments_var_name: "_arguments".to_string(),
+            ref_prefix: "_ref".to_string(),
+            hashed_refs: false,
+            helper_prefix: "_".to_string(),
+            hoist_position: HoistPosition::AfterDecls,
+            eager_helpers: false,
+            compat: Compat::NgAsync,
+            wrapper_function_name: NG_ASYNC_WRAPPER.to_string(),
+            import_helper: None,
+            import_style: None,
+            inline_helper: false,
+            assert_helper: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            skip_test_files: false,
+            require_pragma: false,
+            only_decorated_classes: Vec::new(),
+            lifecycle_hooks_only: false,
+            exclude_function_names: Vec::new(),
+            targets: None,
+            wrapper_emitter: None,
+            function_filter: None,
+            catch_panics: false,
+        }
+    }
+}
+
+impl Config {
+    /// Start building a config from the defaults:
+    ///
+    /// ```rust,ignore
+    /// let config = Config::builder()
+    ///     .wrapper_function_name("ngAsync")
+    ///     .transform_arrow_functions(false)
+    ///     .build()?;
+    /// ```
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Check the options the transform emits as code: helper and variable
+    /// names must be identifiers and `zone` a dotted path of them.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let names = [
+            ("usingHelperName", &self.using_helper_name),
+            ("thisVarName", &self.this_var_name),
+            ("argumentsVarName", &self.arguments_var_name),
+            ("refPrefix", &self.ref_prefix),
+            ("helperPrefix", &self.helper_prefix),
+            ("wrapperFunctionName", &self.wrapper_function_name),
+        ];
+        for (option, name) in names {
+            if !is_identifier(name) {
+                return Err(ConfigError::new(
+                    option,
+                    format!("`{}` is not an identifier", name),
+                ));
+            }
+        }
+        if let Some(zone) = &self.zone {
+            if !zone.split('.').all(is_identifier) {
+                return Err(ConfigError::new(
+                    "zone",
+                    format!("`{}` is not a dotted path such as `Zone.current`", zone),
+                ));
+            }
+        }
+        if let Some(import) = &self.import_helper {
+            if import.module.is_empty() {
+                return Err(ConfigError::new("importHelper", "`module` is empty"));
+            }
+            if let Some(name) = import.name.as_deref().filter(|name| !is_identifier(name)) {
+                return Err(ConfigError::new(
+                    "importHelper",
+                    format!("`{}` is not an identifier", name),
+                ));
+            }
+        }
+        if self.compat == Compat::Tsc {
+            let conflict = if self.inline_helper {
+                Some("inlineHelper")
+            } else if self.zone.is_some() {
+                Some("zone")
+            } else if self.regenerator {
+                Some("regenerator")
+            } else if self.shared_arrow_wrapper {
+                Some("sharedArrowWrapper")
+            } else {
+                None
+            };
+            if let Some(option) = conflict {
+                return Err(ConfigError::new(
+                    "compat",
+                    format!("`\"tsc\"` cannot be combined with `{}`", option),
+                ));
+            }
+        }
+        if self.regenerator {
+            // These passes recognise generated functions by their shape,
+            // which regenerator compiles away
+            let conflict = if self.strict_generated_code {
+                Some("strictGeneratedCode")
+            } else if self.coverage_ignore_hint.is_some() {
+                Some("coverageIgnoreHint")
+            } else if self.marker_comment.is_some() {
+                Some("markerComment")
+            } else {
+                None
+            };
+            if let Some(option) = conflict {
+                return Err(ConfigError::new(
+                    "regenerator",
+                    format!("cannot be combined with `{}`", option),
+                ));
+            }
+        }
+        if self.shared_arrow_wrapper && self.lazy_refs {
+            return Err(ConfigError::new(
+                "sharedArrowWrapper",
+                "cannot be combined with `lazyRefs`",
+            ));
+        }
+        if self.max_nesting_depth == 0 {
+            return Err(ConfigError::new("maxNestingDepth", "must be at least 1"));
+        }
+        for pattern in &self.exclude_function_names {
+            if let Err(error) = Regex::new(pattern) {
+                return Err(ConfigError::new(
+                    "excludeFunctionNames",
+                    format!("`{}` is not a regular expression: {}", pattern, error),
+                ));
+            }
+        }
+        if self.max_function_size == Some(0) {
+            return Err(ConfigError::new("maxFunctionSize", "must be at least 1"));
+        }
+        Ok(())
+    }
+
+    /// Parse the plugin options object from the SWC config.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        Self::from_json_for_env(json, None)
+    }
+
+    /// Parse the plugin options object for the environment SWC runs in
+    /// (`development`, `production`, ...).
+    ///
+    /// Options under `env.<name>` override the top-level ones key by key:
+    ///
+    /// ```json
+    /// { "zone": "Zone.current", "env": { "development": { "exclude": ["**"] } } }
+    /// ```
+    ///
+    /// Environments without an entry use the top-level options unchanged.
+    ///
+    /// `helperNames` renames the runtime helpers in one map keyed by their
+    /// default names (see [`HELPER_OPTIONS`]), and takes precedence over
+    /// the options naming them one by one:
+    ///
+    /// ```json
+    /// { "helperNames": { "_ngAsyncToGenerator": "ngAsync", "_ngUsingCtx": "ngUsing" } }
+    /// ```
+    pub fn from_json_for_env(json: &str, env: Option<&str>) -> serde_json::Result<Self> {
+        let mut options: Value = serde_json::from_str(json)?;
+        if let Some(options) = options.as_object_mut() {
+            match options.remove("env") {
+                None => {}
+                Some(Value::Object(mut overrides)) => {
+                    match env.and_then(|env| overrides.remove(env)) {
+                        None => {}
+                        Some(Value::Object(selected)) => options.extend(selected),
+                        Some(_) => return Err(de::Error::custom("`env` entries must be objects")),
+                    }
+                }
+                Some(_) => return Err(de::Error::custom("`env` must be an object")),
+            }
+            apply_helper_names(options)?;
+        }
+        serde_json::from_value(options)
+    }
+
+    /// Parse the plugin options like [`Config::from_json_for_env`], fill in
+    /// the defaults that depend on the environment (named generators are on
+    /// for `development` unless set explicitly) and [`validate`] the result.
+    ///
+    /// This is how the wasm plugin and the Node addon read their options, so
+    /// both reject the same ones.
+    ///
+    /// [`validate`]: Config::validate
+    pub fn from_options_for_env(json: &str, env: Option<&str>) -> serde_json::Result<Self> {
+        let mut config = Self::from_json_for_env(json, env)?;
+        if config.named_generators.is_none() {
+            config.named_generators = Some(env == Some("development"));
+        }
+        config.validate().map_err(de::Error::custom)?;
+        Ok(config)
+    }
+
+    /// The `exclude_function_names` patterns, leaving out invalid ones (see
+    /// [`Config::validate`]).
+    pub(crate) fn excluded_function_names(&self) -> Vec<Regex> {
+        self.exclude_function_names
+            .iter()
+            .filter_map(|pattern| Regex::new(pattern).ok())
+            .collect()
+    }
+
+    /// Name of the runtime helper the output calls: `__awaiter` with
+    /// `compat: "tsc"`, `wrapper_function_name` otherwise.
+    pub fn helper_name(&self) -> &str {
+        match self.compat {
+            Compat::NgAsync => &self.wrapper_function_name,
+            Compat::Tsc => TSLIB_AWAITER,
+        }
+    }
+
+    /// Whether generators get names (see `named_generators`). Off unless set
+    /// explicitly or filled in from the environment.
+    pub fn names_generators(&self) -> bool {
+        self.named_generators.unwrap_or(false)
+    }
+
+    /// Whether a file should be transformed according to `include`,
+    /// `exclude` and `skip_test_files`. Files without a known name only pass
+    /// when `include` is empty.
+    pub fn should_transform_file(&self, filename: Option<&str>) -> bool {
+        let Some(filename) = filename else {
+            return self.include.is_empty();
+        };
+
+        let included = self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| glob::matches(pattern, filename));
+        let excluded = self
+            .exclude
+            .iter()
+            .any(|pattern| glob::matches(pattern, filename))
+            || (self.skip_test_files
+                && TEST_FILE_PATTERNS
+                    .iter()
+                    .any(|pattern| glob::matches(pattern, filename)));
+        included && !excluded
+    }
+
+    /// Whether `targets` is set and every target runs async functions
+    /// natively, making the transform unnecessary.
+    pub fn targets_support_async(&self) -> bool {
+        self.targets
+            .as_ref()
+            .is_some_and(targets::support_async_functions)
+    }
+}
+
+/// Whether `name` is a JavaScript identifier (reserved words aside).
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first == '_' || first == '$' || first.is_alphabetic())
+        && chars.all(|c| c == '_' || c == '$' || c.is_alphanumeric())
+}
+
+/// Builder of a [`Config`], started with [`Config::builder`].
+///
+/// Every option has a setter named after its field; options left alone
+/// keep their default. [`ConfigBuilder::build`] validates the result.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// See [`Config::typescript`].
+    pub fn typescript(mut self, typescript: bool) -> Self {
+        self.config.typescript = typescript;
+        self
+    }
+
+    /// See [`Config::simplify_return_await`].
+    pub fn simplify_return_await(mut self, simplify: bool) -> Self {
+        self.config.simplify_return_await = simplify;
+        self
+    }
+
+    /// See [`Config::allow_eval_and_with`].
+    pub fn allow_eval_and_with(mut self, allow: bool) -> Self {
+        self.config.allow_eval_and_with = allow;
+        self
+    }
+
+    /// See [`Config::error_on_unsupported`].
+    pub fn error_on_unsupported(mut self, error: bool) -> Self {
+        self.config.error_on_unsupported = error;
+        self
+    }
+
+    /// See [`Config::max_nesting_depth`].
+    pub fn max_nesting_depth(mut self, depth: usize) -> Self {
+        self.config.max_nesting_depth = depth;
+        self
+    }
+
+    /// See [`Config::max_function_size`].
+    pub fn max_function_size(mut self, bytes: u32) -> Self {
+        self.config.max_function_size = Some(bytes);
+        self
+    }
+
+    /// See [`Config::debug`].
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.config.debug = debug;
+        self
+    }
+
+    /// See [`Config::transform_arrow_functions`].
+    pub fn transform_arrow_functions(mut self, transform: bool) -> Self {
+        self.config.transform_arrow_functions = transform;
+        self
+    }
+
+    /// See [`Config::awaitless_functions`].
+    pub fn awaitless_functions(mut self, awaitless: AwaitlessFunctions) -> Self {
+        self.config.awaitless_functions = awaitless;
+        self
+    }
+
+    /// See [`Config::force_reprocess`].
+    pub fn force_reprocess(mut self, force: bool) -> Self {
+        self.config.force_reprocess = force;
+        self
+    }
+
+    /// See [`Config::using_helper_name`].
+    pub fn using_helper_name(mut self, name: impl Into<String>) -> Self {
+        self.config.using_helper_name = name.into();
+        self
+    }
+
+    /// See [`Config::lazy_refs`].
+    pub fn lazy_refs(mut self, lazy: bool) -> Self {
+        self.config.lazy_refs = lazy;
+        self
+    }
+
+    /// See [`Config::shared_arrow_wrapper`].
+    pub fn shared_arrow_wrapper(mut self, shared: bool) -> Self {
+        self.config.shared_arrow_wrapper = shared;
+        self
+    }
+
+    /// See [`Config::loose`].
+    pub fn loose(mut self, loose: bool) -> Self {
+        self.config.loose = loose;
+        self
+    }
+
+    /// See [`Config::assumptions`].
+    pub fn assumptions(mut self, assumptions: Assumptions) -> Self {
+        self.config.assumptions = assumptions;
+        self
+    }
+
+    /// See [`Config::call_style`].
+    pub fn call_style(mut self, style: CallStyle) -> Self {
+        self.config.call_style = style;
+        self
+    }
+
+    /// See [`Config::preserve_wrapper_params`].
+    pub fn preserve_wrapper_params(mut self, preserve: bool) -> Self {
+        self.config.preserve_wrapper_params = preserve;
+        self
+    }
+
+    /// See [`Config::strict_generated_code`].
+    pub fn strict_generated_code(mut self, strict: bool) -> Self {
+        self.config.strict_generated_code = strict;
+        self
+    }
+
+    /// See [`Config::esnext_output`].
+    pub fn esnext_output(mut self, esnext: bool) -> Self {
+        self.config.esnext_output = esnext;
+        self
+    }
+
+    /// See [`Config::regenerator`].
+    pub fn regenerator(mut self, regenerator: bool) -> Self {
+        self.config.regenerator = regenerator;
+        self
+    }
+
+    /// See [`Config::declaration_kind`].
+    pub fn declaration_kind(mut self, kind: DeclarationKind) -> Self {
+        self.config.declaration_kind = Some(kind);
+        self
+    }
+
+    /// See [`Config::zone`].
+    pub fn zone(mut self, zone: impl Into<String>) -> Self {
+        self.config.zone = Some(zone.into());
+        self
+    }
+
+    /// See [`Config::named_generators`].
+    pub fn named_generators(mut self, named: bool) -> Self {
+        self.config.named_generators = Some(named);
+        self
+    }
+
+    /// See [`Config::coverage`].
+    pub fn coverage(mut self, coverage: bool) -> Self {
+        self.config.coverage = coverage;
+        self
+    }
+
+    /// See [`Config::coverage_ignore_hint`].
+    pub fn coverage_ignore_hint(mut self, hint: impl Into<String>) -> Self {
+        self.config.coverage_ignore_hint = Some(hint.into());
+        self
+    }
+
+    /// See [`Config::marker_comment`].
+    pub fn marker_comment(mut self, marker: impl Into<String>) -> Self {
+        self.config.marker_comment = Some(marker.into());
+        self
+    }
+
+    /// See [`Config::this_var_name`].
+    pub fn this_var_name(mut self, name: impl Into<String>) -> Self {
+        self.config.this_var_name = name.into();
+        self
+    }
+
+    /// See [`Config::arguments_var_name`].
+    pub fn arguments_var_name(mut self, name: impl Into<String>) -> Self {
+        self.config.arguments_var_name = name.into();
+        self
+    }
+
+    /// See [`Config::ref_prefix`].
+    pub fn ref_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.ref_prefix = prefix.into();
+        self
+    }
+
+    /// See [`Config::hashed_refs`].
+    pub fn hashed_refs(mut self, hashed: bool) -> Self {
+        self.config.hashed_refs = hashed;
+        self
+    }
+
+    /// See [`Config::helper_prefix`].
+    pub fn helper_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.helper_prefix = prefix.into();
+        self
+    }
+
+    /// See [`Config::hoist_position`].
+    pub fn hoist_position(mut self, position: HoistPosition) -> Self {
+        self.config.hoist_position = position;
+        self
+    }
+
+    /// See [`Config::eager_helpers`].
+    pub fn eager_helpers(mut self, eager: bool) -> Self {
+        self.config.eager_helpers = eager;
+        self
+    }
+
+    /// See [`Config::compat`].
+    pub fn compat(mut self, compat: Compat) -> Self {
+        self.config.compat = compat;
+        self
+    }
+
+    /// See [`Config::wrapper_function_name`].
+    pub fn wrapper_function_name(mut self, name: impl Into<String>) -> Self {
+        self.config.wrapper_function_name = name.into();
+        self
+    }
+
+    /// See [`Config::import_helper`].
+    pub fn import_helper(mut self, import: ImportHelper) -> Self {
+        self.config.import_helper = Some(import);
+        self
+    }
+
+    /// See [`Config::import_style`].
+    pub fn import_style(mut self, style: ImportStyle) -> Self {
+        self.config.import_style = Some(style);
+        self
+    }
+
+    /// See [`Config::inline_helper`].
+    pub fn inline_helper(mut self, inline: bool) -> Self {
+        self.config.inline_helper = inline;
+        self
+    }
+
+    /// See [`Config::assert_helper`].
+    pub fn assert_helper(mut self, assert: bool) -> Self {
+        self.config.assert_helper = assert;
+        self
+    }
+
+    /// See [`Config::include`].
+    pub fn include<I: IntoIterator<Item = S>, S: Into<String>>(mut self, globs: I) -> Self {
+        self.config.include = globs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// See [`Config::exclude`].
+    pub fn exclude<I: IntoIterator<Item = S>, S: Into<String>>(mut self, globs: I) -> Self {
+        self.config.exclude = globs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// See [`Config::skip_test_files`].
+    pub fn skip_test_files(mut self, skip: bool) -> Self {
+        self.config.skip_test_files = skip;
+        self
+    }
+
+    /// See [`Config::require_pragma`].
+    pub fn require_pragma(mut self, require: bool) -> Self {
+        self.config.require_pragma = require;
+        self
+    }
+
+    /// See [`Config::only_decorated_classes`].
+    pub fn only_decorated_classes<I: IntoIterator<Item = S>, S: Into<String>>(
+        mut self,
+        decorators: I,
+    ) -> Self {
+        self.config.only_decorated_classes = decorators.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// See [`Config::lifecycle_hooks_only`].
+    pub fn lifecycle_hooks_only(mut self, only: bool) -> Self {
+        self.config.lifecycle_hooks_only = only;
+        self
+    }
+
+    /// See [`Config::exclude_function_names`].
+    pub fn exclude_function_names<I: IntoIterator<Item = S>, S: Into<String>>(
+        mut self,
+        patterns: I,
+    ) -> Self {
+        self.config.exclude_function_names = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// See [`Config::targets`].
+    pub fn targets(mut self, targets: Targets) -> Self {
+        self.config.targets = Some(targets);
+        self
+    }
+
+    /// See [`Config::wrapper_emitter`].
+    pub fn wrapper_emitter<E: WrapperEmitter + 'static>(mut self, emitter: E) -> Self {
+        self.config.wrapper_emitter = Some(Arc::new(emitter));
+        self
+    }
+
+    /// See [`Config::function_filter`].
+    pub fn function_filter<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&FunctionInfo<'_>) -> bool + Send + Sync + 'static,
+    {
+        self.config.function_filter = Some(FunctionFilter::new(predicate));
+        self
+    }
+
+    /// See [`Config::catch_panics`].
+    pub fn catch_panics(mut self, enabled: bool) -> Self {
+        self.config.catch_panics = enabled;
+        self
+    }
+
+    /// Validate the options (see [`Config::validate`]) and return the
+    /// config.
+    pub fn build(self) -> Result<Config, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Replace the `helperNames` entry of the JSON `options` by the options
+/// naming each helper.
+fn apply_helper_names(options: &mut Map<String, Value>) -> serde_json::Result<()> {
+    let names = match options.remove("helperNames") {
+        None => return Ok(()),
+        Some(Value::Object(names)) => names,
+        Some(_) => return Err(de::Error::custom("`helperNames` must be an object")),
+    };
+    for (helper, name) in names {
+        let Some((_, option)) = HELPER_OPTIONS
+            .iter()
+            .find(|(default, _)| *default == helper)
+        else {
+            let known: Vec<String> = HELPER_OPTIONS
+                .iter()
+                .map(|(default, _)| format!("`{}`", default))
+                .collect();
+            return Err(de::Error::custom(format!(
+                "unknown helper `{}` in `helperNames`, expected one of {}",
+                helper,
+                known.join(", ")
+            )));
+        };
+        options.insert(option.to_string(), name);
+    }
+    Ok(())
+}
+
+/// Invalid option found by [`Config::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// Name of the option as written in the SWC config, e.g. `"zone"`.
+    pub option: &'static str,
+    /// What is wrong with its value.
+    pub message: String,
+}
+
+impl ConfigError {
+    fn new(option: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            option,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid `{}` option: {}", self.option, self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
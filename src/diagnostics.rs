@@ -0,0 +1,40 @@
+//! Diagnostics reported through SWC's error handler.
+//!
+//! Messages are only emitted when a handler is installed (always the case
+//! inside the plugin host and the test harness), so embedders driving the
+//! visitor directly never panic on a missing handler.
+
+use swc_core::common::{errors::HANDLER, Span};
+
+use crate::config::Config;
+
+/// Emit a warning pointing at `span`.
+pub fn warn(span: Span, message: &str) {
+    if HANDLER.is_set() {
+        HANDLER.with(|handler| handler.struct_span_warn(span, message).emit());
+    }
+}
+
+/// Emit a note pointing at `span`, which never fails the compilation.
+pub fn note(span: Span, message: &str) {
+    if HANDLER.is_set() {
+        HANDLER.with(|handler| handler.span_note_without_error(span, message));
+    }
+}
+
+/// Emit an error pointing at `span`, which fails the compilation.
+pub fn error(span: Span, message: &str) {
+    if HANDLER.is_set() {
+        HANDLER.with(|handler| handler.struct_span_err(span, message).emit());
+    }
+}
+
+/// Report a construct the transform left native: a warning, or an error
+/// with `errorOnUnsupported`.
+pub fn unsupported(span: Span, message: &str, config: &Config) {
+    if config.error_on_unsupported {
+        error(span, message);
+    } else {
+        warn(span, message);
+    }
+}
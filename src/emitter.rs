@@ -0,0 +1,90 @@
+//! Extension point for the code that runs the generators.
+//!
+//! Every transformed function ends up with its body in a `function*`
+//! generator handed to the runtime helper:
+//!
+//! ```javascript
+//! _ngAsyncToGenerator(function* () { ... })
+//! ```
+//!
+//! Embedders can replace that expression per function kind with a
+//! [`WrapperEmitter`] in [`Config::wrapper_emitter`], e.g. to report
+//! telemetry around the helper or hand the generator to a scheduler of
+//! their own. The rest of the output (delegating wrappers, `_ref`
+//! declarations, captures) is unchanged.
+
+use std::fmt;
+
+use swc_core::ecma::ast::{Expr, Ident};
+
+use crate::ast_builders::Hygiene;
+use crate::config::Config;
+use crate::report::FunctionKind;
+use crate::transforms::runtime_wrapper;
+
+/// Builds the expression that turns the generator of a transformed function
+/// into a function returning a promise.
+///
+/// The result is called with the `this` and arguments of the original
+/// function (right away for methods, through `_foo` / `_ref` otherwise), so
+/// it must evaluate to a function that runs the generator and returns a
+/// promise of its result. The generator itself must be kept in the output:
+/// `regenerator`, `coverage` and the already-transformed check look at it.
+///
+/// ```rust,ignore
+/// #[derive(Debug)]
+/// struct Telemetry;
+///
+/// impl WrapperEmitter for Telemetry {
+///     fn wrap_generator(&self, generator: Expr, context: &WrapperContext<'_>) -> Expr {
+///         // traced("load", _ngAsyncToGenerator(function* () { ... }))
+///         let name = context.name.unwrap_or("anonymous");
+///         let wrapped = context.default_wrapper(generator);
+///         let name: Expr = name.into();
+///         quote_expr!("traced($name, $wrapped)", name: Expr = name, wrapped: Expr = wrapped)
+///     }
+/// }
+///
+/// let config = Config::builder().wrapper_emitter(Telemetry).build()?;
+/// ```
+pub trait WrapperEmitter: fmt::Debug + Send + Sync {
+    /// Wrap `generator`, the `function* () { ... }` holding the body of a
+    /// function described by `context`. Defaults to the runtime helper call
+    /// the plugin emits, [`WrapperContext::default_wrapper`].
+    fn wrap_generator(&self, generator: Expr, context: &WrapperContext<'_>) -> Expr {
+        context.default_wrapper(generator)
+    }
+}
+
+/// The function a generator is wrapped for.
+pub struct WrapperContext<'a> {
+    /// Kind of the transformed function.
+    pub kind: FunctionKind,
+    /// Its name, for declarations, named or inferred expressions and arrows,
+    /// and methods with identifier keys.
+    pub name: Option<&'a str>,
+    /// Options the transform runs with.
+    pub config: &'a Config,
+    pub(crate) hygiene: Hygiene,
+}
+
+impl WrapperContext<'_> {
+    /// The wrapper the plugin emits by default:
+    /// `_ngAsyncToGenerator(generator)`, with the zone getter when `zone` is
+    /// set.
+    pub fn default_wrapper(&self, generator: Expr) -> Expr {
+        runtime_wrapper(generator, self.config, self.hygiene)
+    }
+
+    /// Reference to the global `name`, resolving like the globals of the
+    /// original program.
+    pub fn global_ident(&self, name: &str) -> Ident {
+        self.hygiene.global_ident(name)
+    }
+
+    /// Contexts for identifiers built with the
+    /// [`ast_builders`](crate::ast_builders).
+    pub fn hygiene(&self) -> Hygiene {
+        self.hygiene
+    }
+}
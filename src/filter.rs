@@ -0,0 +1,75 @@
+//! Per-function filter for Rust embedders.
+//!
+//! [`Config::function_filter`] holds a predicate deciding, for each async
+//! function the static options would transform, whether it is transformed:
+//!
+//! ```rust,ignore
+//! let config = Config::builder()
+//!     .function_filter(|info| !info.name.is_some_and(|name| name.ends_with("Native")))
+//!     .build()?;
+//! ```
+//!
+//! Functions it rejects are left native and reported with
+//! [`SkipReason::Filtered`].
+//!
+//! [`Config::function_filter`]: crate::Config::function_filter
+//! [`SkipReason::Filtered`]: crate::SkipReason::Filtered
+
+use std::{fmt, sync::Arc};
+
+use swc_core::common::Span;
+
+use crate::report::FunctionKind;
+
+/// An async function about to be transformed.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct FunctionInfo<'a> {
+    pub kind: FunctionKind,
+    /// Name of the function: declared, inferred from the binding it is
+    /// assigned to, or the identifier key of a method.
+    pub name: Option<&'a str>,
+    /// Span of the function in the original program.
+    pub span: Span,
+    /// Innermost class the function is written in, if any.
+    pub class: Option<&'a EnclosingClass>,
+}
+
+/// A class enclosing an async function.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EnclosingClass {
+    /// Name of the class, unless it is an anonymous class expression.
+    pub name: Option<String>,
+    /// Names of its decorators: `Component` for `@Component`,
+    /// `@Component({...})` and `@core.Component`.
+    pub decorators: Vec<String>,
+}
+
+/// Predicate of [`Config::function_filter`](crate::Config::function_filter).
+///
+/// It may be called more than once for the same function, so it should only
+/// depend on the [`FunctionInfo`].
+#[derive(Clone)]
+pub struct FunctionFilter(Arc<dyn Fn(&FunctionInfo<'_>) -> bool + Send + Sync>);
+
+impl FunctionFilter {
+    /// Wrap a predicate returning whether a function is transformed.
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&FunctionInfo<'_>) -> bool + Send + Sync + 'static,
+    {
+        Self(Arc::new(predicate))
+    }
+
+    /// Whether the function described by `info` is transformed.
+    pub fn transforms(&self, info: &FunctionInfo<'_>) -> bool {
+        (self.0)(info)
+    }
+}
+
+impl fmt::Debug for FunctionFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FunctionFilter(..)")
+    }
+}
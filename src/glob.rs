@@ -0,0 +1,52 @@
+//! Minimal glob matching for the `include` / `exclude` options.
+//!
+//! Supported syntax:
+//! - `*` matches any run of characters except `/`
+//! - `**` matches any run of characters, including `/`
+//! - `?` matches a single character except `/`
+//!
+//! Patterns are matched against the whole path or any suffix starting right
+//! after a `/`, so `node_modules/**` matches `/repo/node_modules/a.js`.
+//! Backslashes in paths are treated as `/`.
+
+/// Whether `path` matches `pattern`.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    let mut start = 0;
+    loop {
+        let candidate: Vec<char> = path[start..].chars().collect();
+        if match_from(&pattern, &candidate) {
+            return true;
+        }
+        match path[start..].find('/') {
+            Some(i) => start += i + 1,
+            None => return false,
+        }
+    }
+}
+
+/// Whether the whole of `text` matches the whole of `pattern`.
+fn match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `**/` may also match zero directories
+            let rest_without_slash = rest.strip_prefix(&['/']).unwrap_or(rest);
+            (0..=text.len())
+                .any(|i| match_from(rest, &text[i..]) || match_from(rest_without_slash, &text[i..]))
+        }
+        ['*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| match_from(rest, &text[i..])),
+        ['?', rest @ ..] => match text {
+            [c, text_rest @ ..] if *c != '/' => match_from(rest, text_rest),
+            _ => false,
+        },
+        [p, rest @ ..] => match text {
+            [c, text_rest @ ..] if c == p => match_from(rest, text_rest),
+            _ => false,
+        },
+    }
+}
@@ -41,14 +41,12 @@
 //! };
 //!
 //! // Output
-//! const fetchData = (function () {
-//!     var _ref = _ngAsyncToGenerator(function* () {
-//!         return yield fetch('/api');
-//!     });
-//!     return function () {
-//!         return _ref.apply(this, arguments);
-//!     };
-//! })();
+//! var _ref = _ngAsyncToGenerator(function* () {
+//!     return yield fetch('/api');
+//! });
+//! const fetchData = function fetchData() {
+//!     return _ref.apply(this, arguments);
+//! };
 //! ```
 //!
 //! ### Async Class Method
@@ -76,31 +74,206 @@
 //! ## Module Structure
 //!
 //! - [`config`]: Plugin configuration
-//! - [`ast_builders`]: Helper functions for creating AST nodes
+//! - [`ast_builders`]: Helper functions for creating AST nodes (public)
+//! - [`diagnostics`]: Warnings reported through SWC's error handler
+//! - [`emitter`]: Extension point for the generated wrapper calls
+//! - [`filter`]: Per-function filter for Rust embedders
+//! - [`glob`]: Filename matching for `include` / `exclude`
+//! - [`node`]: Native Node.js bindings (`napi` feature)
+//! - [`pragma`]: Comment pragmas controlling the transform
+//! - [`report`]: Statistics about what the transform did
+//! - [`source`]: Source-to-source API (`source` feature)
+//! - [`targets`]: Target environments for the `targets` option
 //! - [`transforms`]: Transformation logic for different async function types
 //! - [`visitor`]: Main AST visitor
+//!
+//! ## Rust API
+//!
+//! Besides the wasm entry point (the default `plugin` feature; build with
+//! `default-features = false` to leave out swc's plugin proxies),
+//! [`transform_program`] and [`transform_program_with`] run the transform
+//! on a `Program` built with the `swc_ecma_*` crates directly, e.g. in a
+//! custom bundler or codemod.
+//! [`async_to_ng_generator`] returns it as a `Pass` to chain with other
+//! swc passes. With the `source` feature, `transform_source` goes from
+//! source text to source text, and the `napi` feature exposes that to Node
+//! as a native addon.
+//!
+//! [`runtime_module`] returns the runtime helper as a module (and
+//! `runtime_source`, with the `source` feature, as text), so integrations
+//! serve the helper that matches the generated code. A [`WrapperEmitter`]
+//! set with [`ConfigBuilder::wrapper_emitter`] replaces the helper call
+//! around each generator, and [`ConfigBuilder::function_filter`] decides per
+//! function whether it is transformed.
 
-mod ast_builders;
+pub mod ast_builders;
 mod config;
+mod diagnostics;
+mod emitter;
+mod filter;
+mod glob;
+#[cfg(all(feature = "napi", not(target_arch = "wasm32")))]
+mod node;
+mod pragma;
+mod report;
+#[cfg(feature = "source")]
+mod source;
+mod targets;
 mod transforms;
 mod visitor;
 
 // Public exports
-pub use config::Config;
+pub use config::{
+    Assumptions, AwaitlessFunctions, CallStyle, Compat, Config, ConfigBuilder, ConfigError,
+    DeclarationKind, HoistPosition, ImportHelper, ImportStyle, HELPER_OPTIONS, LIFECYCLE_HOOKS,
+    TEST_FILE_PATTERNS,
+};
+pub use emitter::{WrapperContext, WrapperEmitter};
+pub use filter::{EnclosingClass, FunctionFilter, FunctionInfo};
+pub use report::{FileSkipReason, FunctionKind, SkipReason, SkippedFunction, TransformReport};
+#[cfg(feature = "source")]
+pub use source::{
+    runtime_source, transform_source, transform_source_with_map, SourceError, TransformOutput,
+};
+pub use targets::{TargetVersion, Targets};
+pub use transforms::runtime_module;
 pub use visitor::AsyncToNgGeneratorVisitor;
 
+#[cfg(feature = "plugin")]
+use swc_core::{
+    common::Spanned,
+    plugin::{
+        metadata::TransformPluginMetadataContextKind, plugin_transform,
+        proxies::TransformPluginProgramMetadata,
+    },
+};
 use swc_core::{
-    ecma::{ast::Program, visit::VisitMutWith},
-    plugin::{plugin_transform, proxies::TransformPluginProgramMetadata},
+    common::{comments::Comments, Mark},
+    ecma::{
+        ast::{Pass, Program},
+        visit::{visit_mut_pass, VisitMutWith},
+    },
 };
 
-/// Plugin entry point.
+/// Everything about a program the transform uses besides its AST.
+pub struct TransformContext {
+    /// Name of the file, matched against `include` / `exclude`. Without a
+    /// name, the file is only transformed when `include` is empty.
+    pub filename: Option<String>,
+    /// Mark `resolver` gave unresolved references, or the root mark for
+    /// programs that did not go through `resolver`.
+    pub unresolved_mark: Mark,
+    /// Comments of the program, enabling the comment pragmas and the
+    /// coverage hints.
+    pub comments: Option<Box<dyn Comments>>,
+}
+
+impl Default for TransformContext {
+    fn default() -> Self {
+        Self {
+            filename: None,
+            unresolved_mark: Mark::root(),
+            comments: None,
+        }
+    }
+}
+
+/// The transform as a pass, for swc pipelines:
 ///
-/// This function is called by SWC to transform the program.
-#[plugin_transform]
-pub fn process_transform(program: Program, _metadata: TransformPluginProgramMetadata) -> Program {
-    let mut visitor = AsyncToNgGeneratorVisitor::new();
+/// ```rust,ignore
+/// let program = program.apply((
+///     resolver(unresolved_mark, top_level_mark, false),
+///     async_to_ng_generator(Config::default(), unresolved_mark),
+///     hygiene(),
+/// ));
+/// ```
+///
+/// `unresolved_mark` is the mark given to `resolver`, or `Mark::root()`
+/// when the pipeline has no `resolver`. File filters are not applied, since
+/// a pass does not know the filename.
+pub fn async_to_ng_generator(config: Config, unresolved_mark: Mark) -> impl Pass {
+    visit_mut_pass(AsyncToNgGeneratorVisitor::with_marks(
+        config,
+        unresolved_mark,
+        Mark::root(),
+    ))
+}
+
+/// Transform the async functions of `program` with `config`.
+///
+/// This runs the same transform as the plugin, without the plugin runtime,
+/// for programs that have no filename, comments or `resolver` marks to
+/// share. See [`transform_program_with`] for those.
+///
+/// ```rust,ignore
+/// let program = transform_program(program, Config::default());
+/// ```
+pub fn transform_program(program: Program, config: Config) -> Program {
+    transform_program_with(program, config, TransformContext::default())
+}
+
+/// Transform the async functions of `program` with `config`, using what
+/// `context` knows about the file.
+///
+/// Files filtered out by `include` / `exclude` / `skipTestFiles` are
+/// returned unchanged.
+pub fn transform_program_with(
+    program: Program,
+    config: Config,
+    context: TransformContext,
+) -> Program {
+    if !config.should_transform_file(context.filename.as_deref()) {
+        return program;
+    }
+
+    // `resolver` shares only its unresolved mark with later passes, so
+    // generated marks hang off the root mark
+    let mut visitor =
+        AsyncToNgGeneratorVisitor::with_marks(config, context.unresolved_mark, Mark::root());
+    if let Some(comments) = context.comments {
+        visitor = visitor.with_boxed_comments(comments);
+    }
     let mut program = program;
     program.visit_mut_with(&mut visitor);
     program
 }
+
+/// Plugin entry point.
+///
+/// This function is called by SWC to transform the program. It reads the
+/// config and context from the plugin metadata and hands over to
+/// [`transform_program_with`]. Only built with the `plugin` feature (on by
+/// default).
+///
+/// An invalid config is reported as an error on the file, which is left
+/// untransformed, instead of aborting the plugin.
+#[cfg(feature = "plugin")]
+#[plugin_transform]
+pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
+    let env = metadata.get_context(&TransformPluginMetadataContextKind::Env);
+    let json = metadata
+        .get_transform_plugin_config()
+        .unwrap_or_else(|| "{}".to_string());
+    let config = match Config::from_options_for_env(&json, env.as_deref()) {
+        Ok(config) => config,
+        Err(error) => {
+            diagnostics::error(
+                program.span(),
+                &format!(
+                    "invalid config for swc-plugin-transform-async-to-ng-generator: {}",
+                    error
+                ),
+            );
+            return program;
+        }
+    };
+
+    let context = TransformContext {
+        filename: metadata.get_context(&TransformPluginMetadataContextKind::Filename),
+        unresolved_mark: metadata.unresolved_mark,
+        comments: metadata
+            .comments
+            .map(|comments| Box::new(comments) as Box<dyn Comments>),
+    };
+    transform_program_with(program, config, context)
+}
@@ -0,0 +1,91 @@
+//! Native Node.js bindings, behind the `napi` cargo feature.
+//!
+//! For toolchains that cannot load wasm plugins, the addon exposes the same
+//! transform to JavaScript:
+//!
+//! ```javascript
+//! const { transform, transformFile, transformWithMap } =
+//!     require('./swc-plugin-transform-async-to-ng-generator.node');
+//!
+//! const output = transform('async function f() { await g(); }', { loose: true });
+//! const fileOutput = transformFile('src/service.js', { exclude: ['vendor/**'] });
+//! const { code, map } = transformWithMap(source, 'src/service.js', {});
+//! ```
+//!
+//! `options` takes the plugin options object. Options under `env` are
+//! selected with `NODE_ENV`, like SWC's default `envName`.
+
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+use serde_json::Value;
+
+use crate::{
+    config::Config,
+    source::{transform_source, transform_source_with_map},
+};
+
+/// Transform the async functions of `code` and return the printed output.
+#[napi]
+pub fn transform(code: String, options: Option<Value>) -> Result<String> {
+    let config = config_from_options(options)?;
+    transform_source(&code, config).map_err(|error| Error::new(Status::GenericFailure, error))
+}
+
+/// Transformed code with its V3 source map, as returned to JavaScript.
+#[napi(object, js_name = "TransformOutput")]
+pub struct JsTransformOutput {
+    pub code: String,
+    pub map: String,
+}
+
+/// Transform `code` like [`transform`] and return a source map of the
+/// output too, naming the source `filename`.
+#[napi]
+pub fn transform_with_map(
+    code: String,
+    filename: String,
+    options: Option<Value>,
+) -> Result<JsTransformOutput> {
+    let config = config_from_options(options)?;
+    let output = transform_source_with_map(&code, &filename, config)
+        .map_err(|error| Error::new(Status::GenericFailure, error))?;
+    Ok(JsTransformOutput {
+        code: output.code,
+        map: output.map,
+    })
+}
+
+/// Read the file at `path` and transform it like [`transform`]. Files
+/// filtered out by `include` / `exclude` / `skipTestFiles` are returned
+/// unchanged.
+#[napi]
+pub fn transform_file(path: String, options: Option<Value>) -> Result<String> {
+    let config = config_from_options(options)?;
+    let code = std::fs::read_to_string(&path).map_err(|error| {
+        Error::new(
+            Status::GenericFailure,
+            format!("failed to read {}: {}", path, error),
+        )
+    })?;
+    if !config.should_transform_file(Some(&path)) {
+        return Ok(code);
+    }
+    transform_source(&code, config).map_err(|error| Error::new(Status::GenericFailure, error))
+}
+
+/// Parse and validate the options object passed from JavaScript.
+fn config_from_options(options: Option<Value>) -> Result<Config> {
+    let json = options
+        .unwrap_or_else(|| Value::Object(Default::default()))
+        .to_string();
+    let env = std::env::var("NODE_ENV").ok();
+    Config::from_options_for_env(&json, env.as_deref()).map_err(|error| {
+        Error::new(
+            Status::InvalidArg,
+            format!(
+                "invalid options for swc-plugin-transform-async-to-ng-generator: {}",
+                error
+            ),
+        )
+    })
+}
@@ -0,0 +1,31 @@
+//! Comment pragmas that control the transform.
+//!
+//! - `/* @ng-async */` (or `// @ng-async`) before the first statement opts a
+//!   file in when `requirePragma` is set.
+//! - `/* @ng-async-ignore */` directly before an async function or method
+//!   leaves that function native. Functions nested inside it are still
+//!   transformed.
+
+use swc_core::common::{comments::Comments, BytePos};
+
+/// File-level opt-in pragma.
+pub const FILE_PRAGMA: &str = "@ng-async";
+
+/// Per-function opt-out pragma.
+pub const IGNORE_PRAGMA: &str = "@ng-async-ignore";
+
+/// Whether one of the comments leading `pos` contains `pragma` as a word.
+///
+/// JSDoc-style leading `*`s are ignored, so `/** @ng-async */` works too.
+pub fn has_leading_pragma(comments: &dyn Comments, pos: BytePos, pragma: &str) -> bool {
+    comments
+        .get_leading(pos)
+        .unwrap_or_default()
+        .iter()
+        .any(|comment| {
+            comment
+                .text
+                .split_whitespace()
+                .any(|word| word.trim_matches('*') == pragma)
+        })
+}
@@ -0,0 +1,172 @@
+//! Statistics about what the transform did to a program.
+//!
+//! After visiting a program, [`AsyncToNgGeneratorVisitor::report`] tells how
+//! many async functions of each kind were transformed, which ones were left
+//! native and why, or why the whole file was skipped:
+//!
+//! ```rust,ignore
+//! let mut visitor = AsyncToNgGeneratorVisitor::with_config(config);
+//! program.visit_mut_with(&mut visitor);
+//! let report = visitor.report();
+//! println!("{} transformed, {} left native", report.transformed(), report.skipped.len());
+//! ```
+//!
+//! [`AsyncToNgGeneratorVisitor::report`]: crate::AsyncToNgGeneratorVisitor::report
+
+use std::fmt;
+
+use swc_core::common::Span;
+
+/// What the transform did to the last program it visited.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransformReport {
+    /// Transformed `async function f() {}` declarations.
+    pub declarations: usize,
+    /// Transformed `async function () {}` expressions.
+    pub expressions: usize,
+    /// Transformed `async () => {}` arrows.
+    pub arrows: usize,
+    /// Transformed async class methods, private ones included.
+    pub class_methods: usize,
+    /// Transformed `{ async m() {} }` object methods.
+    pub object_methods: usize,
+    /// Async functions left native, in the order they were visited.
+    pub skipped: Vec<SkippedFunction>,
+    /// Why the whole program was left untransformed, if it was.
+    pub skipped_file: Option<FileSkipReason>,
+}
+
+impl TransformReport {
+    /// Number of transformed functions of every kind.
+    pub fn transformed(&self) -> usize {
+        self.declarations
+            + self.expressions
+            + self.arrows
+            + self.class_methods
+            + self.object_methods
+    }
+
+    /// Number of transformed functions of `kind`.
+    pub fn count(&self, kind: FunctionKind) -> usize {
+        match kind {
+            FunctionKind::Declaration => self.declarations,
+            FunctionKind::Expression => self.expressions,
+            FunctionKind::Arrow => self.arrows,
+            FunctionKind::ClassMethod => self.class_methods,
+            FunctionKind::ObjectMethod => self.object_methods,
+        }
+    }
+
+    /// Count a transformed function of `kind`.
+    pub(crate) fn transformed_fn(&mut self, kind: FunctionKind) {
+        let count = match kind {
+            FunctionKind::Declaration => &mut self.declarations,
+            FunctionKind::Expression => &mut self.expressions,
+            FunctionKind::Arrow => &mut self.arrows,
+            FunctionKind::ClassMethod => &mut self.class_methods,
+            FunctionKind::ObjectMethod => &mut self.object_methods,
+        };
+        *count += 1;
+    }
+
+    /// Record a function of `kind` at `span` left native for `reason`.
+    pub(crate) fn skipped_fn(&mut self, kind: FunctionKind, span: Span, reason: SkipReason) {
+        self.skipped.push(SkippedFunction { kind, span, reason });
+    }
+}
+
+/// Kinds of async functions the transform handles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FunctionKind {
+    Declaration,
+    Expression,
+    Arrow,
+    ClassMethod,
+    ObjectMethod,
+}
+
+/// An async function left native.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFunction {
+    pub kind: FunctionKind,
+    /// Span of the function in the original program.
+    pub span: Span,
+    pub reason: SkipReason,
+}
+
+/// Why an async function was left native.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    /// A `/* @ng-async-ignore */` comment opts it out.
+    Ignored,
+    /// It lies outside the classes selected by `onlyDecoratedClasses`.
+    OutsideDecoratedClasses,
+    /// It is neither a lifecycle hook nor inside one, and
+    /// `lifecycleHooksOnly` is set.
+    NotLifecycleHook,
+    /// It is an arrow and `transformArrowFunctions` is off.
+    ArrowsDisabled,
+    /// The `function_filter` of the config rejected it.
+    Filtered,
+    /// It is larger than `maxFunctionSize`.
+    TooLarge,
+    /// Its name matches one of `excludeFunctionNames`.
+    ExcludedName,
+    /// The transform does not support it (async generators, `super`,
+    /// `for await`, direct `eval` / `with`); a diagnostic says why.
+    Unsupported,
+}
+
+/// Why a whole program was left untransformed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileSkipReason {
+    /// It already contains the plugin's output (see `forceReprocess`).
+    AlreadyTransformed,
+    /// `requirePragma` is set and the file has no `/* @ng-async */`.
+    MissingPragma,
+    /// Every one of the `targets` runs async functions natively.
+    NativeTargets,
+    /// It nests deeper than `maxNestingDepth`.
+    TooDeeplyNested,
+    /// The transform hit an internal error and restored the program.
+    InternalError,
+}
+
+impl fmt::Display for FunctionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FunctionKind::Declaration => "async function",
+            FunctionKind::Expression => "async function expression",
+            FunctionKind::Arrow => "async arrow",
+            FunctionKind::ClassMethod => "async class method",
+            FunctionKind::ObjectMethod => "async object method",
+        })
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SkipReason::Ignored => "opted out with `@ng-async-ignore`",
+            SkipReason::OutsideDecoratedClasses => "outside the `onlyDecoratedClasses`",
+            SkipReason::NotLifecycleHook => "not in a lifecycle hook (`lifecycleHooksOnly`)",
+            SkipReason::ArrowsDisabled => "`transformArrowFunctions` is off",
+            SkipReason::Filtered => "rejected by the `function_filter`",
+            SkipReason::TooLarge => "larger than `maxFunctionSize`",
+            SkipReason::ExcludedName => "name matches `excludeFunctionNames`",
+            SkipReason::Unsupported => "not supported by the transform",
+        })
+    }
+}
+
+impl fmt::Display for FileSkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FileSkipReason::AlreadyTransformed => "it already contains the plugin's output",
+            FileSkipReason::MissingPragma => "`requirePragma` is set and it has no `@ng-async`",
+            FileSkipReason::NativeTargets => "every target supports async functions",
+            FileSkipReason::TooDeeplyNested => "it nests deeper than `maxNestingDepth`",
+            FileSkipReason::InternalError => "internal error",
+        })
+    }
+}
@@ -0,0 +1,230 @@
+//! Source-to-source API, behind the `source` cargo feature.
+//!
+//! [`transform_source`] parses, transforms and prints a file in one call,
+//! for CLI tools, codemods and tests that have text rather than an AST;
+//! [`transform_source_with_map`] also returns a source map of the output.
+//! [`runtime_source`] prints the matching runtime helper module.
+
+use std::{
+    fmt,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use swc_core::{
+    common::{
+        comments::{Comments, SingleThreadedComments},
+        errors::{Handler, HANDLER},
+        source_map::DefaultSourceMapGenConfig,
+        sync::Lrc,
+        BytePos, FileName, Globals, LineCol, Mark, SourceMap, GLOBALS,
+    },
+    ecma::{
+        ast::{EsVersion, Program},
+        codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter},
+        parser::{parse_file_as_program, EsSyntax, Syntax, TsSyntax},
+        transforms::base::{fixer::fixer, hygiene::hygiene, resolver},
+        visit::visit_mut_pass,
+    },
+};
+
+use crate::{config::Config, transforms::runtime_module, visitor::AsyncToNgGeneratorVisitor};
+
+/// Error of [`transform_source`].
+#[derive(Debug)]
+pub enum SourceError {
+    /// The source is not valid JavaScript (or TypeScript with `typescript`).
+    Parse(String),
+    /// `errorOnUnsupported` is set and the source contains code the plugin
+    /// leaves native; holds the rendered diagnostics.
+    Unsupported(String),
+    /// Printing the transformed program failed.
+    Emit(io::Error),
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Parse(message) => write!(f, "failed to parse the source: {}", message),
+            SourceError::Unsupported(diagnostics) => f.write_str(diagnostics),
+            SourceError::Emit(error) => write!(f, "failed to print the output: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+/// Output of [`transform_source_with_map`].
+#[derive(Debug, Clone)]
+pub struct TransformOutput {
+    /// The transformed source.
+    pub code: String,
+    /// Source map from `code` back to the original source, as V3 JSON.
+    pub map: String,
+}
+
+/// Transform the async functions of `source` and print the result.
+///
+/// The source is parsed as JavaScript with JSX, or as TypeScript when
+/// `typescript` is set, and goes through `resolver`, the transform,
+/// `hygiene` and `fixer`. Comments are kept. The file filters are not
+/// applied, since there is no filename; warnings are not reported.
+///
+/// ```rust,ignore
+/// let output = transform_source("async function f() { await g(); }", Config::default())?;
+/// ```
+pub fn transform_source(source: &str, config: Config) -> Result<String, SourceError> {
+    transform(source, FileName::Anon, config, false).map(|(code, _)| code)
+}
+
+/// Transform `source` like [`transform_source`] and build a standard V3
+/// source map of the output, for editors and debuggers of code that does
+/// not go through SWC's own source map pipeline.
+///
+/// `filename` is the name of the source in the map's `sources`.
+///
+/// ```rust,ignore
+/// let output = transform_source_with_map(&source, "src/app.js", config)?;
+/// std::fs::write("dist/app.js.map", output.map)?;
+/// ```
+pub fn transform_source_with_map(
+    source: &str,
+    filename: &str,
+    config: Config,
+) -> Result<TransformOutput, SourceError> {
+    let file_name = FileName::Real(PathBuf::from(filename));
+    let (code, map) = transform(source, file_name, config, true)?;
+    Ok(TransformOutput {
+        code,
+        map: map.unwrap_or_default(),
+    })
+}
+
+/// Parse, transform and print `source`, with a source map of the output
+/// when `with_map` is set.
+fn transform(
+    source: &str,
+    file_name: FileName,
+    config: Config,
+    with_map: bool,
+) -> Result<(String, Option<String>), SourceError> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let diagnostics = DiagnosticBuffer::default();
+    let handler = Handler::with_emitter_writer(Box::new(diagnostics.clone()), Some(cm.clone()));
+
+    GLOBALS.set(&Globals::new(), || {
+        HANDLER.set(&handler, || {
+            let fm = cm.new_source_file(Lrc::new(file_name), source.to_string());
+            let comments = SingleThreadedComments::default();
+            let typescript = config.typescript;
+            let syntax = if typescript {
+                Syntax::Typescript(TsSyntax::default())
+            } else {
+                Syntax::Es(EsSyntax {
+                    jsx: true,
+                    ..Default::default()
+                })
+            };
+            let program = parse_file_as_program(
+                &fm,
+                syntax,
+                EsVersion::latest(),
+                Some(&comments),
+                &mut vec![],
+            )
+            .map_err(|error| SourceError::Parse(error.kind().msg().into_owned()))?;
+
+            let unresolved_mark = Mark::new();
+            let top_level_mark = Mark::new();
+            let visitor =
+                AsyncToNgGeneratorVisitor::with_marks(config, unresolved_mark, top_level_mark)
+                    .with_comments(comments.clone());
+            let program = program.apply((
+                resolver(unresolved_mark, top_level_mark, typescript),
+                visit_mut_pass(visitor),
+                hygiene(),
+                fixer(Some(&comments)),
+            ));
+            if handler.has_errors() {
+                return Err(SourceError::Unsupported(diagnostics.text()));
+            }
+
+            if !with_map {
+                let code = print(&cm, &program, Some(&comments), None);
+                return code.map(|code| (code, None)).map_err(SourceError::Emit);
+            }
+            let mut mappings = vec![];
+            let code = print(&cm, &program, Some(&comments), Some(&mut mappings))
+                .map_err(SourceError::Emit)?;
+            let mut map = vec![];
+            cm.build_source_map(&mappings, None, DefaultSourceMapGenConfig)
+                .to_writer(&mut map)
+                .map_err(|error| SourceError::Emit(io::Error::other(error)))?;
+            Ok((code, Some(String::from_utf8_lossy(&map).into_owned())))
+        })
+    })
+}
+
+/// The runtime helper module of [`runtime_module`] as source text, e.g. to
+/// write it next to the bundle or serve it as a virtual module.
+///
+/// ```rust,ignore
+/// std::fs::write("ng-async.js", runtime_source(&config))?;
+/// ```
+pub fn runtime_source(config: &Config) -> String {
+    GLOBALS.set(&Globals::new(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let program = Program::Module(runtime_module(config));
+        print(&cm, &program, None, None).expect("printing to memory does not fail")
+    })
+}
+
+/// Print `program` as JavaScript, recording source map `mappings` if given.
+fn print(
+    cm: &Lrc<SourceMap>,
+    program: &Program,
+    comments: Option<&SingleThreadedComments>,
+    mappings: Option<&mut Vec<(BytePos, LineCol)>>,
+) -> io::Result<String> {
+    let mut output = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: CodegenConfig::default(),
+            cm: cm.clone(),
+            comments: comments.map(|comments| comments as &dyn Comments),
+            wr: JsWriter::new(cm.clone(), "\n", &mut output, mappings),
+        };
+        emitter.emit_program(program)?;
+    }
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+/// Shared buffer the diagnostics handler renders into.
+#[derive(Clone, Default)]
+struct DiagnosticBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl DiagnosticBuffer {
+    fn text(&self) -> String {
+        let buffer = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        String::from_utf8_lossy(&buffer).trim_end().to_string()
+    }
+}
+
+impl Write for DiagnosticBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut buffer = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+//! Target environments for the `targets` option.
+//!
+//! Targets are given the way Babel and SWC's `env` accept them, as a map of
+//! environment name to minimum version:
+//!
+//! ```json
+//! { "chrome": "58", "firefox": 52, "node": "8.9" }
+//! ```
+//!
+//! The versions are compared against the first release of each environment
+//! with native async functions. Unknown environments never count as native,
+//! so a typo keeps the transform running instead of silently disabling it.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// Minimum version of each environment by name.
+pub type Targets = BTreeMap<String, TargetVersion>;
+
+/// A target version, written as a string (`"7.6"`) or a number (`58`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TargetVersion {
+    Number(f64),
+    Text(String),
+}
+
+impl std::fmt::Display for TargetVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TargetVersion::Number(number) => write!(f, "{}", number),
+            TargetVersion::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+/// First version of each environment with native async functions.
+const NATIVE_ASYNC_FUNCTIONS: &[(&str, &str)] = &[
+    ("android", "55"),
+    ("chrome", "55"),
+    ("deno", "1"),
+    ("edge", "15"),
+    ("electron", "1.6"),
+    ("firefox", "52"),
+    ("ios", "11"),
+    ("node", "7.6"),
+    ("opera", "42"),
+    ("safari", "11"),
+    ("samsung", "6"),
+];
+
+/// Whether every target runs async functions natively. An empty map does
+/// not count, since it says nothing about the environments.
+pub fn support_async_functions(targets: &Targets) -> bool {
+    !targets.is_empty()
+        && targets.iter().all(|(name, version)| {
+            let Some((_, minimum)) = NATIVE_ASYNC_FUNCTIONS
+                .iter()
+                .find(|(env, _)| env.eq_ignore_ascii_case(name))
+            else {
+                return false;
+            };
+            match (parse_version(&version.to_string()), parse_version(minimum)) {
+                (Some(version), Some(minimum)) => version >= minimum,
+                _ => false,
+            }
+        })
+}
+
+/// Parse `"7.6.0"` into `[7, 6]`. Trailing zero components are dropped so
+/// `"7.6"` and `"7.6.0"` compare equal.
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let mut parts = version
+        .trim()
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    while parts.len() > 1 && parts.last() == Some(&0) {
+        parts.pop();
+    }
+    Some(parts)
+}
@@ -0,0 +1,144 @@
+//! Coverage ignore hints for generated code.
+//!
+//! With `coverageIgnoreHint` set, generated functions that contain no code
+//! of the original file are tagged for coverage tools:
+//!
+//! ```javascript
+//! var _ref = _ngAsyncToGenerator(function* () {
+//!     return yield fetch('/api');
+//! }, /* istanbul ignore next */ function () {
+//!     return Zone.current;
+//! });
+//! ```
+//!
+//! Functions that wrap original code (the generators, `function _foo()`
+//! helpers, capture IIFEs) are never tagged, since ignoring them would hide
+//! the code they run. Neither are wrappers that took over the span of the
+//! function they replace, as they are called exactly when it would be.
+
+use swc_core::{
+    common::{
+        comments::{Comment, CommentKind, Comments},
+        Span, DUMMY_SP,
+    },
+    ecma::{
+        ast::*,
+        visit::{noop_visit_mut_type, noop_visit_type, Visit, VisitMut, VisitMutWith, VisitWith},
+    },
+};
+
+/// Put a `/* <hint> */` comment before every generated function in
+/// `program` that contains no original code.
+///
+/// A first pass finds those functions bottom-up, so each node is visited
+/// once however deeply generated functions nest; a second one tags them.
+pub fn tag_generated_functions(program: &mut Program, comments: &dyn Comments, hint: &str) {
+    let mut finder = GeneratedFunctionFinder::default();
+    program.visit_with(&mut finder);
+    program.visit_mut_with(&mut CoverageHintVisitor {
+        comments,
+        hint,
+        tagged: &finder.tagged,
+        count: 0,
+    });
+}
+
+/// Whether a span came from the original file rather than the transform.
+pub(super) fn is_original(span: Span) -> bool {
+    !span.is_dummy() && !span.lo.is_reserved_for_comments()
+}
+
+/// Visitor that numbers functions and arrows in visiting order and collects
+/// the outermost generated ones without original code.
+#[derive(Default)]
+struct GeneratedFunctionFinder {
+    /// Number of functions and arrows visited so far.
+    count: usize,
+    /// Whether the innermost function being visited contains original code
+    /// so far.
+    found_original: bool,
+    /// Numbers of the functions to tag, in ascending order.
+    tagged: Vec<usize>,
+}
+
+impl GeneratedFunctionFinder {
+    fn visit_fn_like<N: VisitWith<Self>>(&mut self, node: &N, span: Span) {
+        let index = self.count;
+        self.count += 1;
+        let outer_found = std::mem::take(&mut self.found_original);
+        let first_nested = self.tagged.len();
+
+        node.visit_children_with(self);
+
+        if !is_original(span) && !self.found_original {
+            // Functions nested in a tagged one need no hint of their own
+            self.tagged.truncate(first_nested);
+            self.tagged.push(index);
+        }
+        self.found_original |= outer_found;
+    }
+}
+
+impl Visit for GeneratedFunctionFinder {
+    noop_visit_type!();
+
+    fn visit_span(&mut self, span: &Span) {
+        if is_original(*span) {
+            self.found_original = true;
+        }
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        self.visit_fn_like(function, function.span);
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        self.visit_fn_like(arrow, arrow.span);
+    }
+}
+
+/// Visitor that tags the functions found by [`GeneratedFunctionFinder`]
+/// with the hint comment.
+struct CoverageHintVisitor<'a> {
+    comments: &'a dyn Comments,
+    hint: &'a str,
+    /// Numbers of the functions to tag, in ascending order.
+    tagged: &'a [usize],
+    /// Number of functions and arrows visited so far.
+    count: usize,
+}
+
+impl CoverageHintVisitor<'_> {
+    /// Give the next function a span that can carry comments, and attach the
+    /// hint to it, if it is one to tag.
+    fn tag(&mut self, span: &mut Span) {
+        let index = self.count;
+        self.count += 1;
+        if self.tagged.binary_search(&index).is_err() {
+            return;
+        }
+        *span = Span::dummy_with_cmt();
+        self.comments.add_leading(
+            span.lo,
+            Comment {
+                kind: CommentKind::Block,
+                span: DUMMY_SP,
+                text: format!(" {} ", self.hint).into(),
+            },
+        );
+    }
+}
+
+impl VisitMut for CoverageHintVisitor<'_> {
+    noop_visit_mut_type!();
+
+    fn visit_mut_function(&mut self, function: &mut Function) {
+        self.tag(&mut function.span);
+        function.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        self.tag(&mut arrow.span);
+        arrow.visit_mut_children_with(self);
+    }
+}
@@ -9,7 +9,7 @@
 //!
 //! Into:
 //! ```javascript
-//! function foo() {
+//! function foo(_x, _x2) {
 //!     return _foo.apply(this, arguments);
 //! }
 //! function _foo() {
@@ -22,67 +22,123 @@
 
 use swc_core::ecma::ast::*;
 
+use crate::config::Config;
+use crate::report::FunctionKind;
+
+use super::helpers::{
+    arity_placeholders, bails_on_unsafe_scope, create_generator_function, directive_prologue,
+    forwards_arguments, generated_decl_kind, generator_fn_expr, handles_awaitless,
+    placeholders_cover, styled_delegate, takes_arguments, wrap_generator, GeneratorBodyVisitor,
+};
+use super::unsupported::{bails_on_async_generator, bails_on_for_await};
 use crate::ast_builders::{
-    apply_call, assign_expr, block, expr_stmt, fn_decl, generator_fn_expr, ident,
-    ng_async_wrapper, return_stmt,
+    block, delegate_call, return_stmt, self_replacing_fn_decl, var_decl_with_kind, Hygiene,
 };
-use super::helpers::{create_generator_function, HasAwaitVisitor};
 
 /// Transform an async function declaration.
 ///
-/// Returns the helper function declaration that should be hoisted.
-/// If the function has no await expressions, simply removes the async keyword
-/// and returns None (no transformation needed).
-pub fn transform_fn_decl(decl: &mut FnDecl) -> Option<FnDecl> {
-    if !decl.function.is_async {
+/// `helper_name` is the collision-free name chosen by the visitor for the
+/// hoisted helper (usually `_foo`); it is minted under `hygiene` like every
+/// other generated binding.
+///
+/// Returns the helper declaration that should be hoisted: a function that
+/// creates the generator wrapper on its first call, or with `eagerHelpers`
+/// a variable holding it right away.
+/// Without await expressions and with `awaitlessFunctions: "drop"`, simply
+/// removes the async keyword and returns None.
+///
+/// Ambient declarations (`declare function`) and overload signatures have no
+/// body and are left untouched, so they stay adjacent to the implementation
+/// they describe.
+pub fn transform_fn_decl(
+    decl: &mut FnDecl,
+    helper_name: &str,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Option<Stmt> {
+    if !decl.function.is_async || decl.declare || decl.function.body.is_none() {
+        return None;
+    }
+
+    if bails_on_async_generator(&decl.function, config) {
         return None;
     }
 
     let func = &mut decl.function;
 
-    // Check if the function body contains await
-    // If not, just remove async keyword - no transformation needed
-    if let Some(body) = &func.body {
-        if !HasAwaitVisitor::check(body) {
+    let facts = GeneratorBodyVisitor::inspect(&func.body);
+
+    // Awaitless functions may only lose the async keyword
+    if let Some(body) = &mut func.body {
+        if handles_awaitless(body, &facts, config, hygiene) {
             func.is_async = false;
             return None;
         }
+        if bails_on_for_await(&facts, config) || bails_on_unsafe_scope(&facts, config) {
+            return None;
+        }
     }
 
-    let func_name = decl.ident.sym.to_string();
-    let helper_name = format!("_{}", func_name);
-
     // Get the body
     let body = func.body.take()?;
+    let prologue = directive_prologue(&body);
+    let forward = forwards_arguments(takes_arguments(&func.params), &facts, config);
+    let cover = placeholders_cover(func.params.iter().map(|p| &p.pat))
+        && !GeneratorBodyVisitor::inspect(&func.params).arguments
+        && !facts.arguments;
+
+    // Placeholder params keep `foo.length` intact on the delegating wrapper
+    let mut placeholders = arity_placeholders(func.params.iter().map(|p| &p.pat), config, hygiene);
 
     // Create generator function with original params
     let params: Vec<Param> = func.params.drain(..).collect();
-    let (generator_func, _) = create_generator_function(params, body, false);
+    let (mut generator_func, _) = create_generator_function(params, body, None, config, hygiene);
+    // The generator takes over the params and body, so it keeps the scope
+    // context `resolver` gave the original function
+    generator_func.ctxt = func.ctxt;
+    if config.typescript {
+        // Param annotations may reference the function's type params
+        generator_func.type_params = func.type_params.clone();
+    }
 
-    // Create the helper function:
-    // function _foo() {
-    //     _foo = _ngAsyncToGenerator(function* () { ... });
-    //     return _foo.apply(this, arguments);
-    // }
-    let generator_expr = generator_fn_expr(generator_func.params, generator_func.body.unwrap());
-    let helper_fn = fn_decl(
-        &helper_name,
-        block(vec![
-            // _foo = _ngAsyncToGenerator(function* () { ... })
-            expr_stmt(assign_expr(&helper_name, ng_async_wrapper(generator_expr))),
-            // return _foo.apply(this, arguments)
-            return_stmt(apply_call(Expr::Ident(ident(&helper_name)))),
-        ]),
+    let helper = hygiene.private_ident(helper_name);
+    let generator_expr = generator_fn_expr(generator_func, Some(&decl.ident.sym), config, hygiene);
+    let wrapped = wrap_generator(
+        generator_expr,
+        FunctionKind::Declaration,
+        Some(&decl.ident.sym),
+        config,
+        hygiene,
     );
+    let helper_decl = if config.eager_helpers {
+        // var _foo = _ngAsyncToGenerator(function* () { ... });
+        var_decl_with_kind(generated_decl_kind(config, false), helper.clone(), wrapped)
+    } else {
+        // function _foo() {
+        //     _foo = _ngAsyncToGenerator(function* () { ... });
+        //     return _foo.apply(this, arguments);
+        // }
+        let delegate = delegate_call(Expr::Ident(helper.clone()), forward, hygiene);
+        self_replacing_fn_decl(helper.clone(), wrapped, delegate)
+    };
 
     // Modify the original function to delegate to helper:
-    // function foo() { return _foo.apply(this, arguments); }
+    // function foo(_x) { return _foo.apply(this, arguments); }
+    let delegate = styled_delegate(
+        Expr::Ident(helper),
+        None,
+        &mut placeholders,
+        forward,
+        cover,
+        config,
+        hygiene,
+    );
     func.is_async = false;
     func.is_generator = false;
-    func.params = vec![];
-    func.body = Some(block(vec![return_stmt(apply_call(Expr::Ident(ident(
-        &helper_name,
-    ))))]));
+    func.params = placeholders;
+    let mut wrapper_stmts = prologue;
+    wrapper_stmts.push(return_stmt(delegate));
+    func.body = Some(block(wrapper_stmts));
 
-    Some(helper_fn)
+    Some(helper_decl)
 }
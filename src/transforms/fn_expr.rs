@@ -10,85 +10,443 @@
 //!
 //! Into (without `this`):
 //! ```javascript
-//! const fetchData = (function() {
-//!     var _ref = _ngAsyncToGenerator(function* (url) {
-//!         return yield fetch(url);
-//!     });
-//!     return function() {
-//!         return _ref.apply(this, arguments);
-//!     };
-//! })();
+//! var _ref = _ngAsyncToGenerator(function* (url) {
+//!     return yield fetch(url);
+//! });
+//! const fetchData = function fetchData(_x) {
+//!     return _ref.apply(this, arguments);
+//! };
 //! ```
 //!
 //! Or (with `this`):
 //! ```javascript
+//! var _this = this;
+//! var _ref = _ngAsyncToGenerator(function* (url) {
+//!     return yield _this.fetch(url);
+//! });
+//! const fetchData = function fetchData(_x) {
+//!     return _ref.apply(_this, arguments);
+//! };
+//! ```
+//!
+//! An anonymous arrow without params that reads neither `this` nor
+//! `arguments`, typically a callback, needs no wrapper at all:
+//! ```javascript
+//! tasks.push(async () => await step());
+//! // becomes
+//! tasks.push(_ngAsyncToGenerator(function* () {
+//!     return yield step();
+//! }));
+//! ```
+//!
+//! The `_ref` (and `_this` / `_arguments`) declarations are hoisted right
+//! before the statement containing the function. Where that statement would
+//! see different bindings than the function (inside loops, nested functions
+//! or class bodies), they stay in an IIFE next to the wrapper instead:
+//! ```javascript
 //! const fetchData = (function(_this) {
-//!     var _ref = _ngAsyncToGenerator(function* (url) {
-//!         return yield _this.fetch(url);
-//!     });
-//!     return function() {
+//!     var _ref = _ngAsyncToGenerator(function* (url) { ... });
+//!     return function(_x) {
 //!         return _ref.apply(_this, arguments);
 //!     };
 //! })(this);
 //! ```
 //!
+//! With `sharedArrowWrapper` the wrapper comes from one helper per file
+//! instead: `const fetchData = _ngWrap(_ref);`.
+//!
 //! ## Function Expression
 //! Similar transformation for `async function() { ... }` expressions.
+//!
+//! A named expression whose body refers to its own name must see the
+//! wrapper under that name, as it saw the async function. The wrapper is
+//! then declared in the IIFE next to the generator, never hoisted:
+//! ```javascript
+//! const retry = (function() {
+//!     var _ref = _ngAsyncToGenerator(function* (n) { ... yield attempt(n - 1); });
+//!     function attempt(_x) {
+//!         return _ref.apply(this, arguments);
+//!     }
+//!     return attempt;
+//! })();
+//! ```
+//!
+//! ## Lazy References
+//! With `lazyRefs`, `_ref` starts out as a stub that creates the generator
+//! wrapper on the first call and replaces itself, the same way the helpers
+//! of function declarations do:
+//! ```javascript
+//! var _ref = function() {
+//!     _ref = _ngAsyncToGenerator(function* (url) { ... });
+//!     return _ref.apply(this, arguments);
+//! };
+//! ```
 
 use swc_core::{
     common::{util::take::Take, SyntaxContext, DUMMY_SP},
-    ecma::ast::*,
+    ecma::{ast::*, atoms::atom},
 };
 
+use super::helpers::{
+    arity_placeholders, bails_on_unsafe_scope, create_generator_function, directive_prologue,
+    forwards_arguments, generated_decl_kind, generator_fn_expr, handles_awaitless,
+    handles_awaitless_arrow, placeholders_cover, styled_delegate, takes_arguments, wrap_generator,
+    BodyFacts, CaptureIdents, GeneratorBodyVisitor, LexicalCaptures, SelfReferenceVisitor,
+};
+use super::tsc::{transform_awaiter_arrow, transform_awaiter_function};
+use super::unsupported::{bails_on_async_generator, bails_on_for_await, bails_on_super};
 use crate::ast_builders::{
-    apply_call, apply_call_with_captured_this, block, generator_fn_expr, ident, iife,
-    iife_with_this_param, ng_async_wrapper, regular_fn_expr, return_stmt, var_decl,
+    arrow_delegate, block, delegate_call, fn_expr as wrapper_fn_expr, iife, iife_with_captures,
+    return_stmt, self_replacing_fn_expr, shared_wrapper_call, var_decl_with_kind,
+    with_ts_signature, Hygiene,
 };
-use super::helpers::{create_generator_function, HasAwaitVisitor, HasThisVisitor};
+use crate::config::{Compat, Config};
+use crate::report::FunctionKind;
+
+/// Result of transforming an async arrow or function expression.
+pub enum TransformedFn {
+    /// Self-contained replacement: an IIFE declaring `_ref` and returning
+    /// the wrapper.
+    Inline(Expr),
+    /// Wrapper calling a `_ref` that must be declared right before the
+    /// statement containing the function.
+    Hoisted {
+        /// Replacement for the function
+        wrapper: Expr,
+        /// `var _ref = _ngAsyncToGenerator(function* () { ... });`
+        ref_decl: Stmt,
+        /// `this` / `arguments` the generator reads through the captures,
+        /// which must be declared ahead of the statement too
+        captures: LexicalCaptures,
+    },
+    /// The function was rewritten where it is (`compat: "tsc"`).
+    InPlace,
+}
+
+impl TransformedFn {
+    /// Short description of the form, for debug output.
+    pub fn strategy(&self) -> &'static str {
+        match self {
+            TransformedFn::Inline(_) => "inline wrapper",
+            TransformedFn::Hoisted { .. } => "hoisted ref",
+            TransformedFn::InPlace => "tslib `__awaiter`",
+        }
+    }
+}
 
 /// Transform an async arrow function expression.
 ///
 /// # Arguments
 /// * `arrow` - The arrow function to transform
 /// * `ref_name` - The unique reference name for the wrapper (e.g., "_ref", "_ref1")
+/// * `name` - Name inferred from the binding the arrow is assigned to, given to
+///   the returned wrapper so `.name` matches the untransformed code
+/// * `capture` - Identifiers capturing `this` / `arguments` at the arrow's
+///   nesting level
+/// * `hoist` - Whether `_ref` may be declared ahead of the enclosing
+///   statement instead of in an IIFE
+/// * `hygiene` - Contexts for the generated bindings
 ///
 /// # Returns
-/// The transformed IIFE expression, or None if transformation not needed
-/// (e.g., not async or no await expressions)
-pub fn transform_arrow_fn(arrow: &mut ArrowExpr, ref_name: &str) -> Option<Expr> {
-    if !arrow.is_async {
+/// The transformed function, or None if transformation not needed
+/// (e.g., not async, or awaitless with `awaitlessFunctions: "drop"`)
+pub fn transform_arrow_fn(
+    arrow: &mut ArrowExpr,
+    ref_name: &str,
+    name: Option<Ident>,
+    capture: &CaptureIdents,
+    hoist: bool,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Option<TransformedFn> {
+    if config.compat == Compat::Tsc {
+        return transform_awaiter_arrow(arrow, config, hygiene).then_some(TransformedFn::InPlace);
+    }
+    let facts = GeneratorBodyVisitor::inspect(&*arrow.body);
+    if !is_transformable_arrow(arrow, &facts, config, hygiene) {
         return None;
     }
 
-    // Check if body contains await - if not, just remove async keyword
-    let has_await = match &*arrow.body {
-        BlockStmtOrExpr::BlockStmt(b) => HasAwaitVisitor::check(b),
-        BlockStmtOrExpr::Expr(e) => matches!(**e, Expr::Await(_)),
+    let body = take_arrow_body(&mut arrow.body);
+    let prologue = directive_prologue(&body);
+
+    // Placeholder params keep the returned wrapper's `length` intact
+    let mut placeholders = arity_placeholders(arrow.params.iter(), config, hygiene);
+    let cover = placeholders_cover(&arrow.params);
+
+    // `arguments` in the body is the outer one, read through `_arguments`,
+    // so only the arrow's own params may need forwarding
+    let forward = !config.loose || !arrow.params.is_empty();
+
+    let params = take_arrow_params(arrow);
+
+    // Create the generator function
+    // Arrow functions have lexical `this`/`arguments`, so references are
+    // replaced with `_this`/`_arguments` captured at the definition site
+    let (mut generator_func, captures) =
+        create_generator_function(params, body, Some(capture), config, hygiene);
+
+    // TypeScript mode keeps the signature: the generator needs the type
+    // params for its param annotations, the wrapper takes the return type
+    let (type_params, return_type) = if config.typescript {
+        (arrow.type_params.take(), arrow.return_type.take())
+    } else {
+        (None, None)
     };
+    generator_func.ctxt = arrow.ctxt;
+    generator_func.type_params = type_params.clone();
+    let generator_name = name.as_ref().map(|name| &*name.sym);
+    let generator_expr = generator_fn_expr(generator_func, generator_name, config, hygiene);
+    let wrapped = wrap_generator(
+        generator_expr,
+        FunctionKind::Arrow,
+        generator_name,
+        config,
+        hygiene,
+    );
 
-    if !has_await {
-        arrow.is_async = false;
-        return None;
+    let ref_ident = hygiene.private_ident(ref_name);
+    let ref_decl = ref_decl(ref_ident.clone(), wrapped, forward, config, hygiene);
+
+    // _ngWrap(_ref, _this): the generator sees no other `this` than the
+    // captured one, so the shared helper's wrapper can pass that on
+    if config.shared_arrow_wrapper {
+        let this = captures.this.then(|| capture.this.clone());
+        let wrapper = shared_wrapper_call(ref_ident, this, hygiene);
+        if hoist {
+            return Some(TransformedFn::Hoisted {
+                wrapper,
+                ref_decl,
+                captures,
+            });
+        }
+        let iife_stmts = vec![ref_decl, return_stmt(wrapper)];
+        return Some(TransformedFn::Inline(arrow_iife(
+            iife_stmts, captures, capture, hygiene,
+        )));
     }
 
-    // Extract body
-    let body = match &mut *arrow.body {
-        BlockStmtOrExpr::BlockStmt(b) => b.take(),
-        BlockStmtOrExpr::Expr(e) => {
-            // Convert expression body to block with return
-            BlockStmt {
-                span: DUMMY_SP,
-                ctxt: SyntaxContext::empty(),
-                stmts: vec![return_stmt(*e.take())],
+    // The generator reads `this` through `_this`, so in esnext mode the
+    // wrapper can be an arrow: (_x, ...args) => _ref(_x, ...args)
+    // Arrows cannot have a "use strict" prologue next to a rest param.
+    if config.esnext_output && prologue.is_empty() {
+        let wrapper = with_ts_signature(
+            arrow_delegate(Expr::Ident(ref_ident), placeholders, forward, hygiene),
+            type_params,
+            return_type,
+        );
+        // In place of the original arrow, the wrapper gets its name inferred
+        if hoist {
+            return Some(TransformedFn::Hoisted {
+                wrapper,
+                ref_decl,
+                captures,
+            });
+        }
+        let mut iife_stmts = vec![ref_decl];
+        match name {
+            // const name = (...) => ...; return name;
+            Some(name) => {
+                let binding = hygiene.private_ident(name.sym.clone());
+                iife_stmts.push(var_decl_with_kind(
+                    VarDeclKind::Const,
+                    binding.clone(),
+                    wrapper,
+                ));
+                iife_stmts.push(return_stmt(Expr::Ident(binding)));
             }
+            None => iife_stmts.push(return_stmt(wrapper)),
         }
-    };
+        return Some(TransformedFn::Inline(arrow_iife(
+            iife_stmts, captures, capture, hygiene,
+        )));
+    }
+
+    // function() { "use strict"; return _ref.apply(this, arguments); }
+    let delegate = styled_delegate(
+        Expr::Ident(ref_ident.clone()),
+        captures.this.then(|| capture.this.clone()),
+        &mut placeholders,
+        forward,
+        cover,
+        config,
+        hygiene,
+    );
+    let mut wrapper_stmts = prologue;
+    wrapper_stmts.push(return_stmt(delegate));
+    let wrapper = with_ts_signature(
+        wrapper_fn_expr(name, placeholders, block(wrapper_stmts), false),
+        type_params,
+        return_type,
+    );
+    if hoist {
+        return Some(TransformedFn::Hoisted {
+            wrapper,
+            ref_decl,
+            captures,
+        });
+    }
+
+    let iife_stmts = vec![ref_decl, return_stmt(wrapper)];
+    Some(TransformedFn::Inline(arrow_iife(
+        iife_stmts, captures, capture, hygiene,
+    )))
+}
+
+/// Facts of the body of an async arrow that can be replaced by its wrapped
+/// generator alone, or `None` if it can't: with no params to keep `length`
+/// for and no `this` or `arguments` to capture,
+/// `_ngAsyncToGenerator(function* () { ... })` behaves the same.
+///
+/// Arrows that get an inferred name must keep their wrapper, which carries
+/// the name; the caller checks that.
+pub fn trivial_arrow_facts(arrow: &ArrowExpr, config: &Config) -> Option<BodyFacts> {
+    let trivial = config.compat != Compat::Tsc
+        && arrow.params.is_empty()
+        // TypeScript signatures stay on the wrapper
+        && arrow.type_params.is_none()
+        && arrow.return_type.is_none();
+    if !trivial {
+        return None;
+    }
+    let facts = GeneratorBodyVisitor::inspect(&*arrow.body);
+    (!facts.this && !facts.arguments).then_some(facts)
+}
+
+/// Transform an async arrow with [`trivial_arrow_facts`] into its wrapped
+/// generator: `_ngAsyncToGenerator(function* () { ... })`.
+pub fn transform_trivial_arrow(
+    arrow: &mut ArrowExpr,
+    facts: &BodyFacts,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Option<TransformedFn> {
+    if !is_transformable_arrow(arrow, facts, config, hygiene) {
+        return None;
+    }
+
+    let body = take_arrow_body(&mut arrow.body);
+    let (mut generator_func, _) = create_generator_function(vec![], body, None, config, hygiene);
+    generator_func.ctxt = arrow.ctxt;
+    let generator_expr = generator_fn_expr(generator_func, None, config, hygiene);
+    let wrapped = wrap_generator(generator_expr, FunctionKind::Arrow, None, config, hygiene);
+    Some(TransformedFn::Inline(wrapped))
+}
+
+/// Transform an immediately invoked async arrow, `(async () => { ... })()`,
+/// into the wrapped generator the call can invoke directly:
+/// `_ngAsyncToGenerator(function* () { ... })()`.
+///
+/// The generator reads the arrow's `this` / `arguments` through `capture`.
+/// With `hoist`, the captures it used are returned for the caller to declare
+/// before the enclosing statement; otherwise they are passed into an IIFE
+/// around the wrapped generator, and nothing is left to declare.
+pub fn transform_immediate_arrow(
+    arrow: &mut ArrowExpr,
+    capture: &CaptureIdents,
+    hoist: bool,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Option<(Expr, LexicalCaptures)> {
+    let facts = GeneratorBodyVisitor::inspect(&*arrow.body);
+    if !is_transformable_arrow(arrow, &facts, config, hygiene) {
+        return None;
+    }
+
+    let body = take_arrow_body(&mut arrow.body);
+    let params = take_arrow_params(arrow);
+    let (mut generator_func, captures) =
+        create_generator_function(params, body, Some(capture), config, hygiene);
+    generator_func.ctxt = arrow.ctxt;
+    let generator_expr = generator_fn_expr(generator_func, None, config, hygiene);
+    let wrapped = wrap_generator(generator_expr, FunctionKind::Arrow, None, config, hygiene);
+
+    if hoist || (!captures.this && !captures.arguments) {
+        return Some((wrapped, captures));
+    }
+    // (function(_this) { return _ngAsyncToGenerator(function* () { ... }); })(this)
+    let wrapped = arrow_iife(vec![return_stmt(wrapped)], captures, capture, hygiene);
+    Some((wrapped, LexicalCaptures::default()))
+}
+
+/// Transform an immediately invoked anonymous async function expression,
+/// `(async function () { ... })()`, like [`transform_immediate_arrow`].
+/// The call passes on its `this` and arguments unchanged, so nothing needs
+/// to be captured.
+pub fn transform_immediate_fn_expr(
+    fn_expr: &mut FnExpr,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Option<Expr> {
+    let func = &mut fn_expr.function;
+    if !func.is_async || bails_on_async_generator(func, config) {
+        return None;
+    }
+
+    let body = func.body.as_mut()?;
+    let facts = GeneratorBodyVisitor::inspect(&*body);
+    if handles_awaitless(body, &facts, config, hygiene) {
+        func.is_async = false;
+        return None;
+    }
+    if bails_on_for_await(&facts, config) || bails_on_unsafe_scope(&facts, config) {
+        return None;
+    }
+
+    let body = body.take();
+    let params: Vec<Param> = func.params.drain(..).collect();
+    let (mut generator_func, _) = create_generator_function(params, body, None, config, hygiene);
+    generator_func.ctxt = func.ctxt;
+    let generator_expr = generator_fn_expr(generator_func, None, config, hygiene);
+    Some(wrap_generator(
+        generator_expr,
+        FunctionKind::Expression,
+        None,
+        config,
+        hygiene,
+    ))
+}
+
+/// Whether an arrow is async and should be transformed, given the `facts`
+/// of its body. Awaitless arrows handled by `awaitlessFunctions` lose their
+/// `async` keyword instead.
+pub fn is_transformable_arrow(
+    arrow: &mut ArrowExpr,
+    facts: &BodyFacts,
+    config: &Config,
+    hygiene: Hygiene,
+) -> bool {
+    if !arrow.is_async {
+        return false;
+    }
+
+    // Awaitless arrows may only lose the async keyword
+    if handles_awaitless_arrow(&mut arrow.body, facts, config, hygiene) {
+        arrow.is_async = false;
+        return false;
+    }
+
+    !bails_on_for_await(facts, config)
+        && !bails_on_unsafe_scope(facts, config)
+        && !bails_on_super(&arrow.body, config)
+}
 
-    // Check if body uses `this` - arrow functions have lexical this binding
-    let uses_this = HasThisVisitor::check(&body);
+/// Take the body of an arrow as a block, turning an expression body into
+/// `{ return expr; }`.
+pub fn take_arrow_body(body: &mut BlockStmtOrExpr) -> BlockStmt {
+    match body {
+        BlockStmtOrExpr::BlockStmt(b) => b.take(),
+        BlockStmtOrExpr::Expr(e) => BlockStmt {
+            span: DUMMY_SP,
+            ctxt: SyntaxContext::empty(),
+            stmts: vec![return_stmt(*e.take())],
+        },
+    }
+}
 
-    // Convert arrow params to function params
-    let params: Vec<Param> = arrow
+/// Take the params of an arrow as function params.
+fn take_arrow_params(arrow: &mut ArrowExpr) -> Vec<Param> {
+    arrow
         .params
         .drain(..)
         .map(|pat| Param {
@@ -96,40 +454,46 @@ pub fn transform_arrow_fn(arrow: &mut ArrowExpr, ref_name: &str) -> Option<Expr>
             decorators: vec![],
             pat,
         })
-        .collect();
+        .collect()
+}
 
-    // Create the generator function
-    // If arrow uses `this`, we need to capture it and replace `this` with `_this`
-    let (generator_func, _) = create_generator_function(params, body, uses_this);
-    let generator_expr = generator_fn_expr(generator_func.params, generator_func.body.unwrap());
-
-    // Build the IIFE based on whether `this` is used
-    if uses_this {
-        // Arrow function uses `this` - capture it via IIFE parameter:
-        // (function(_this) {
+/// Wrap the statements built for an arrow in an IIFE, passing in the
+/// `this` / `arguments` the generator captured.
+fn arrow_iife(
+    iife_stmts: Vec<Stmt>,
+    captures: LexicalCaptures,
+    capture: &CaptureIdents,
+    hygiene: Hygiene,
+) -> Expr {
+    let mut lexical = Vec::new();
+    if captures.this {
+        lexical.push((
+            capture.this.clone(),
+            Expr::This(ThisExpr { span: DUMMY_SP }),
+        ));
+    }
+    if captures.arguments {
+        lexical.push((
+            capture.arguments.clone(),
+            Expr::Ident(hygiene.global_ident(atom!("arguments"))),
+        ));
+    }
+
+    // Build the IIFE based on whether `this`/`arguments` are used
+    if !lexical.is_empty() {
+        // Arrow function uses lexical bindings - capture them via IIFE parameters:
+        // (function(_this, _arguments) {
         //     var _ref = _ngAsyncToGenerator(function* () { ... uses _this ... });
         //     return function() { return _ref.apply(_this, arguments); };
-        // })(this)
-        Some(iife_with_this_param(vec![
-            var_decl(ref_name, ng_async_wrapper(generator_expr)),
-            return_stmt(regular_fn_expr(
-                None,
-                block(vec![return_stmt(apply_call_with_captured_this(Expr::Ident(ident(ref_name))))]),
-            )),
-        ]))
+        // })(this, arguments)
+        iife_with_captures(lexical, iife_stmts)
     } else {
         // Arrow function doesn't use `this` - standard IIFE:
         // (function() {
         //     var _ref = _ngAsyncToGenerator(function* () { ... });
         //     return function() { return _ref.apply(this, arguments); };
         // })()
-        Some(iife(vec![
-            var_decl(ref_name, ng_async_wrapper(generator_expr)),
-            return_stmt(regular_fn_expr(
-                None,
-                block(vec![return_stmt(apply_call(Expr::Ident(ident(ref_name))))]),
-            )),
-        ]))
+        iife(iife_stmts)
     }
 }
 
@@ -138,41 +502,152 @@ pub fn transform_arrow_fn(arrow: &mut ArrowExpr, ref_name: &str) -> Option<Expr>
 /// # Arguments
 /// * `fn_expr` - The function expression to transform
 /// * `ref_name` - The unique reference name for the wrapper
+/// * `name` - Name inferred from the assignment target, used when the
+///   expression itself is anonymous
+/// * `hoist` - Whether `_ref` may be declared ahead of the enclosing
+///   statement instead of in an IIFE; ignored when the function refers to
+///   its own name
+/// * `hygiene` - Contexts for the generated bindings
 ///
 /// # Returns
-/// The transformed IIFE expression, or None if transformation not needed
-/// (e.g., not async or no await expressions)
-pub fn transform_fn_expr(fn_expr: &mut FnExpr, ref_name: &str) -> Option<Expr> {
+/// The transformed function, or None if transformation not needed
+/// (e.g., not async, or awaitless with `awaitlessFunctions: "drop"`)
+pub fn transform_fn_expr(
+    fn_expr: &mut FnExpr,
+    ref_name: &str,
+    name: Option<Ident>,
+    hoist: bool,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Option<TransformedFn> {
+    if config.compat == Compat::Tsc {
+        let transformed = transform_awaiter_function(&mut fn_expr.function, config, hygiene);
+        return transformed.then_some(TransformedFn::InPlace);
+    }
     let func = &mut fn_expr.function;
 
-    if !func.is_async {
+    if !func.is_async || bails_on_async_generator(func, config) {
         return None;
     }
 
-    // Check if body contains await - if not, just remove async keyword
-    if let Some(body) = &func.body {
-        if !HasAwaitVisitor::check(body) {
+    let facts = GeneratorBodyVisitor::inspect(&func.body);
+
+    // Awaitless functions may only lose the async keyword
+    if let Some(body) = &mut func.body {
+        if handles_awaitless(body, &facts, config, hygiene) {
             func.is_async = false;
             return None;
         }
+        if bails_on_for_await(&facts, config) || bails_on_unsafe_scope(&facts, config) {
+            return None;
+        }
     }
 
     let body = func.body.take()?;
-    let original_ident = fn_expr.ident.take();
+    let prologue = directive_prologue(&body);
+    let forward = forwards_arguments(takes_arguments(&func.params), &facts, config);
+    let cover = placeholders_cover(func.params.iter().map(|p| &p.pat))
+        && !GeneratorBodyVisitor::inspect(&func.params).arguments
+        && !facts.arguments;
+    // The generator moves out of the function, out of the scope of its name
+    let self_reference = fn_expr.ident.as_ref().is_some_and(|ident| {
+        SelfReferenceVisitor::check(&func.params, ident)
+            || SelfReferenceVisitor::check(&body, ident)
+    });
+    let original_ident = fn_expr.ident.take().or(name);
+    let mut placeholders = arity_placeholders(func.params.iter().map(|p| &p.pat), config, hygiene);
 
     // Collect params
     let params: Vec<Param> = func.params.drain(..).collect();
-    let (generator_func, _) = create_generator_function(params, body, false);
-    let generator_expr = generator_fn_expr(generator_func.params, generator_func.body.unwrap());
-
-    // Build the IIFE (similar to arrow function)
-    Some(iife(vec![
-        // var _ref = _ngAsyncToGenerator(function* () { ... });
-        var_decl(ref_name, ng_async_wrapper(generator_expr)),
-        // return function originalName() { return _ref.apply(this, arguments); };
-        return_stmt(regular_fn_expr(
-            original_ident,
-            block(vec![return_stmt(apply_call(Expr::Ident(ident(ref_name))))]),
-        )),
-    ]))
+    let (mut generator_func, _) = create_generator_function(params, body, None, config, hygiene);
+
+    // TypeScript mode keeps the signature (see `transform_arrow_fn`)
+    let (type_params, return_type) = if config.typescript {
+        (func.type_params.take(), func.return_type.take())
+    } else {
+        (None, None)
+    };
+    generator_func.ctxt = func.ctxt;
+    generator_func.type_params = type_params.clone();
+    let generator_name = original_ident.as_ref().map(|name| &*name.sym);
+    let generator_expr = generator_fn_expr(generator_func, generator_name, config, hygiene);
+    let wrapped = wrap_generator(
+        generator_expr,
+        FunctionKind::Expression,
+        generator_name,
+        config,
+        hygiene,
+    );
+
+    let ref_ident = hygiene.private_ident(ref_name);
+    let mut wrapper_stmts = prologue;
+    wrapper_stmts.push(return_stmt(styled_delegate(
+        Expr::Ident(ref_ident.clone()),
+        None,
+        &mut placeholders,
+        forward,
+        cover,
+        config,
+        hygiene,
+    )));
+
+    // var _ref = _ngAsyncToGenerator(function* () { ... });
+    let ref_decl = ref_decl(ref_ident, wrapped, forward, config, hygiene);
+    // function originalName(_x) { return _ref.apply(this, arguments); }
+    let wrapper = with_ts_signature(
+        wrapper_fn_expr(original_ident, placeholders, block(wrapper_stmts), false),
+        type_params,
+        return_type,
+    );
+    match wrapper {
+        // function originalName(_x) { ... } return originalName;
+        Expr::Fn(FnExpr {
+            ident: Some(ident),
+            function,
+        }) if self_reference => {
+            let wrapper_decl = Stmt::Decl(Decl::Fn(FnDecl {
+                ident: ident.clone(),
+                declare: false,
+                function,
+            }));
+            let stmts = vec![ref_decl, wrapper_decl, return_stmt(Expr::Ident(ident))];
+            Some(TransformedFn::Inline(iife(stmts)))
+        }
+        wrapper if hoist => Some(TransformedFn::Hoisted {
+            wrapper,
+            ref_decl,
+            captures: LexicalCaptures::default(),
+        }),
+        // Build the IIFE (similar to arrow function)
+        wrapper => Some(TransformedFn::Inline(iife(vec![
+            ref_decl,
+            return_stmt(wrapper),
+        ]))),
+    }
+}
+
+/// Create the `_ref` declaration holding the `wrapped` generator:
+/// `var _ref = _ngAsyncToGenerator(function* () { ... });`
+///
+/// With `lazyRefs` the wrapper is only created on the first call, by a stub
+/// that replaces itself and calls it the way the outer wrapper does.
+fn ref_decl(
+    ref_ident: Ident,
+    wrapped: Expr,
+    forward_arguments: bool,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Stmt {
+    let kind = generated_decl_kind(config, config.lazy_refs);
+    if !config.lazy_refs {
+        return var_decl_with_kind(kind, ref_ident, wrapped);
+    }
+
+    // var _ref = function () {
+    //     _ref = _ngAsyncToGenerator(function* () { ... });
+    //     return _ref.apply(this, arguments);
+    // };
+    let delegate = delegate_call(Expr::Ident(ref_ident.clone()), forward_arguments, hygiene);
+    let stub = self_replacing_fn_expr(ref_ident.clone(), wrapped, delegate);
+    var_decl_with_kind(kind, ref_ident, stub)
 }
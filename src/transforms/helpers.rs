@@ -1,237 +1,918 @@
 //! Helper visitors and utility functions for async transformation.
 
+use std::collections::HashSet;
+
 use swc_core::{
-    common::{util::take::Take, SyntaxContext, DUMMY_SP},
+    common::{util::take::Take, Span, SyntaxContext, DUMMY_SP},
     ecma::{
         ast::*,
+        atoms::{atom, Atom},
         visit::{noop_visit_mut_type, noop_visit_type, Visit, VisitMut, VisitMutWith, VisitWith},
     },
 };
 
-use crate::ast_builders::ident;
+use crate::ast_builders::{
+    apply_call, apply_call_with_captured_this, arrow_fn_expr, binding_ident, block, call_with_args,
+    call_with_this, function_expr, immediate_call, member_expr, ng_async_wrapper, param,
+    promise_settling_try, regular_fn_expr, return_stmt, Hygiene,
+};
+use crate::config::{AwaitlessFunctions, CallStyle, Config, DeclarationKind};
+use crate::diagnostics;
+use crate::emitter::WrapperContext;
+use crate::report::FunctionKind;
 
-/// Visitor that transforms `await` expressions to `yield` expressions.
-///
-/// This is used to convert the body of async functions to generator functions.
-/// It does not descend into nested async functions or arrow expressions.
-pub struct AwaitToYieldVisitor;
+use super::using::UsingLoweringVisitor;
 
-impl VisitMut for AwaitToYieldVisitor {
-    noop_visit_mut_type!();
+// ============================================================================
+// UsedNamesCollector - Collect every identifier name in a program
+// ============================================================================
 
-    fn visit_mut_expr(&mut self, expr: &mut Expr) {
-        // First, recursively visit children
-        expr.visit_mut_children_with(self);
+/// Visitor that collects the name of every identifier in a program.
+///
+/// This is a superset of the program's bindings, which keeps the check cheap
+/// (no scope analysis) while guaranteeing that a generated name outside the
+/// set can neither shadow a user binding nor be shadowed by one.
+#[derive(Default)]
+pub struct UsedNamesCollector {
+    /// Collected identifier names.
+    pub names: HashSet<Atom>,
+}
 
-        // Then transform await to yield
-        if let Expr::Await(await_expr) = expr {
-            *expr = Expr::Yield(YieldExpr {
-                span: await_expr.span,
-                arg: Some(await_expr.arg.take()),
-                delegate: false,
-            });
-        }
+impl UsedNamesCollector {
+    /// Collect all identifier names in the given node.
+    pub fn collect<N: VisitWith<Self>>(node: &N) -> HashSet<Atom> {
+        let mut visitor = Self::default();
+        node.visit_with(&mut visitor);
+        visitor.names
     }
+}
 
-    // Don't descend into nested async functions - they have their own await/yield scope
-    fn visit_mut_function(&mut self, _: &mut Function) {}
-    fn visit_mut_arrow_expr(&mut self, _: &mut ArrowExpr) {}
+impl Visit for UsedNamesCollector {
+    noop_visit_type!();
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.names.insert(ident.sym.clone());
+    }
 }
 
 // ============================================================================
-// HasAwaitVisitor - Check if function body contains await
+// AlreadyTransformedVisitor - Detect the plugin's own output
 // ============================================================================
 
-/// Visitor that checks if a function body contains `await` expressions.
+/// Visitor that checks whether a program already contains the plugin's output.
 ///
-/// This is used to determine if an async function should be transformed.
-/// If there's no await, we can simply remove the async keyword instead
-/// of wrapping it in a generator.
-pub struct HasAwaitVisitor {
-    /// Whether any `await` expressions were found.
-    pub has_await: bool,
+/// The signature is a call to the runtime helper whose first argument is a
+/// generator function expression: `_ngAsyncToGenerator(function* () { ... })`.
+pub struct AlreadyTransformedVisitor<'a> {
+    /// Name of the runtime helper.
+    wrapper: &'a str,
+    /// Whether a wrapper call was found.
+    pub found: bool,
 }
 
-impl HasAwaitVisitor {
-    pub fn new() -> Self {
-        Self { has_await: false }
+impl<'a> AlreadyTransformedVisitor<'a> {
+    pub fn new(wrapper: &'a str) -> Self {
+        Self {
+            wrapper,
+            found: false,
+        }
     }
 
-    /// Check if the given block statement contains any await expressions.
-    pub fn check(body: &BlockStmt) -> bool {
-        let mut visitor = Self::new();
-        body.visit_with(&mut visitor);
-        visitor.has_await
+    /// Check if the given node contains a call to the `wrapper` helper.
+    pub fn check<N: VisitWith<Self>>(node: &N, wrapper: &'a str) -> bool {
+        let mut visitor = Self::new(wrapper);
+        node.visit_with(&mut visitor);
+        visitor.found
     }
 }
 
-impl Default for HasAwaitVisitor {
-    fn default() -> Self {
-        Self::new()
+impl Visit for AlreadyTransformedVisitor<'_> {
+    noop_visit_type!();
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if self.found {
+            return;
+        }
+
+        let calls_wrapper = matches!(
+            &call.callee,
+            Callee::Expr(callee) if matches!(&**callee, Expr::Ident(id) if &*id.sym == self.wrapper)
+        );
+        let wraps_generator = matches!(
+            call.args.first(),
+            Some(ExprOrSpread { spread: None, expr }) if matches!(&**expr, Expr::Fn(f) if f.function.is_generator)
+        );
+        if calls_wrapper && wraps_generator {
+            self.found = true;
+            return;
+        }
+
+        call.visit_children_with(self);
+    }
+}
+
+// ============================================================================
+// AsyncCodeVisitor - Cheap pre-scan for async code
+// ============================================================================
+
+/// Visitor that checks whether a program contains anything the transform
+/// acts on: an async function, arrow or method, or a top-level `await`.
+///
+/// Most files have none, and skipping them up front avoids the scope
+/// bookkeeping and the copy kept for error recovery.
+pub struct AsyncCodeVisitor {
+    /// Whether async code was found.
+    pub found: bool,
+}
+
+impl AsyncCodeVisitor {
+    /// Check if the given node contains async code.
+    pub fn check<N: VisitWith<Self>>(node: &N) -> bool {
+        let mut visitor = Self { found: false };
+        node.visit_with(&mut visitor);
+        visitor.found
     }
 }
 
-impl Visit for HasAwaitVisitor {
+impl Visit for AsyncCodeVisitor {
     noop_visit_type!();
 
-    fn visit_expr(&mut self, expr: &Expr) {
-        // If we already found an await, no need to continue
-        if self.has_await {
-            return;
+    fn visit_function(&mut self, function: &Function) {
+        if function.is_async {
+            self.found = true;
+        } else if !self.found {
+            function.visit_children_with(self);
         }
+    }
 
-        // Check if this is an await expression
-        if matches!(expr, Expr::Await(_)) {
-            self.has_await = true;
-            return;
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        if arrow.is_async {
+            self.found = true;
+        } else if !self.found {
+            arrow.visit_children_with(self);
         }
+    }
 
-        // Recursively visit children
-        expr.visit_children_with(self);
+    fn visit_await_expr(&mut self, _: &AwaitExpr) {
+        self.found = true;
     }
 
-    // Don't descend into nested async functions/arrows - they have their own await scope
-    fn visit_function(&mut self, _: &Function) {}
-    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+    fn visit_for_of_stmt(&mut self, stmt: &ForOfStmt) {
+        if stmt.is_await {
+            self.found = true;
+        } else if !self.found {
+            stmt.visit_children_with(self);
+        }
+    }
 }
 
 // ============================================================================
-// HasThisVisitor - Check if function body uses `this`
+// SelfReferenceVisitor - Detect references to a function's own name
 // ============================================================================
 
-/// Visitor that checks if a function body uses `this`.
+/// Visitor that checks whether a named function expression refers to itself
+/// through its name, in its params or body.
 ///
-/// This is used to determine if we need to capture `this` for arrow functions.
-/// Arrow functions have lexical `this` binding, so we need to capture it
-/// at the definition site.
-pub struct HasThisVisitor {
-    /// Whether any `this` references were found.
-    pub has_this: bool,
+/// References are matched by name and syntax context, so after `resolver`
+/// inner bindings shadowing the name do not count. Without `resolver`
+/// they do, which only costs the hoisted form.
+pub struct SelfReferenceVisitor {
+    name: Id,
+    /// Whether a reference to the name was found.
+    pub found: bool,
 }
 
-impl HasThisVisitor {
-    pub fn new() -> Self {
-        Self { has_this: false }
+impl SelfReferenceVisitor {
+    /// Check if `node` refers to `name`.
+    pub fn check<N: VisitWith<Self>>(node: &N, name: &Ident) -> bool {
+        let mut visitor = Self {
+            name: name.to_id(),
+            found: false,
+        };
+        node.visit_with(&mut visitor);
+        visitor.found
     }
+}
+
+impl Visit for SelfReferenceVisitor {
+    noop_visit_type!();
 
-    /// Check if the given block statement uses `this`.
-    pub fn check(body: &BlockStmt) -> bool {
-        let mut visitor = Self::new();
-        body.visit_with(&mut visitor);
-        visitor.has_this
+    fn visit_ident(&mut self, ident: &Ident) {
+        if ident.sym == self.name.0 && ident.ctxt == self.name.1 {
+            self.found = true;
+        }
     }
 }
 
-impl Default for HasThisVisitor {
-    fn default() -> Self {
-        Self::new()
+/// Kind of a generated declaration according to `declarationKind`, which
+/// defaults to `var` (`const` with `esnextOutput`). A `const` binding that is
+/// `reassigned` later becomes `let`.
+pub fn generated_decl_kind(config: &Config, reassigned: bool) -> VarDeclKind {
+    let kind = config.declaration_kind.unwrap_or(if config.esnext_output {
+        DeclarationKind::Const
+    } else {
+        DeclarationKind::Var
+    });
+    match kind {
+        DeclarationKind::Var => VarDeclKind::Var,
+        DeclarationKind::Let => VarDeclKind::Let,
+        DeclarationKind::Const if reassigned => VarDeclKind::Let,
+        DeclarationKind::Const => VarDeclKind::Const,
     }
 }
 
-impl Visit for HasThisVisitor {
-    noop_visit_type!();
+/// Turn a generator into an expression. With `namedGenerators` it is named
+/// after the function it came from, so stack traces show `_load$` instead of
+/// an anonymous function: `function* _load$() { ... }`. Anonymous functions
+/// (and names that are not identifiers) give `_callee$`.
+///
+/// With `coverage` the generator takes the span of the body it runs, so
+/// coverage of the output maps back to the original function.
+pub fn generator_fn_expr(
+    mut generator: Function,
+    name: Option<&str>,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Expr {
+    let ident = config.names_generators().then(|| {
+        let base = name
+            .filter(|name| Ident::verify_symbol(name).is_ok())
+            .unwrap_or("callee");
+        hygiene.private_ident(format!("_{}$", base))
+    });
+    if config.coverage {
+        if let Some(body) = &generator.body {
+            generator.span = body.span;
+        }
+    }
+    function_expr(ident, generator)
+}
 
-    fn visit_expr(&mut self, expr: &Expr) {
-        // If we already found a this, no need to continue
-        if self.has_this {
-            return;
+/// Wrap the generator of a function of `kind` named `name`, with the
+/// `wrapperEmitter` of `config` or [`runtime_wrapper`] by default.
+pub fn wrap_generator(
+    generator_expr: Expr,
+    kind: FunctionKind,
+    name: Option<&str>,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Expr {
+    match &config.wrapper_emitter {
+        Some(emitter) => {
+            let context = WrapperContext {
+                kind,
+                name,
+                config,
+                hygiene,
+            };
+            emitter.wrap_generator(generator_expr, &context)
         }
+        None => runtime_wrapper(generator_expr, config, hygiene),
+    }
+}
 
-        // Check if this is a `this` expression
-        if matches!(expr, Expr::This(_)) {
-            self.has_this = true;
-            return;
+/// Create the runtime helper call for a generator:
+/// `_ngAsyncToGenerator(function* () { ... })`.
+///
+/// With `zone` a getter for the zone is passed along, so the runtime can
+/// re-enter it on every resumption:
+/// `_ngAsyncToGenerator(function* () { ... }, function () { return Zone.current; })`
+pub fn runtime_wrapper(generator_expr: Expr, config: &Config, hygiene: Hygiene) -> Expr {
+    let mut wrapped = ng_async_wrapper(generator_expr, &config.wrapper_function_name, hygiene);
+    if let (Some(zone), Expr::Call(call)) = (&config.zone, &mut wrapped) {
+        let mut path = zone.split('.');
+        let root = hygiene.global_ident(path.next().unwrap_or_default());
+        let zone_expr = path.fold(Expr::Ident(root), member_expr);
+        let getter = regular_fn_expr(None, block(vec![return_stmt(zone_expr)]));
+        call.args.push(ExprOrSpread {
+            spread: None,
+            expr: Box::new(getter),
+        });
+    }
+    wrapped
+}
+
+/// Whether a delegating call has to forward `arguments`.
+///
+/// In `loose` mode, functions without params whose body never reads
+/// `arguments` are called with `.call(this)` instead of
+/// `.apply(this, arguments)`.
+pub fn forwards_arguments(has_params: bool, facts: &BodyFacts, config: &Config) -> bool {
+    !config.loose || has_params || facts.arguments
+}
+
+/// Whether the [`arity_placeholders`] of `pats` stand in for every one of
+/// them, so passing the placeholders on passes each argument the params
+/// bind. Defaults and rest params have no placeholder.
+pub fn placeholders_cover<'a>(pats: impl IntoIterator<Item = &'a Pat>) -> bool {
+    pats.into_iter()
+        .all(|pat| !matches!(pat, Pat::Assign(_) | Pat::Rest(_)))
+}
+
+/// Create the call by which a wrapper with the `placeholders` params
+/// delegates to `target`, in the `callStyle` of `config`.
+///
+/// `this` is the captured `_this` the call passes on, if any. Without
+/// `forward` the call is `target.call(this)` in every style. `"call"`
+/// passes the placeholders when they `cover` the params of a function that
+/// does not read `arguments`, and falls back to `.apply` otherwise;
+/// `"spread"` adds a rest param `...args` to `placeholders`.
+pub fn styled_delegate(
+    target: Expr,
+    this: Option<Ident>,
+    placeholders: &mut Vec<Param>,
+    forward: bool,
+    cover: bool,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Expr {
+    let this_expr = match &this {
+        Some(this) => Expr::Ident(this.clone()),
+        None => Expr::This(ThisExpr { span: DUMMY_SP }),
+    };
+    if !forward {
+        return call_with_this(target, this_expr);
+    }
+    let args = placeholders
+        .iter()
+        .filter(|param| !is_this_param(&param.pat))
+        .filter_map(|param| param.pat.as_ident())
+        .map(|binding| binding.id.clone())
+        .collect();
+    match (config.call_style, this) {
+        (CallStyle::Call, _) if cover => call_with_args(target, this_expr, args, None),
+        (CallStyle::Spread, _) => {
+            let rest = hygiene.private_ident(atom!("args"));
+            placeholders.push(Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat: Pat::Rest(RestPat {
+                    span: DUMMY_SP,
+                    dot3_token: DUMMY_SP,
+                    arg: Box::new(Pat::Ident(binding_ident(rest.clone()))),
+                    type_ann: None,
+                }),
+            });
+            call_with_args(target, this_expr, args, Some(rest))
         }
+        (_, Some(this)) => apply_call_with_captured_this(target, this, hygiene),
+        (_, None) => apply_call(target, hygiene),
+    }
+}
 
-        // Recursively visit children
-        expr.visit_children_with(self);
+/// Handle an async function body without `await` according to
+/// `awaitlessFunctions`. `facts` are those of `body`.
+///
+/// Returns whether the body was dealt with, in which case the caller only
+/// removes the `async` keyword. With `"promise"` the body is rewritten to
+/// settle a promise itself:
+///
+/// ```javascript
+/// try {
+///     return Promise.resolve((() => { ...body })());
+/// } catch (e) {
+///     return Promise.reject(e);
+/// }
+/// ```
+///
+/// The arrow keeps `this`, `arguments`, `super` and `new.target` of the
+/// function, so the body runs unchanged.
+pub fn handles_awaitless(
+    body: &mut BlockStmt,
+    facts: &BodyFacts,
+    config: &Config,
+    hygiene: Hygiene,
+) -> bool {
+    match config.awaitless_functions {
+        AwaitlessFunctions::Wrap => false,
+        _ if facts.has_await => false,
+        AwaitlessFunctions::Drop => true,
+        AwaitlessFunctions::Promise => {
+            let mut stmts = directive_prologue(body);
+            let result = immediate_call(arrow_fn_expr(body.take()));
+            let error = hygiene.private_ident(atom!("e"));
+            stmts.push(promise_settling_try(result, error, hygiene));
+            *body = block(stmts);
+            true
+        }
     }
+}
 
-    // Don't descend into nested regular functions - they have their own `this` context
-    // But DO descend into arrow functions - they inherit `this` from outer scope
-    fn visit_function(&mut self, _: &Function) {}
+/// [`handles_awaitless`] for arrow bodies, which may be expressions.
+pub fn handles_awaitless_arrow(
+    body: &mut BlockStmtOrExpr,
+    facts: &BodyFacts,
+    config: &Config,
+    hygiene: Hygiene,
+) -> bool {
+    match body {
+        BlockStmtOrExpr::BlockStmt(b) => handles_awaitless(b, facts, config, hygiene),
+        BlockStmtOrExpr::Expr(e) => {
+            let wraps = config.awaitless_functions == AwaitlessFunctions::Wrap;
+            if wraps || facts.has_await {
+                return false;
+            }
+            if config.awaitless_functions == AwaitlessFunctions::Promise {
+                let mut b = block(vec![return_stmt(*e.take())]);
+                handles_awaitless(&mut b, facts, config, hygiene);
+                *body = BlockStmtOrExpr::BlockStmt(b);
+            }
+            true
+        }
+    }
+}
+
+/// Whether the transform must leave a function native because its body uses
+/// direct `eval` or `with`, according to its `facts`. Emits a warning when
+/// it does.
+pub fn bails_on_unsafe_scope(facts: &BodyFacts, config: &Config) -> bool {
+    if config.allow_eval_and_with {
+        return false;
+    }
+
+    match facts.unsafe_scope {
+        Some(span) => {
+            diagnostics::unsupported(
+                span,
+                "async function left untransformed: direct `eval`/`with` could observe the \
+                 scope changes made by the generator wrapper (set `allowEvalAndWith` to \
+                 transform it anyway)",
+                config,
+            );
+            true
+        }
+        None => false,
+    }
 }
 
 // ============================================================================
-// ThisCaptureVisitor - Capture this references
+// GeneratorBodyVisitor - Inspect and rewrite a body in one pass each
 // ============================================================================
 
-/// Visitor that captures and replaces `this` references with `_this`.
+/// Lexical bindings a generator body had to capture from its outer function.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LexicalCaptures {
+    /// Whether `this` was replaced with `_this`.
+    pub this: bool,
+    /// Whether `arguments` was replaced with `_arguments`.
+    pub arguments: bool,
+}
+
+/// Identifiers that replace `this` / `arguments` in a generator body.
+///
+/// Each nesting level of capturing functions (an async arrow inside an async
+/// method, an async arrow inside that arrow, ...) gets its own pair: `_this`,
+/// `_this2`, `_this3`, ... So every generator refers to the capture made at
+/// its own lexical position instead of relying on an inner capture shadowing
+/// an outer one with the same name.
+#[derive(Debug, Clone)]
+pub struct CaptureIdents {
+    /// Replacement for `this`.
+    pub this: Ident,
+    /// Replacement for `arguments`.
+    pub arguments: Ident,
+}
+
+impl CaptureIdents {
+    /// Identifiers for the capturing boundary at `depth` (1 = outermost).
+    pub fn new(config: &Config, hygiene: Hygiene, depth: usize) -> Self {
+        let name = |base: &str| {
+            if depth <= 1 {
+                hygiene.private_ident(base)
+            } else {
+                hygiene.private_ident(format!("{}{}", base, depth))
+            }
+        };
+
+        Self {
+            this: name(&config.this_var_name),
+            arguments: name(&config.arguments_var_name),
+        }
+    }
+}
+
+/// What an async function body uses of its function, as found by
+/// [`GeneratorBodyVisitor::inspect`].
+///
+/// Nested arrows share the `this` / `arguments` of the body and are
+/// searched for them; nested regular functions, accessors and the
+/// constructors, fields and static blocks of nested classes have their own
+/// and are not, apart from the computed keys and decorators evaluated in
+/// the body. Awaits of nested arrows belong to the arrows.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BodyFacts {
+    /// Whether the body contains an `await`, an `await using` or a
+    /// `for await` loop.
+    ///
+    /// With `awaitlessFunctions`, functions without one simply lose the
+    /// async keyword instead of being wrapped in a generator.
+    pub has_await: bool,
+    /// Whether the body reads `this`.
+    pub this: bool,
+    /// Whether the body reads `arguments`. Direct `eval` counts too, since
+    /// it may reach `arguments` as well.
+    pub arguments: bool,
+    /// Span of the first direct `eval(...)` call or `with` statement.
+    ///
+    /// Both can observe the scope of the body, which the transform changes
+    /// (captured `_this`/`_arguments`, generator boundary).
+    pub unsafe_scope: Option<Span>,
+    /// Span of the first `for await` loop, which a generator cannot run.
+    pub for_await: Option<Span>,
+}
+
+/// Visitor that turns an async function body into a generator body in a
+/// single traversal.
+///
+/// - `await` expressions of the body become `yield` expressions. Nested
+///   functions and arrows have their own await scope and keep theirs.
+/// - With capture identifiers (methods and arrows), `this` / `arguments` are
+///   replaced with `_this` / `_arguments`, since the generator function
+///   creates a new context. Nested arrows inherit the outer context and are
+///   rewritten too; nested regular functions, accessors and class members
+///   with a `this` of their own are not (see [`BodyFacts`]).
+/// - With `simplifyReturnAwait`, `return await expr;` becomes
+///   `return expr;`: a returned promise is adopted by the wrapper's promise,
+///   so the extra `await` only costs a tick. Anywhere in a `try` statement
+///   the `await` decides whether a rejection reaches the `catch` and whether
+///   `finally` runs before or after settlement, so those returns are left
+///   alone.
 ///
-/// This is necessary for class/object methods because the generator function
-/// creates a new `this` context. By capturing the outer `this` as `_this`,
-/// we preserve the correct reference.
-pub struct ThisCaptureVisitor {
-    /// Whether any `this` references were found and replaced.
-    pub needs_this: bool,
+/// The same walk, read-only, gathers the [`BodyFacts`] the transform decides
+/// on beforehand.
+pub struct GeneratorBodyVisitor {
+    /// Which bindings were found and replaced.
+    pub captures: LexicalCaptures,
+    /// Replacements for `this` / `arguments`, if they are captured.
+    idents: Option<CaptureIdents>,
+    /// Whether `return await expr;` is simplified.
+    simplify_return_await: bool,
+    /// Facts gathered by [`GeneratorBodyVisitor::inspect`].
+    facts: BodyFacts,
+    /// Number of nested arrows around the current node.
+    arrow_depth: usize,
+    /// Number of `try` statements around the current node.
+    protected_depth: usize,
 }
 
-impl ThisCaptureVisitor {
-    pub fn new() -> Self {
-        Self { needs_this: false }
+impl GeneratorBodyVisitor {
+    pub fn new(idents: Option<CaptureIdents>, simplify_return_await: bool) -> Self {
+        Self {
+            captures: LexicalCaptures::default(),
+            idents,
+            simplify_return_await,
+            facts: BodyFacts::default(),
+            arrow_depth: 0,
+            protected_depth: 0,
+        }
+    }
+
+    /// Gather the [`BodyFacts`] of `node`, a function body (or arrow
+    /// expression body) or its params.
+    pub fn inspect<N: VisitWith<Self>>(node: &N) -> BodyFacts {
+        let mut visitor = Self::new(None, false);
+        node.visit_with(&mut visitor);
+        visitor.facts
     }
 }
 
-impl Default for ThisCaptureVisitor {
-    fn default() -> Self {
-        Self::new()
+impl Visit for GeneratorBodyVisitor {
+    noop_visit_type!();
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        if matches!(expr, Expr::Await(_)) && self.arrow_depth == 0 {
+            self.facts.has_await = true;
+        }
+        expr.visit_children_with(self);
+    }
+
+    // `await using` awaits the disposal even without an explicit `await`
+    fn visit_using_decl(&mut self, decl: &UsingDecl) {
+        if decl.is_await && self.arrow_depth == 0 {
+            self.facts.has_await = true;
+        }
+        decl.visit_children_with(self);
+    }
+
+    fn visit_for_of_stmt(&mut self, stmt: &ForOfStmt) {
+        if stmt.is_await && self.arrow_depth == 0 {
+            self.facts.has_await = true;
+            self.facts.for_await.get_or_insert(stmt.span);
+        }
+        stmt.visit_children_with(self);
+    }
+
+    fn visit_this_expr(&mut self, _: &ThisExpr) {
+        self.facts.this = true;
     }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if matches!(&*ident.sym, "arguments" | "eval") {
+            self.facts.arguments = true;
+        }
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            if matches!(&**callee, Expr::Ident(id) if &*id.sym == "eval") {
+                self.facts.unsafe_scope.get_or_insert(call.span);
+            }
+        }
+        call.visit_children_with(self);
+    }
+
+    fn visit_with_stmt(&mut self, stmt: &WithStmt) {
+        self.facts.unsafe_scope.get_or_insert(stmt.span);
+        stmt.visit_children_with(self);
+    }
+
+    // Nested regular functions get their own scope
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        self.arrow_depth += 1;
+        arrow.visit_children_with(self);
+        self.arrow_depth -= 1;
+    }
+
+    // Accessors and class members with a `this` of their own only have
+    // their computed keys and decorators evaluated in the body
+    fn visit_getter_prop(&mut self, prop: &GetterProp) {
+        prop.key.visit_with(self);
+    }
+
+    fn visit_setter_prop(&mut self, prop: &SetterProp) {
+        prop.key.visit_with(self);
+    }
+
+    fn visit_constructor(&mut self, _: &Constructor) {}
+
+    fn visit_class_prop(&mut self, prop: &ClassProp) {
+        prop.key.visit_with(self);
+        prop.decorators.visit_with(self);
+    }
+
+    fn visit_private_prop(&mut self, prop: &PrivateProp) {
+        prop.decorators.visit_with(self);
+    }
+
+    fn visit_auto_accessor(&mut self, accessor: &AutoAccessor) {
+        accessor.key.visit_with(self);
+        accessor.decorators.visit_with(self);
+    }
+
+    fn visit_static_block(&mut self, _: &StaticBlock) {}
 }
 
-impl VisitMut for ThisCaptureVisitor {
+impl VisitMut for GeneratorBodyVisitor {
     noop_visit_mut_type!();
 
     fn visit_mut_expr(&mut self, expr: &mut Expr) {
-        // Check if this is a `this` expression
-        if matches!(expr, Expr::This(_)) {
-            self.needs_this = true;
-            *expr = Expr::Ident(ident("_this"));
-            return;
+        if let Some(idents) = &self.idents {
+            match expr {
+                Expr::This(_) => {
+                    self.captures.this = true;
+                    *expr = Expr::Ident(idents.this.clone());
+                    return;
+                }
+                Expr::Ident(id) if &*id.sym == "arguments" => {
+                    self.captures.arguments = true;
+                    *expr = Expr::Ident(idents.arguments.clone());
+                    return;
+                }
+                _ => {}
+            }
         }
-        // Recursively visit children
+
+        // First, recursively visit children
         expr.visit_mut_children_with(self);
+
+        // Then transform await to yield, unless it belongs to a nested arrow
+        if self.arrow_depth == 0 {
+            if let Expr::Await(await_expr) = expr {
+                *expr = Expr::Yield(YieldExpr {
+                    span: await_expr.span,
+                    arg: Some(await_expr.arg.take()),
+                    delegate: false,
+                });
+            }
+        }
+    }
+
+    fn visit_mut_return_stmt(&mut self, stmt: &mut ReturnStmt) {
+        // Returns of nested arrows belong to them
+        if self.simplify_return_await && self.arrow_depth == 0 && self.protected_depth == 0 {
+            if let Some(arg) = &mut stmt.arg {
+                if let Expr::Await(await_expr) = &mut **arg {
+                    *arg = await_expr.arg.take();
+                }
+            }
+        }
+        stmt.visit_mut_children_with(self);
     }
 
-    // Don't descend into nested regular functions - they have their own `this` context
+    // Returns in any part of a `try` statement keep their `await`
+    fn visit_mut_try_stmt(&mut self, stmt: &mut TryStmt) {
+        self.protected_depth += 1;
+        stmt.visit_mut_children_with(self);
+        self.protected_depth -= 1;
+    }
+
+    // Don't descend into nested regular functions - they have their own context
     fn visit_mut_function(&mut self, _: &mut Function) {}
 
-    // DO descend into arrow functions - they inherit `this` from the outer scope
+    // Arrows inherit `this`/`arguments` from the outer scope, but not its awaits
     fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
-        arrow.visit_mut_children_with(self);
+        if self.idents.is_some() {
+            self.arrow_depth += 1;
+            arrow.visit_mut_children_with(self);
+            self.arrow_depth -= 1;
+        }
+    }
+
+    // Accessors and class members with a `this` of their own only have
+    // their computed keys and decorators evaluated in the body
+    fn visit_mut_getter_prop(&mut self, prop: &mut GetterProp) {
+        prop.key.visit_mut_with(self);
+    }
+
+    fn visit_mut_setter_prop(&mut self, prop: &mut SetterProp) {
+        prop.key.visit_mut_with(self);
+    }
+
+    fn visit_mut_constructor(&mut self, _: &mut Constructor) {}
+
+    fn visit_mut_class_prop(&mut self, prop: &mut ClassProp) {
+        prop.key.visit_mut_with(self);
+        prop.decorators.visit_mut_with(self);
+    }
+
+    fn visit_mut_private_prop(&mut self, prop: &mut PrivateProp) {
+        prop.decorators.visit_mut_with(self);
+    }
+
+    fn visit_mut_auto_accessor(&mut self, accessor: &mut AutoAccessor) {
+        accessor.key.visit_mut_with(self);
+        accessor.decorators.visit_mut_with(self);
+    }
+
+    fn visit_mut_static_block(&mut self, _: &mut StaticBlock) {}
+}
+
+// ============================================================================
+// Directive Prologues
+// ============================================================================
+
+/// Clone the directive prologue (`"use strict";` and friends) at the top of
+/// a function body.
+///
+/// The body itself keeps its directives when it moves into the generator;
+/// the clones go onto the outer delegating function so its strictness does
+/// not change either.
+pub fn directive_prologue(body: &BlockStmt) -> Vec<Stmt> {
+    body.stmts
+        .iter()
+        .take_while(|stmt| is_directive(stmt))
+        .cloned()
+        .collect()
+}
+
+/// Whether `stmt` is a directive such as `"use strict";`.
+pub fn is_directive(stmt: &Stmt) -> bool {
+    matches!(stmt, Stmt::Expr(ExprStmt { expr, .. }) if matches!(&**expr, Expr::Lit(Lit::Str(_))))
+}
+
+// ============================================================================
+// Arity Preservation
+// ============================================================================
+
+/// Build placeholder params (`_x`, `_x2`, `_x3`, ...) for a delegating wrapper.
+///
+/// Unless `callStyle` passes them on, wrappers forward everything through
+/// `arguments` and the placeholders are never read; they keep `fn.length`
+/// identical to the original function. Like `Function.prototype.length`,
+/// only params before the first default or rest param are counted.
+///
+/// With `preserve_wrapper_params`, placeholders of plain params take their
+/// names instead; destructured params keep `_x`-style names.
+///
+/// Assuming `ignoreFunctionLength`, there are none, unless the wrapper
+/// passes them on or `preserve_wrapper_params` wants them.
+///
+/// With `typescript`, each placeholder copies the type annotation and
+/// optionality of the param it stands in for.
+///
+/// A TypeScript `this` param is not counted; with `typescript` the wrapper
+/// keeps it as its first param, so `this` stays typed for callers.
+pub fn arity_placeholders<'a>(
+    pats: impl IntoIterator<Item = &'a Pat>,
+    config: &Config,
+    hygiene: Hygiene,
+) -> Vec<Param> {
+    let keep_types = config.typescript;
+    let mut pats = pats.into_iter().peekable();
+    let this_param = pats
+        .next_if(|pat| is_this_param(pat))
+        .filter(|_| keep_types)
+        .map(|pat| Param {
+            span: DUMMY_SP,
+            decorators: vec![],
+            pat: pat.clone(),
+        });
+    let needed = !config.assumptions.ignore_function_length
+        || config.preserve_wrapper_params
+        || config.call_style == CallStyle::Call;
+    if !needed {
+        return this_param.into_iter().collect();
+    }
+    let placeholders = pats
+        .take_while(|pat| !matches!(pat, Pat::Assign(_) | Pat::Rest(_)))
+        .enumerate()
+        .map(|(i, pat)| {
+            let mut placeholder = match pat {
+                Pat::Ident(binding) if config.preserve_wrapper_params => {
+                    param(hygiene.private_ident(binding.id.sym.clone()))
+                }
+                _ if i == 0 => param(hygiene.private_ident(atom!("_x"))),
+                _ => param(hygiene.private_ident(format!("_x{}", i + 1))),
+            };
+            if keep_types {
+                if let Pat::Ident(binding) = &mut placeholder.pat {
+                    binding.type_ann = pat_type_ann(pat);
+                    binding.id.optional = matches!(pat, Pat::Ident(b) if b.id.optional);
+                }
+            }
+            placeholder
+        });
+    this_param.into_iter().chain(placeholders).collect()
+}
+
+/// Whether `pat` is a TypeScript `this` param (`this: Service`). It only
+/// types `this` and is not an argument: it is neither counted in the arity
+/// nor a reason to forward `arguments`.
+pub fn is_this_param(pat: &Pat) -> bool {
+    matches!(pat, Pat::Ident(binding) if &*binding.id.sym == "this")
+}
+
+/// Whether `params` take any argument, leaving out a `this` param.
+pub fn takes_arguments(params: &[Param]) -> bool {
+    params.iter().any(|param| !is_this_param(&param.pat))
+}
+
+/// Type annotation of a parameter pattern, if any.
+fn pat_type_ann(pat: &Pat) -> Option<Box<TsTypeAnn>> {
+    match pat {
+        Pat::Ident(binding) => binding.type_ann.clone(),
+        Pat::Array(array) => array.type_ann.clone(),
+        Pat::Object(object) => object.type_ann.clone(),
+        _ => None,
     }
 }
 
 /// Create a generator function from an async function body.
 ///
 /// This function:
-/// 1. Transforms all `await` expressions to `yield` expressions
-/// 2. Optionally captures `this` references (for methods)
+/// 1. Lowers `using` / `await using` declarations to try/finally disposal
+/// 2. Transforms all `await` expressions to `yield` expressions and, in the
+///    same pass, simplifies `return await` and optionally captures `this` /
+///    `arguments` references (for methods and arrows)
 ///
 /// # Arguments
 /// * `params` - The function parameters
 /// * `body` - The function body
-/// * `capture_this` - Whether to capture and replace `this` references
+/// * `capture` - Identifiers to replace `this` / `arguments` references with,
+///   or `None` to leave them alone
+/// * `config` - Plugin options
+/// * `hygiene` - Contexts for the identifiers introduced into the body
 ///
 /// # Returns
-/// A tuple of (generator function, whether `this` capture is needed)
+/// A tuple of (generator function, which bindings had to be captured)
 pub fn create_generator_function(
     params: Vec<Param>,
     body: BlockStmt,
-    capture_this: bool,
-) -> (Function, bool) {
+    capture: Option<&CaptureIdents>,
+    config: &Config,
+    hygiene: Hygiene,
+) -> (Function, LexicalCaptures) {
+    let mut params = params;
     let mut new_body = body;
 
-    // Transform await to yield
-    let mut await_visitor = AwaitToYieldVisitor;
-    new_body.visit_mut_with(&mut await_visitor);
-
-    // For methods, capture `this`
-    let mut needs_this = false;
-    if capture_this {
-        let mut this_visitor = ThisCaptureVisitor::new();
-        new_body.visit_mut_with(&mut this_visitor);
-        needs_this = this_visitor.needs_this;
+    // Lower `using` declarations first so the `await` emitted for async
+    // disposal goes through the same await-to-yield rewrite
+    new_body.visit_mut_with(&mut UsingLoweringVisitor::new(
+        &config.using_helper_name,
+        hygiene,
+    ));
+
+    // Transform await to yield, simplify `return await` and, for methods and
+    // arrows, capture `this` and `arguments`; params cannot contain `await`
+    let mut body_visitor =
+        GeneratorBodyVisitor::new(capture.cloned(), config.simplify_return_await);
+    if capture.is_some() {
+        params.visit_mut_with(&mut body_visitor);
     }
+    new_body.visit_mut_with(&mut body_visitor);
+    let captures = body_visitor.captures;
 
     let func = Function {
         params,
@@ -245,5 +926,5 @@ pub fn create_generator_function(
         return_type: None,
     };
 
-    (func, needs_this)
+    (func, captures)
 }
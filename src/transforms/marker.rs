@@ -0,0 +1,115 @@
+//! Marker comments for generated code.
+//!
+//! With `markerComment` set, every statement the transform inserted starts
+//! with the marker, so reviews, lint exemptions and coverage filters can
+//! tell generated code from the original:
+//!
+//! ```javascript
+//! function load(_x) {
+//!     /* ng-async-generated */ return _load.apply(this, arguments);
+//! }
+//! /* ng-async-generated */ function _load() {
+//!     _load = _ngAsyncToGenerator(function* (id) {
+//!         return yield fetch(id);
+//!     });
+//!     return _load.apply(this, arguments);
+//! }
+//! ```
+//!
+//! Statements nested in a marked one are covered by its marker, up to the
+//! original statements they run, inside which generated statements are
+//! marked again.
+
+use swc_core::{
+    common::{
+        comments::{Comment, CommentKind, Comments},
+        Span, Spanned, DUMMY_SP,
+    },
+    ecma::{
+        ast::*,
+        visit::{noop_visit_mut_type, VisitMut, VisitMutWith},
+    },
+};
+
+use super::coverage::is_original;
+
+/// Put a `/* <marker> */` comment before every statement in `program` that
+/// the transform generated.
+pub fn mark_generated_code(program: &mut Program, comments: &dyn Comments, marker: &str) {
+    program.visit_mut_with(&mut MarkerVisitor {
+        comments,
+        marker,
+        in_marked: false,
+    });
+}
+
+/// Visitor that marks generated statements.
+struct MarkerVisitor<'a> {
+    comments: &'a dyn Comments,
+    marker: &'a str,
+    /// Whether the current node is inside a marked statement, with no
+    /// original statement in between.
+    in_marked: bool,
+}
+
+impl MarkerVisitor<'_> {
+    /// Give a statement a span that can carry comments, unless it has one
+    /// already (see [`super::tag_generated_functions`]), and attach the
+    /// marker to it.
+    fn mark(&self, span: &mut Span) {
+        if !span.lo.is_reserved_for_comments() {
+            *span = Span::dummy_with_cmt();
+        }
+        self.comments.add_leading(
+            span.lo,
+            Comment {
+                kind: CommentKind::Block,
+                span: DUMMY_SP,
+                text: format!(" {} ", self.marker).into(),
+            },
+        );
+    }
+}
+
+impl VisitMut for MarkerVisitor<'_> {
+    noop_visit_mut_type!();
+
+    fn visit_mut_module_item(&mut self, item: &mut ModuleItem) {
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if !is_original(import.span) => {
+                self.mark(&mut import.span);
+            }
+            _ => item.visit_mut_children_with(self),
+        }
+    }
+
+    fn visit_mut_stmt(&mut self, stmt: &mut Stmt) {
+        let outer = self.in_marked;
+        if is_original(stmt.span()) {
+            self.in_marked = false;
+        } else if !self.in_marked {
+            if let Some(span) = leading_span_mut(stmt) {
+                self.mark(span);
+                self.in_marked = true;
+            }
+        }
+        stmt.visit_mut_children_with(self);
+        self.in_marked = outer;
+    }
+}
+
+/// The span whose leading comments are printed before `stmt`, for the kinds
+/// of statements the transform generates.
+fn leading_span_mut(stmt: &mut Stmt) -> Option<&mut Span> {
+    match stmt {
+        Stmt::Decl(Decl::Var(var)) => Some(&mut var.span),
+        Stmt::Decl(Decl::Fn(fn_decl)) => Some(&mut fn_decl.function.span),
+        Stmt::Expr(expr) => Some(&mut expr.span),
+        Stmt::Return(ret) => Some(&mut ret.span),
+        Stmt::If(if_stmt) => Some(&mut if_stmt.span),
+        Stmt::Throw(throw) => Some(&mut throw.span),
+        Stmt::Try(try_stmt) => Some(&mut try_stmt.span),
+        Stmt::Block(block) => Some(&mut block.span),
+        _ => None,
+    }
+}
@@ -28,10 +28,18 @@
 
 use swc_core::ecma::ast::*;
 
+use crate::config::{Compat, Config};
+use crate::report::FunctionKind;
+
+use super::helpers::{
+    bails_on_unsafe_scope, create_generator_function, directive_prologue, generated_decl_kind,
+    generator_fn_expr, handles_awaitless, wrap_generator, CaptureIdents, GeneratorBodyVisitor,
+};
+use super::tsc::transform_awaiter_function;
+use super::unsupported::{bails_on_async_generator, bails_on_for_await, bails_on_super};
 use crate::ast_builders::{
-    block, generator_fn_expr, immediate_call, ng_async_wrapper, return_stmt, this_capture,
+    arguments_capture, block, immediate_call, return_stmt, this_capture, Hygiene,
 };
-use super::helpers::{create_generator_function, HasAwaitVisitor};
 
 /// Result of transforming an async method.
 pub struct MethodTransformResult {
@@ -41,83 +49,146 @@ pub struct MethodTransformResult {
 
 /// Transform an async method (class method or object method).
 ///
-/// This handles the `this` capture logic that's unique to methods.
+/// This handles the `this` / `arguments` capture logic that's unique to methods.
 ///
 /// # Arguments
 /// * `body` - The method body
+/// * `kind` - Class or object method, passed to the `wrapperEmitter`
+/// * `name` - Name of the method, used for `namedGenerators`
+/// * `config` - Plugin options
+/// * `hygiene` - Contexts for the generated `_this` / `_arguments` bindings
 ///
 /// # Returns
 /// The transformation result containing the new body statements
-pub fn transform_method(body: BlockStmt) -> MethodTransformResult {
-    // Create generator with this capture enabled
-    let (generator_func, needs_this) = create_generator_function(vec![], body, true);
-    let generator_expr = generator_fn_expr(generator_func.params, generator_func.body.unwrap());
-
-    let mut stmts = Vec::new();
+pub fn transform_method(
+    body: BlockStmt,
+    kind: FunctionKind,
+    name: Option<&str>,
+    config: &Config,
+    hygiene: Hygiene,
+) -> MethodTransformResult {
+    // Directives stay in effect for the method itself
+    let mut stmts = directive_prologue(&body);
+
+    // Create generator with this/arguments capture enabled. A method starts
+    // a new `this` scope, so its captures are always the outermost level
+    let idents = CaptureIdents::new(config, hygiene, 1);
+    let (generator_func, captures) =
+        create_generator_function(vec![], body, Some(&idents), config, hygiene);
+    let generator_expr = generator_fn_expr(generator_func, name, config, hygiene);
 
     // Add `var _this = this;` if needed
-    if needs_this {
-        stmts.push(this_capture());
+    if captures.this {
+        stmts.push(this_capture(
+            generated_decl_kind(config, false),
+            idents.this.clone(),
+        ));
+    }
+
+    // Add `var _arguments = arguments;` if needed
+    if captures.arguments {
+        stmts.push(arguments_capture(
+            generated_decl_kind(config, false),
+            idents.arguments.clone(),
+            hygiene,
+        ));
     }
 
     // return _ngAsyncToGenerator(function* () { ... })()
-    stmts.push(return_stmt(immediate_call(ng_async_wrapper(generator_expr))));
+    let wrapped = wrap_generator(generator_expr, kind, name, config, hygiene);
+    stmts.push(return_stmt(immediate_call(wrapped)));
 
     MethodTransformResult { stmts }
 }
 
-/// Apply transformation to a class method.
-pub fn transform_class_method(method: &mut ClassMethod) {
-    if !method.function.is_async {
-        return;
+/// Transform the function of an async method in place.
+///
+/// The original parameter list stays on the outer method, so defaults,
+/// destructuring and rest params are evaluated exactly once at call time and
+/// the generator simply closes over the resulting bindings. This also keeps
+/// the method's `length` unchanged.
+///
+/// Only the body is swapped, so `func.decorators` (and any param decorators)
+/// stay on the same method node in their original order. Decorators - legacy
+/// or 2022 - therefore wrap the delegating method, never the raw generator.
+///
+/// Returns whether the body was rewritten to call the runtime helper.
+fn transform_method_function(
+    func: &mut Function,
+    kind: FunctionKind,
+    name: Option<&str>,
+    config: &Config,
+    hygiene: Hygiene,
+) -> bool {
+    if config.compat == Compat::Tsc {
+        return transform_awaiter_function(func, config, hygiene);
+    }
+    // Overload signatures and abstract methods have no body - leave them untouched
+    if !func.is_async || func.body.is_none() || bails_on_async_generator(func, config) {
+        return false;
     }
 
-    let func = &mut method.function;
+    let facts = GeneratorBodyVisitor::inspect(&func.body);
 
-    // Check if body contains await - if not, just remove async keyword
-    if let Some(body) = &func.body {
-        if !HasAwaitVisitor::check(body) {
+    // Awaitless methods may only lose the async keyword
+    if let Some(body) = &mut func.body {
+        if handles_awaitless(body, &facts, config, hygiene) {
             func.is_async = false;
-            return;
+            return false;
+        }
+        if bails_on_for_await(&facts, config)
+            || bails_on_unsafe_scope(&facts, config)
+            || bails_on_super(&*body, config)
+        {
+            return false;
         }
     }
 
     let body = match func.body.take() {
         Some(b) => b,
-        None => return,
+        None => return false,
     };
 
-    let result = transform_method(body);
+    let result = transform_method(body, kind, name, config, hygiene);
 
     func.is_async = false;
-    // Keep original params - they are accessed via closure in the generator
     func.body = Some(block(result.stmts));
+    true
 }
 
-/// Apply transformation to an object method property.
-pub fn transform_object_method(method_prop: &mut MethodProp) {
-    if !method_prop.function.is_async {
-        return;
-    }
-
-    let func = &mut method_prop.function;
-
-    // Check if body contains await - if not, just remove async keyword
-    if let Some(body) = &func.body {
-        if !HasAwaitVisitor::check(body) {
-            func.is_async = false;
-            return;
-        }
-    }
+/// Apply transformation to a class method.
+pub fn transform_class_method(method: &mut ClassMethod, config: &Config, hygiene: Hygiene) -> bool {
+    let name = prop_name(&method.key);
+    let kind = FunctionKind::ClassMethod;
+    transform_method_function(&mut method.function, kind, name, config, hygiene)
+}
 
-    let body = match func.body.take() {
-        Some(b) => b,
-        None => return,
-    };
+/// Apply transformation to a private class method (`async #load() {}`).
+pub fn transform_private_method(
+    method: &mut PrivateMethod,
+    config: &Config,
+    hygiene: Hygiene,
+) -> bool {
+    let name = Some(&*method.key.name);
+    let kind = FunctionKind::ClassMethod;
+    transform_method_function(&mut method.function, kind, name, config, hygiene)
+}
 
-    let result = transform_method(body);
+/// Apply transformation to an object method property.
+pub fn transform_object_method(
+    method_prop: &mut MethodProp,
+    config: &Config,
+    hygiene: Hygiene,
+) -> bool {
+    let name = prop_name(&method_prop.key);
+    let kind = FunctionKind::ObjectMethod;
+    transform_method_function(&mut method_prop.function, kind, name, config, hygiene)
+}
 
-    func.is_async = false;
-    // Keep original params - they are accessed via closure in the generator
-    func.body = Some(block(result.stmts));
+/// Name of a method key, for identifier keys (`load() {}`).
+fn prop_name(key: &PropName) -> Option<&str> {
+    match key {
+        PropName::Ident(ident) => Some(&ident.sym),
+        _ => None,
+    }
 }
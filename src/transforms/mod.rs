@@ -1,9 +1,31 @@
 //! Transformation modules for different async function types.
 
-mod helpers;
+mod coverage;
 mod fn_decl;
 mod fn_expr;
+mod helpers;
+mod marker;
 pub mod method;
+mod regenerator;
+mod runtime;
+mod strict;
+mod tsc;
+mod unsupported;
+mod using;
 
+pub use coverage::tag_generated_functions;
 pub use fn_decl::transform_fn_decl;
-pub use fn_expr::{transform_arrow_fn, transform_fn_expr};
+pub use fn_expr::{
+    transform_arrow_fn, transform_fn_expr, transform_immediate_arrow, transform_immediate_fn_expr,
+    transform_trivial_arrow, trivial_arrow_facts, TransformedFn,
+};
+pub use helpers::{
+    generated_decl_kind, is_directive, runtime_wrapper, AlreadyTransformedVisitor,
+    AsyncCodeVisitor, CaptureIdents, LexicalCaptures, UsedNamesCollector,
+};
+pub use marker::mark_generated_code;
+pub use regenerator::{lower_generators, resolve_program};
+pub use runtime::{inject_runtime, inject_shared_wrapper, provides_runtime, runtime_module};
+pub use strict::strict_generated_functions;
+pub use tsc::transform_awaiter_function;
+pub use unsupported::{bails_on_nesting_depth, warn_unsupported_program};
@@ -0,0 +1,39 @@
+//! Lowering of the emitted generators for ES5 output.
+//!
+//! With `regenerator` set, every `function*` the transform produced (and any
+//! other generator in the file) is compiled to a regenerator state machine in
+//! the same plugin invocation. The regenerator runtime is inlined into the
+//! file, so the output needs nothing beyond `_ngAsyncToGenerator`.
+
+use swc_core::{
+    common::Mark,
+    ecma::{
+        ast::Program,
+        transforms::{
+            base::{
+                helpers::{inject_helpers, Helpers, HELPERS},
+                resolver,
+            },
+            compat::regenerator::regenerator,
+        },
+    },
+};
+
+/// Run `resolver` over a program that has not been through it, for
+/// [`lower_generators`], and return the mark of its unresolved references.
+pub fn resolve_program(program: &mut Program) -> Mark {
+    let unresolved_mark = Mark::new();
+    program.mutate(resolver(unresolved_mark, Mark::new(), false));
+    unresolved_mark
+}
+
+/// Compile the generators in `program` down to ES5.
+///
+/// `unresolved_mark` must be the mark `resolver` used for the program, since
+/// regenerator tells its own references apart from user bindings by it.
+pub fn lower_generators(program: &mut Program, unresolved_mark: Mark) {
+    HELPERS.set(&Helpers::new(false), || {
+        program.mutate(regenerator(Default::default(), unresolved_mark));
+        program.mutate(inject_helpers(unresolved_mark));
+    });
+}
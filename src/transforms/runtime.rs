@@ -0,0 +1,521 @@
+//! Making the runtime helper available to transformed files.
+//!
+//! With `importHelper` configured, a module that had async functions
+//! rewritten gets:
+//! ```javascript
+//! import { _ngAsyncToGenerator } from "@app/ng-async";
+//! ```
+//!
+//! And a script gets:
+//! ```javascript
+//! var _ngAsyncToGenerator = require("@app/ng-async")._ngAsyncToGenerator;
+//! ```
+//!
+//! With `inlineHelper`, the helper from `ngAsyncToGenerator.js` (and its
+//! `asyncGeneratorStep` stepper) is emitted instead.
+//!
+//! Files whose `using` declarations were lowered need `_ngUsingCtx` too,
+//! which is imported, required, inlined or checked alongside. tslib has no
+//! such helper, so with `compat: "tsc"` it is only imported from an
+//! `importHelper` module.
+//!
+//! With neither, `assertHelper` emits a check that the global helper exists:
+//! ```javascript
+//! if (typeof _ngAsyncToGenerator !== "function") {
+//!     throw new Error("`_ngAsyncToGenerator` is not defined: ...");
+//! }
+//! ```
+//!
+//! With `compat: "tsc"`, `__awaiter` is imported from tslib (or the
+//! `importHelper` module) the same way:
+//! ```javascript
+//! import { __awaiter } from "tslib";
+//! ```
+//!
+//! `importStyle` overrides the binding: `"cjs"` requires the helper in
+//! modules too, `"global"` imports nothing.
+//!
+//! With `sharedArrowWrapper`, the helper making the wrappers of async arrows
+//! is declared in every file that uses it, see [`inject_shared_wrapper`].
+//!
+//! Either way the statements go after the directive prologue, so
+//! `"use strict"` keeps applying to the file.
+//!
+//! [`runtime_module`] provides the same helper as a module of its own, for
+//! bundlers serving the module `importHelper` points to.
+
+use swc_core::{
+    common::{BytePos, Span, SyntaxContext, DUMMY_SP},
+    ecma::{
+        ast::*,
+        atoms::{atom, Atom},
+        parser::{EsSyntax, Parser, StringInput, Syntax},
+        visit::{noop_visit_mut_type, noop_visit_type, Visit, VisitMut, VisitMutWith, VisitWith},
+    },
+    quote,
+};
+
+use crate::ast_builders::{
+    call_expr, member_expr, shared_wrapper_decl, var_decl_with_kind, Hygiene, NG_ASYNC_WRAPPER,
+    NG_USING_CTX,
+};
+use crate::config::{Compat, Config, ImportHelper, ImportStyle, TSLIB};
+
+use super::helpers::{generated_decl_kind, is_directive};
+
+/// Source of the runtime helper emitted by `inlineHelper`.
+const RUNTIME_SOURCE: &str = include_str!("../../ngAsyncToGenerator.js");
+
+/// Name of the stepper function defined by the runtime helper.
+const RUNTIME_STEPPER: &str = "asyncGeneratorStep";
+
+/// Whether the program already declares the runtime helper at its top level,
+/// e.g. through an earlier import or inline copy.
+pub fn provides_runtime(program: &Program, config: &Config) -> bool {
+    declares_global(program, config.helper_name())
+}
+
+/// Whether the program declares `name` at its top level.
+fn declares_global(program: &Program, name: &str) -> bool {
+    let declares = |decl: &Decl| match decl {
+        Decl::Fn(fn_decl) => &*fn_decl.ident.sym == name,
+        Decl::Var(var) => var
+            .decls
+            .iter()
+            .any(|decl| matches!(&decl.name, Pat::Ident(binding) if &*binding.id.sym == name)),
+        _ => false,
+    };
+
+    match program {
+        Program::Module(module) => module.body.iter().any(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                import.specifiers.iter().any(|specifier| {
+                    let local = match specifier {
+                        ImportSpecifier::Named(named) => &named.local,
+                        ImportSpecifier::Default(default) => &default.local,
+                        ImportSpecifier::Namespace(namespace) => &namespace.local,
+                    };
+                    &*local.sym == name
+                })
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => declares(&export.decl),
+            ModuleItem::Stmt(Stmt::Decl(decl)) => declares(decl),
+            _ => false,
+        }),
+        Program::Script(script) => script
+            .body
+            .iter()
+            .any(|stmt| matches!(stmt, Stmt::Decl(decl) if declares(decl))),
+    }
+}
+
+/// Insert whatever the configuration asks for to provide the runtime helper
+/// (unless `wrapper` is false, as the program provides it already) and the
+/// `using` helper, if the program calls it.
+///
+/// Returns whether anything was inserted, or an error if the bundled helper
+/// could not be parsed.
+pub fn inject_runtime(
+    program: &mut Program,
+    config: &Config,
+    wrapper: bool,
+    hygiene: Hygiene,
+) -> Result<bool, &'static str> {
+    let helpers = RuntimeHelpers {
+        wrapper,
+        using: needs_using_helper(program, config, hygiene),
+    };
+    if !helpers.wrapper && !helpers.using {
+        return Ok(false);
+    }
+
+    // Scripts cannot import, whatever the style
+    let style = match (config.import_style, &*program) {
+        (Some(ImportStyle::Global), _) => ImportStyle::Global,
+        (Some(ImportStyle::Cjs), _) | (_, Program::Script(_)) => ImportStyle::Cjs,
+        (_, Program::Module(_)) => ImportStyle::Esm,
+    };
+    let import = helper_source(config).filter(|_| style != ImportStyle::Global);
+    match program {
+        Program::Module(module) => {
+            let items: Vec<ModuleItem> = if config.inline_helper {
+                inline_runtime(config, helpers, hygiene)?
+                    .into_iter()
+                    .map(ModuleItem::Stmt)
+                    .collect()
+            } else if let Some(import) = &import {
+                match style {
+                    ImportStyle::Esm => helper_import(import, config, helpers, hygiene)
+                        .into_iter()
+                        .collect(),
+                    _ => helper_require(import, config, helpers, hygiene)
+                        .into_iter()
+                        .map(ModuleItem::Stmt)
+                        .collect(),
+                }
+            } else if config.assert_helper {
+                helper_guards(config, helpers, hygiene)
+                    .into_iter()
+                    .map(ModuleItem::Stmt)
+                    .collect()
+            } else {
+                return Ok(false);
+            };
+
+            let pos = module
+                .body
+                .iter()
+                .take_while(|item| matches!(item, ModuleItem::Stmt(stmt) if is_directive(stmt)))
+                .count();
+            module.body.splice(pos..pos, items);
+        }
+        Program::Script(script) => {
+            let stmts = if config.inline_helper {
+                inline_runtime(config, helpers, hygiene)?
+            } else if let Some(import) = &import {
+                helper_require(import, config, helpers, hygiene)
+            } else if config.assert_helper {
+                helper_guards(config, helpers, hygiene)
+            } else {
+                return Ok(false);
+            };
+
+            let pos = script
+                .body
+                .iter()
+                .take_while(|stmt| is_directive(stmt))
+                .count();
+            script.body.splice(pos..pos, stmts);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Which runtime helpers a program needs.
+#[derive(Debug, Clone, Copy)]
+struct RuntimeHelpers {
+    /// The helper wrapping generators, `_ngAsyncToGenerator`.
+    wrapper: bool,
+    /// The disposal context of lowered `using` declarations, `_ngUsingCtx`.
+    using: bool,
+}
+
+impl RuntimeHelpers {
+    /// Every helper, as the runtime module provides them.
+    const ALL: Self = Self {
+        wrapper: true,
+        using: true,
+    };
+}
+
+/// Whether `program` refers to the `using` helper without declaring it.
+fn needs_using_helper(program: &Program, config: &Config, hygiene: Hygiene) -> bool {
+    let name = config.using_helper_name.as_str();
+    if declares_global(program, name) {
+        return false;
+    }
+    let mut finder = GlobalFinder {
+        name,
+        ctxt: hygiene.unresolved,
+        found: false,
+    };
+    program.visit_with(&mut finder);
+    finder.found
+}
+
+/// Visitor looking for a reference to a global.
+struct GlobalFinder<'a> {
+    name: &'a str,
+    ctxt: SyntaxContext,
+    found: bool,
+}
+
+impl Visit for GlobalFinder<'_> {
+    noop_visit_type!();
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.found |= ident.ctxt == self.ctxt && &*ident.sym == self.name;
+    }
+}
+
+/// Declare the helper of `sharedArrowWrapper` after the directive prologue:
+/// ```javascript
+/// function _ngWrap(ref, self) {
+///     return function () {
+///         return ref.apply(self, arguments);
+///     };
+/// }
+/// ```
+pub fn inject_shared_wrapper(program: &mut Program, hygiene: Hygiene) {
+    let decl = shared_wrapper_decl(hygiene);
+    match program {
+        Program::Module(module) => {
+            let pos = module
+                .body
+                .iter()
+                .take_while(|item| matches!(item, ModuleItem::Stmt(stmt) if is_directive(stmt)))
+                .count();
+            module.body.insert(pos, ModuleItem::Stmt(decl));
+        }
+        Program::Script(script) => {
+            let pos = script
+                .body
+                .iter()
+                .take_while(|stmt| is_directive(stmt))
+                .count();
+            script.body.insert(pos, decl);
+        }
+    }
+}
+
+/// The runtime helper as an ES module exporting it under the name
+/// `importHelper` imports (`wrapperFunctionName` by default):
+///
+/// ```javascript
+/// function asyncGeneratorStep(gen, resolve, reject, _next, _throw, key, arg) { ... }
+/// function _ngAsyncToGenerator(fn, getZone) { ... }
+/// function _ngUsingCtx() { ... }
+/// export { _ngAsyncToGenerator, _ngUsingCtx };
+/// ```
+///
+/// This is the helper `inlineHelper` emits, so the module always matches
+/// the code generated by this version of the plugin.
+pub fn runtime_module(config: &Config) -> Module {
+    let hygiene = Hygiene::default();
+    let mut body: Vec<ModuleItem> = inline_runtime(config, RuntimeHelpers::ALL, hygiene)
+        .expect("the bundled runtime helper parses")
+        .into_iter()
+        .map(ModuleItem::Stmt)
+        .collect();
+
+    let name = match &config.import_helper {
+        Some(import) => exported_name(import, config),
+        None => config.wrapper_function_name.as_str(),
+    };
+    let specifiers = [
+        (config.wrapper_function_name.as_str(), name),
+        (
+            config.using_helper_name.as_str(),
+            config.using_helper_name.as_str(),
+        ),
+    ]
+    .into_iter()
+    .map(|(local, name)| {
+        ExportSpecifier::Named(ExportNamedSpecifier {
+            span: DUMMY_SP,
+            orig: ModuleExportName::Ident(hygiene.global_ident(local)),
+            exported: (name != local).then(|| ModuleExportName::Ident(hygiene.global_ident(name))),
+            is_type_only: false,
+        })
+    })
+    .collect();
+    body.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
+        NamedExport {
+            span: DUMMY_SP,
+            specifiers,
+            src: None,
+            type_only: false,
+            with: None,
+        },
+    )));
+
+    Module {
+        span: DUMMY_SP,
+        body,
+        shebang: None,
+    }
+}
+
+/// Module the helper is imported from: `importHelper`, or tslib with
+/// `compat: "tsc"`.
+fn helper_source(config: &Config) -> Option<ImportHelper> {
+    match (&config.import_helper, config.compat) {
+        (Some(import), _) => Some(import.clone()),
+        (None, Compat::Tsc) => Some(ImportHelper {
+            module: TSLIB.to_string(),
+            name: None,
+        }),
+        (None, Compat::NgAsync) => None,
+    }
+}
+
+/// Exported name of the helper in the runtime module.
+fn exported_name<'a>(import: &'a ImportHelper, config: &'a Config) -> &'a str {
+    import.name.as_deref().unwrap_or(config.helper_name())
+}
+
+/// Local and imported names of the `helpers` the module at `import`
+/// provides. tslib has no `using` helper.
+fn imported_helpers<'a>(
+    import: &'a ImportHelper,
+    config: &'a Config,
+    helpers: RuntimeHelpers,
+) -> Vec<(&'a str, &'a str)> {
+    let mut names = vec![];
+    if helpers.wrapper {
+        names.push((config.helper_name(), exported_name(import, config)));
+    }
+    if helpers.using && import.module != TSLIB {
+        names.push((
+            config.using_helper_name.as_str(),
+            config.using_helper_name.as_str(),
+        ));
+    }
+    names
+}
+
+/// Create: `import { name as _ngAsyncToGenerator, _ngUsingCtx } from "module";`
+fn helper_import(
+    import: &ImportHelper,
+    config: &Config,
+    helpers: RuntimeHelpers,
+    hygiene: Hygiene,
+) -> Option<ModuleItem> {
+    let specifiers: Vec<ImportSpecifier> = imported_helpers(import, config, helpers)
+        .into_iter()
+        .map(|(local, name)| {
+            ImportSpecifier::Named(ImportNamedSpecifier {
+                span: DUMMY_SP,
+                local: hygiene.global_ident(local),
+                imported: (name != local)
+                    .then(|| ModuleExportName::Ident(hygiene.global_ident(name))),
+                is_type_only: false,
+            })
+        })
+        .collect();
+    if specifiers.is_empty() {
+        return None;
+    }
+
+    Some(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+        span: DUMMY_SP,
+        specifiers,
+        src: Box::new(import.module.as_str().into()),
+        type_only: false,
+        with: None,
+        phase: Default::default(),
+    })))
+}
+
+/// Create: `var _ngAsyncToGenerator = require("module").name;`, and the
+/// same for `_ngUsingCtx`.
+fn helper_require(
+    import: &ImportHelper,
+    config: &Config,
+    helpers: RuntimeHelpers,
+    hygiene: Hygiene,
+) -> Vec<Stmt> {
+    imported_helpers(import, config, helpers)
+        .into_iter()
+        .map(|(local, name)| {
+            let module = Expr::Lit(Lit::Str(import.module.as_str().into()));
+            let required = call_expr(
+                Expr::Ident(hygiene.global_ident(atom!("require"))),
+                vec![module],
+            );
+            var_decl_with_kind(
+                generated_decl_kind(config, false),
+                hygiene.global_ident(local),
+                member_expr(required, name),
+            )
+        })
+        .collect()
+}
+
+/// Create the checks of `assertHelper` that the global `helpers` exist.
+fn helper_guards(config: &Config, helpers: RuntimeHelpers, hygiene: Hygiene) -> Vec<Stmt> {
+    let mut guards = vec![];
+    if helpers.wrapper {
+        guards.push(helper_guard(&config.wrapper_function_name, hygiene));
+    }
+    if helpers.using {
+        guards.push(helper_guard(&config.using_helper_name, hygiene));
+    }
+    guards
+}
+
+/// Create the check of `assertHelper` that the global helper exists:
+/// `if (typeof _ngAsyncToGenerator !== "function") { throw new Error("..."); }`
+fn helper_guard(helper: &str, hygiene: Hygiene) -> Stmt {
+    let message = format!(
+        "`{}` is not defined: the async functions of this file were compiled by \
+         swc-plugin-transform-async-to-ng-generator and need its runtime helper. Load \
+         `ngAsyncToGenerator.js` before this file, or set the `importHelper` or \
+         `inlineHelper` option of the plugin.",
+        helper
+    );
+    quote!(
+        "if (typeof $helper !== \"function\") {
+            throw new $error($message);
+        }" as Stmt,
+        helper = hygiene.global_ident(helper),
+        error = hygiene.global_ident(atom!("Error")),
+        message: Expr = Expr::Lit(Lit::Str(message.as_str().into()))
+    )
+}
+
+/// Parse the runtime helper and adapt it to the file it is emitted into,
+/// keeping the `helpers` the file needs.
+fn inline_runtime(
+    config: &Config,
+    helpers: RuntimeHelpers,
+    hygiene: Hygiene,
+) -> Result<Vec<Stmt>, &'static str> {
+    let input = StringInput::new(
+        RUNTIME_SOURCE,
+        BytePos::DUMMY,
+        BytePos(RUNTIME_SOURCE.len() as u32),
+    );
+    let mut script = Parser::new(Syntax::Es(EsSyntax::default()), input, None)
+        .parse_script()
+        .map_err(|_| "the bundled runtime helper failed to parse")?;
+
+    // The wrapper and its stepper come as a pair
+    script.body.retain(|stmt| match stmt {
+        Stmt::Decl(Decl::Fn(function)) if &*function.ident.sym == NG_USING_CTX => helpers.using,
+        Stmt::Decl(Decl::Fn(_)) => helpers.wrapper,
+        _ => true,
+    });
+    script.visit_mut_with(&mut RuntimeAdapter {
+        wrapper: Atom::from(config.wrapper_function_name.as_str()),
+        using: Atom::from(config.using_helper_name.as_str()),
+        hygiene,
+    });
+    Ok(script.body)
+}
+
+/// Visitor that adapts the parsed runtime helper to the current file.
+///
+/// - Spans are dropped, so the helper never picks up comments that belong
+///   to positions in the user's file.
+/// - The helpers are renamed to `wrapperFunctionName` and `usingHelperName`
+///   and given the context of the references the transform emitted.
+/// - The stepper is minted as a generated binding, so `hygiene` renames it
+///   if the file declares its own `asyncGeneratorStep`.
+struct RuntimeAdapter {
+    wrapper: Atom,
+    using: Atom,
+    hygiene: Hygiene,
+}
+
+impl VisitMut for RuntimeAdapter {
+    noop_visit_mut_type!();
+
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        *span = DUMMY_SP;
+    }
+
+    fn visit_mut_ident(&mut self, ident: &mut Ident) {
+        ident.span = DUMMY_SP;
+        if &*ident.sym == NG_ASYNC_WRAPPER {
+            ident.sym = self.wrapper.clone();
+            ident.ctxt = self.hygiene.unresolved;
+        } else if &*ident.sym == NG_USING_CTX {
+            ident.sym = self.using.clone();
+            ident.ctxt = self.hygiene.unresolved;
+        } else if &*ident.sym == RUNTIME_STEPPER {
+            ident.ctxt = self.hygiene.generated;
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! `"use strict"` for generated functions.
+//!
+//! With `strictGeneratedCode`, a script whose helpers run in sloppy mode
+//! gets them started with a directive:
+//!
+//! ```javascript
+//! class Store {
+//!     load() {
+//!         return _ref.apply(this, arguments);
+//!     }
+//! }
+//! var _ref = _ngAsyncToGenerator(function* () {
+//!     "use strict";
+//!     return yield this.fetch();
+//! });
+//! ```
+//!
+//! Functions nested in a strict one, in a class or in a `"use strict"`
+//! script are strict already, and modules are strict throughout, so they
+//! are left as they are.
+
+use std::mem;
+
+use swc_core::{
+    common::DUMMY_SP,
+    ecma::{
+        ast::*,
+        visit::{noop_visit_mut_type, VisitMut, VisitMutWith},
+    },
+};
+
+use super::coverage::is_original;
+
+/// Insert `"use strict";` at the top of every generated function of
+/// `program` that would run in sloppy mode.
+pub fn strict_generated_functions(program: &mut Program) {
+    let Program::Script(script) = program else {
+        return;
+    };
+    script.visit_mut_with(&mut StrictVisitor {
+        strict: has_use_strict(&script.body),
+    });
+}
+
+/// Visitor that tracks strict mode and adds the directive to generated
+/// functions outside of it.
+struct StrictVisitor {
+    /// Whether the current node is in strict mode code.
+    strict: bool,
+}
+
+impl VisitMut for StrictVisitor {
+    noop_visit_mut_type!();
+
+    fn visit_mut_class(&mut self, class: &mut Class) {
+        let outer = mem::replace(&mut self.strict, true);
+        class.visit_mut_children_with(self);
+        self.strict = outer;
+    }
+
+    fn visit_mut_function(&mut self, function: &mut Function) {
+        let outer = self.strict;
+        // A directive makes non-simple params a syntax error
+        let can_hold_directive = function
+            .params
+            .iter()
+            .all(|param| matches!(param.pat, Pat::Ident(_)));
+        let generated = is_generated(function);
+        if let Some(body) = &mut function.body {
+            if has_use_strict(&body.stmts) {
+                self.strict = true;
+            } else if !self.strict && generated && can_hold_directive {
+                body.stmts.insert(0, use_strict());
+                self.strict = true;
+            }
+        }
+        function.visit_mut_children_with(self);
+        self.strict = outer;
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        let outer = self.strict;
+        if let BlockStmtOrExpr::BlockStmt(body) = &*arrow.body {
+            self.strict |= has_use_strict(&body.stmts);
+        }
+        arrow.visit_mut_children_with(self);
+        self.strict = outer;
+    }
+}
+
+/// Whether the transform created `function`. With `coverage`, generators
+/// take the span of the body they run (see `generator_fn_expr`), which no
+/// original function has.
+fn is_generated(function: &Function) -> bool {
+    !is_original(function.span)
+        || function
+            .body
+            .as_ref()
+            .is_some_and(|body| body.span == function.span)
+}
+
+/// Whether the directive prologue of `stmts` contains `"use strict"`.
+///
+/// Like the spec, only directives written without escapes count.
+fn has_use_strict(stmts: &[Stmt]) -> bool {
+    stmts
+        .iter()
+        .map_while(|stmt| match stmt {
+            Stmt::Expr(ExprStmt { expr, .. }) => match &**expr {
+                Expr::Lit(Lit::Str(str)) => Some(str),
+                _ => None,
+            },
+            _ => None,
+        })
+        .any(|str| matches!(str.raw.as_deref(), Some("\"use strict\"" | "'use strict'")))
+}
+
+/// Create: `"use strict";`
+fn use_strict() -> Stmt {
+    Stmt::Expr(ExprStmt {
+        span: DUMMY_SP,
+        expr: Box::new(Expr::Lit(Lit::Str("use strict".into()))),
+    })
+}
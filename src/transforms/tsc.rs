@@ -0,0 +1,120 @@
+//! Transformation for `compat: "tsc"`, the downlevel TypeScript emits for
+//! ES2015+ targets with tslib.
+//!
+//! Transforms:
+//! ```javascript
+//! async function load(id) {
+//!     return await this.fetch(id, arguments);
+//! }
+//! const refresh = async () => await load(1);
+//! ```
+//!
+//! Into:
+//! ```javascript
+//! function load(id) {
+//!     return __awaiter(this, arguments, void 0, function* () {
+//!         return yield this.fetch(id, arguments);
+//!     });
+//! }
+//! const refresh = () => __awaiter(this, void 0, void 0, function* () {
+//!     return yield load(1);
+//! });
+//! ```
+//!
+//! The function keeps its place and params; `__awaiter` runs the generator
+//! with its `this`, and with its `arguments` when the body reads them, so
+//! nothing is hoisted or captured. Methods and function expressions get the
+//! same body.
+
+use swc_core::{
+    common::util::take::Take,
+    ecma::{ast::*, atoms::atom},
+    quote,
+};
+
+use crate::ast_builders::{block, function_expr, return_stmt, Hygiene};
+use crate::config::{Config, TSLIB_AWAITER};
+
+use super::fn_expr::{is_transformable_arrow, take_arrow_body};
+use super::helpers::{
+    bails_on_unsafe_scope, create_generator_function, directive_prologue, handles_awaitless,
+    BodyFacts, GeneratorBodyVisitor,
+};
+use super::unsupported::{bails_on_async_generator, bails_on_for_await, bails_on_super};
+
+/// Rewrite an async function, method or function expression in place:
+/// `{ return __awaiter(this, void 0, void 0, function* () { ... }); }`.
+///
+/// Returns whether the body was rewritten to call `__awaiter`.
+pub fn transform_awaiter_function(func: &mut Function, config: &Config, hygiene: Hygiene) -> bool {
+    if !func.is_async || bails_on_async_generator(func, config) {
+        return false;
+    }
+    let Some(body) = &mut func.body else {
+        return false;
+    };
+
+    let facts = GeneratorBodyVisitor::inspect(&*body);
+
+    // Awaitless functions may only lose the async keyword
+    if handles_awaitless(body, &facts, config, hygiene) {
+        func.is_async = false;
+        return false;
+    }
+    if bails_on_for_await(&facts, config)
+        || bails_on_unsafe_scope(&facts, config)
+        || bails_on_super(&*body, config)
+    {
+        return false;
+    }
+
+    let body = body.take();
+    // Directives stay in effect for the function itself
+    let mut stmts = directive_prologue(&body);
+    stmts.push(return_stmt(awaiter_call(body, &facts, config, hygiene)));
+    func.is_async = false;
+    func.body = Some(block(stmts));
+    true
+}
+
+/// Rewrite an async arrow in place:
+/// `() => __awaiter(this, void 0, void 0, function* () { ... })`.
+///
+/// Returns whether the body was rewritten to call `__awaiter`.
+pub fn transform_awaiter_arrow(arrow: &mut ArrowExpr, config: &Config, hygiene: Hygiene) -> bool {
+    let facts = GeneratorBodyVisitor::inspect(&*arrow.body);
+    if !is_transformable_arrow(arrow, &facts, config, hygiene) {
+        return false;
+    }
+
+    let body = take_arrow_body(&mut arrow.body);
+    let prologue = directive_prologue(&body);
+    let call = awaiter_call(body, &facts, config, hygiene);
+    *arrow.body = if prologue.is_empty() {
+        BlockStmtOrExpr::Expr(Box::new(call))
+    } else {
+        let mut stmts = prologue;
+        stmts.push(return_stmt(call));
+        BlockStmtOrExpr::BlockStmt(block(stmts))
+    };
+    arrow.is_async = false;
+    true
+}
+
+/// Create `__awaiter(this, arguments, void 0, function* () { ... })` running
+/// `body`, passing `arguments` only when the body reads them according to
+/// its `facts`.
+fn awaiter_call(body: BlockStmt, facts: &BodyFacts, config: &Config, hygiene: Hygiene) -> Expr {
+    let arguments = if facts.arguments {
+        Expr::Ident(hygiene.global_ident(atom!("arguments")))
+    } else {
+        quote!("void 0" as Expr)
+    };
+    let (generator, _) = create_generator_function(vec![], body, None, config, hygiene);
+    quote!(
+        "$awaiter(this, $arguments, void 0, $generator)" as Expr,
+        awaiter = hygiene.global_ident(TSLIB_AWAITER),
+        arguments: Expr = arguments,
+        generator: Expr = function_expr(None, generator)
+    )
+}
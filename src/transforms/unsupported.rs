@@ -0,0 +1,280 @@
+//! Constructs the transform cannot handle, reported as warnings (or errors
+//! with `errorOnUnsupported`).
+//!
+//! - Async generators (`async function*`), async functions using `super`
+//!   and async functions with a `for await` loop are left native, since the
+//!   generator wrapper cannot express them.
+//! - Top-level `await` is outside any async function, so it stays native and
+//!   its promise is not a `$q` one.
+//! - Local bindings named like the runtime helper would capture the calls
+//!   generated in their scope.
+//! - Files nesting deeper than `maxNestingDepth` could overflow the stack of
+//!   the recursive visitors, so they are left untransformed.
+
+use swc_core::{
+    common::{Span, Spanned},
+    ecma::{
+        ast::*,
+        visit::{noop_visit_type, Visit, VisitWith},
+    },
+};
+
+use super::helpers::BodyFacts;
+use crate::config::Config;
+use crate::diagnostics;
+
+/// Whether the transform must leave an async generator native: it would
+/// need an async iterator rather than a promise. Emits a warning when it
+/// does.
+pub fn bails_on_async_generator(function: &Function, config: &Config) -> bool {
+    if function.is_generator {
+        diagnostics::unsupported(
+            function.span,
+            "async generator left untransformed: the runtime helper only wraps async functions",
+            config,
+        );
+    }
+    function.is_generator
+}
+
+/// Whether the transform must leave a function native because its body has
+/// a `for await` loop, according to its `facts`: `yield` cannot wait for
+/// an async iterator, and the loop is a syntax error in the plain
+/// `function*` (or, for awaitless handling, the plain function) the body
+/// would move into. Emits a warning when it does.
+pub fn bails_on_for_await(facts: &BodyFacts, config: &Config) -> bool {
+    match facts.for_await {
+        Some(span) => {
+            diagnostics::unsupported(
+                span,
+                "async function left untransformed: the generator wrapper cannot run a \
+                 `for await` loop",
+                config,
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// Whether the transform must leave a method or arrow native because its
+/// body (or a nested arrow) uses `super`, which is a syntax error in the
+/// plain `function*` the body would move into. Emits a warning when it does.
+///
+/// Awaitless functions are checked first, since their handling keeps
+/// `super` in an arrow.
+pub fn bails_on_super<N: VisitWith<SuperVisitor>>(body: &N, config: &Config) -> bool {
+    let mut visitor = SuperVisitor { found: None };
+    body.visit_with(&mut visitor);
+    match visitor.found {
+        Some(span) => {
+            diagnostics::unsupported(
+                span,
+                "async function left untransformed: `super` cannot be used inside the \
+                 generator function the body would move into",
+                config,
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// Warn about the constructs of `program` that no function-level check
+/// sees: top-level `await` and local bindings shadowing the runtime helper.
+pub fn warn_unsupported_program(program: &Program, config: &Config) {
+    if let Program::Module(module) = program {
+        let mut visitor = TopLevelAwaitVisitor { found: None };
+        module.visit_with(&mut visitor);
+        if let Some(span) = visitor.found {
+            diagnostics::unsupported(
+                span,
+                "top-level `await` is left as is: it needs native support and settles \
+                 outside the $q digest cycle",
+                config,
+            );
+        }
+    }
+
+    let mut visitor = ShadowedHelperVisitor {
+        helpers: [
+            config.wrapper_function_name.as_str(),
+            config.using_helper_name.as_str(),
+        ],
+        depth: 0,
+    };
+    program.visit_with(&mut visitor);
+}
+
+/// Whether the transform must leave `program` untransformed because it nests
+/// expressions and statements deeper than `maxNestingDepth`. Emits a warning
+/// at the first node past the limit when it does.
+///
+/// The check itself stops descending at the limit, so it cannot overflow.
+pub fn bails_on_nesting_depth(program: &Program, config: &Config) -> bool {
+    let mut visitor = NestingDepthVisitor {
+        limit: config.max_nesting_depth,
+        depth: 0,
+        found: None,
+    };
+    program.visit_with(&mut visitor);
+    match visitor.found {
+        Some(span) => {
+            diagnostics::unsupported(
+                span,
+                &format!(
+                    "file left untransformed: code nested deeper than {} levels (raise \
+                     `maxNestingDepth` to transform it)",
+                    config.max_nesting_depth
+                ),
+                config,
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// Visitor that finds the first expression or statement nested deeper than
+/// `limit`.
+struct NestingDepthVisitor {
+    limit: usize,
+    depth: usize,
+    found: Option<Span>,
+}
+
+impl NestingDepthVisitor {
+    fn nested<N: VisitWith<Self> + Spanned>(&mut self, node: &N) {
+        if self.found.is_some() {
+            return;
+        }
+        if self.depth == self.limit {
+            self.found = Some(node.span());
+            return;
+        }
+        self.depth += 1;
+        node.visit_children_with(self);
+        self.depth -= 1;
+    }
+}
+
+impl Visit for NestingDepthVisitor {
+    noop_visit_type!();
+
+    fn visit_expr(&mut self, node: &Expr) {
+        self.nested(node);
+    }
+
+    fn visit_stmt(&mut self, node: &Stmt) {
+        self.nested(node);
+    }
+}
+
+/// Visitor that finds the first `super` of a function body. Nested arrows
+/// share the body's `super` and are searched; nested functions and classes
+/// have their own and are not.
+pub struct SuperVisitor {
+    found: Option<Span>,
+}
+
+impl Visit for SuperVisitor {
+    noop_visit_type!();
+
+    fn visit_super(&mut self, node: &Super) {
+        if self.found.is_none() {
+            self.found = Some(node.span);
+        }
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_class(&mut self, _: &Class) {}
+}
+
+/// Visitor that finds the first `await` (or `for await`) outside functions.
+struct TopLevelAwaitVisitor {
+    found: Option<Span>,
+}
+
+impl Visit for TopLevelAwaitVisitor {
+    noop_visit_type!();
+
+    fn visit_await_expr(&mut self, node: &AwaitExpr) {
+        if self.found.is_none() {
+            self.found = Some(node.span);
+        }
+    }
+
+    fn visit_for_of_stmt(&mut self, node: &ForOfStmt) {
+        if node.is_await && self.found.is_none() {
+            self.found = Some(node.span);
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+    fn visit_class(&mut self, _: &Class) {}
+}
+
+/// Visitor that warns about bindings named like a runtime helper below the
+/// top level, where they shadow the helper for the code generated in their
+/// scope. Top-level declarations provide the helper instead.
+struct ShadowedHelperVisitor<'a> {
+    helpers: [&'a str; 2],
+    depth: usize,
+}
+
+impl ShadowedHelperVisitor<'_> {
+    fn check(&self, ident: &Ident) {
+        if self.depth > 0 && self.helpers.contains(&&*ident.sym) {
+            diagnostics::warn(
+                ident.span,
+                &format!(
+                    "`{}` shadows the runtime helper called by the async functions \
+                     transformed in this scope",
+                    ident.sym
+                ),
+            );
+        }
+    }
+
+    fn nested<N: VisitWith<Self>>(&mut self, node: &N) {
+        self.depth += 1;
+        node.visit_children_with(self);
+        self.depth -= 1;
+    }
+}
+
+impl Visit for ShadowedHelperVisitor<'_> {
+    noop_visit_type!();
+
+    fn visit_binding_ident(&mut self, node: &BindingIdent) {
+        self.check(&node.id);
+    }
+
+    fn visit_fn_decl(&mut self, node: &FnDecl) {
+        self.check(&node.ident);
+        node.function.visit_with(self);
+    }
+
+    fn visit_class_decl(&mut self, node: &ClassDecl) {
+        self.check(&node.ident);
+        node.class.visit_with(self);
+    }
+
+    fn visit_function(&mut self, node: &Function) {
+        self.nested(node);
+    }
+
+    fn visit_arrow_expr(&mut self, node: &ArrowExpr) {
+        self.nested(node);
+    }
+
+    fn visit_block_stmt(&mut self, node: &BlockStmt) {
+        self.nested(node);
+    }
+
+    fn visit_class(&mut self, node: &Class) {
+        self.nested(node);
+    }
+}
@@ -0,0 +1,237 @@
+//! Lowering for `using` / `await using` declarations inside async bodies.
+//!
+//! Transforms:
+//! ```javascript
+//! async function run() {
+//!     await using conn = await open();
+//!     return query(conn);
+//! }
+//! ```
+//!
+//! Into (before the await-to-yield rewrite):
+//! ```javascript
+//! async function run() {
+//!     const _usingCtx = _ngUsingCtx();
+//!     try {
+//!         const conn = _usingCtx.a(await open());
+//!         return query(conn);
+//!     } catch (_) {
+//!         _usingCtx.e = _;
+//!     } finally {
+//!         await _usingCtx.d();
+//!     }
+//! }
+//! ```
+//!
+//! A `using` loop head gets a fresh context per iteration: the loop binds a
+//! temporary and its body declares the resource from it, so
+//! `for (using file of files) read(file);` becomes
+//! ```javascript
+//! for (const _value of files) {
+//!     const _usingCtx = _ngUsingCtx();
+//!     try {
+//!         const file = _usingCtx.u(_value);
+//!         read(file);
+//!     } catch (_) {
+//!         _usingCtx.e = _;
+//!     } finally {
+//!         _usingCtx.d();
+//!     }
+//! }
+//! ```
+//!
+//! The runtime helper follows Babel's `usingCtx` contract: `u(value)` registers
+//! a sync disposable, `a(value)` an async one, `e` records the pending error and
+//! `d()` disposes everything in reverse order, returning a promise when any
+//! async resource was registered.
+
+use std::mem;
+
+use swc_core::{
+    common::{SyntaxContext, DUMMY_SP},
+    ecma::{
+        ast::*,
+        atoms::atom,
+        visit::{noop_visit_mut_type, VisitMut, VisitMutWith},
+    },
+};
+
+use crate::ast_builders::{
+    binding_ident, block, call_expr, expr_stmt, member_expr, var_decl_with_kind, Hygiene,
+};
+
+/// Name of the per-block disposal context variable.
+const CTX_NAME: &str = "_usingCtx";
+
+/// Name of the loop binding a `using` loop head is declared from.
+const VALUE_NAME: &str = "_value";
+
+/// Visitor that rewrites `using` declarations into try/catch/finally blocks.
+///
+/// Each statement list containing a `using` declaration gets its own
+/// block-scoped context, so nested blocks never share disposal stacks.
+/// It does not descend into nested functions; they are lowered when they
+/// are transformed themselves.
+pub struct UsingLoweringVisitor<'a> {
+    /// Runtime helper that creates the disposal context.
+    helper: &'a str,
+    /// Contexts for the generated `_usingCtx` and catch bindings.
+    hygiene: Hygiene,
+}
+
+impl<'a> UsingLoweringVisitor<'a> {
+    pub fn new(helper: &'a str, hygiene: Hygiene) -> Self {
+        Self { helper, hygiene }
+    }
+
+    /// Create the context and the try statement disposing it after `guarded`.
+    fn guard(&self, guarded: Vec<Stmt>, has_async: bool) -> [Stmt; 2] {
+        [
+            // const _usingCtx = _ngUsingCtx();
+            var_decl_with_kind(
+                VarDeclKind::Const,
+                self.hygiene.private_ident(CTX_NAME),
+                call_expr(Expr::Ident(self.hygiene.global_ident(self.helper)), vec![]),
+            ),
+            guarded_try(guarded, has_async, self.hygiene),
+        ]
+    }
+}
+
+impl VisitMut for UsingLoweringVisitor<'_> {
+    noop_visit_mut_type!();
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        // Lower nested blocks first
+        stmts.visit_mut_children_with(self);
+
+        let first = match stmts
+            .iter()
+            .position(|stmt| matches!(stmt, Stmt::Decl(Decl::Using(_))))
+        {
+            Some(i) => i,
+            None => return,
+        };
+
+        // Everything from the first `using` onwards is guarded by the context
+        let mut guarded: Vec<Stmt> = stmts.drain(first..).collect();
+        let mut has_async = false;
+        for stmt in guarded.iter_mut() {
+            if let Stmt::Decl(Decl::Using(using)) = stmt {
+                has_async |= using.is_await;
+                let lowered = lower_using_decl(using, self.hygiene);
+                *stmt = lowered;
+            }
+        }
+
+        stmts.extend(self.guard(guarded, has_async));
+    }
+
+    fn visit_mut_for_of_stmt(&mut self, stmt: &mut ForOfStmt) {
+        stmt.visit_mut_children_with(self);
+
+        let ForHead::UsingDecl(using) = &mut stmt.left else {
+            return;
+        };
+
+        // for (using x of y) -> for (const _value of y) + const x = _usingCtx.u(_value)
+        let value = self.hygiene.private_ident(VALUE_NAME);
+        for decl in using.decls.iter_mut() {
+            decl.init = Some(Box::new(Expr::Ident(value.clone())));
+        }
+        let has_async = using.is_await;
+        let declare = lower_using_decl(using, self.hygiene);
+
+        // The body stays a statement of its own so its declarations keep
+        // their scope
+        let body = mem::replace(&mut *stmt.body, Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+        stmt.left = ForHead::VarDecl(Box::new(VarDecl {
+            span: DUMMY_SP,
+            ctxt: SyntaxContext::empty(),
+            kind: VarDeclKind::Const,
+            declare: false,
+            decls: vec![VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(binding_ident(value)),
+                init: None,
+                definite: false,
+            }],
+        }));
+        *stmt.body = Stmt::Block(block(self.guard(vec![declare, body], has_async).into()));
+    }
+
+    // Don't descend into nested functions - they are lowered on their own
+    fn visit_mut_function(&mut self, _: &mut Function) {}
+    fn visit_mut_arrow_expr(&mut self, _: &mut ArrowExpr) {}
+}
+
+/// Turn `[await] using x = init` into `const x = _usingCtx.a(init)`
+/// (or `.u(init)` for sync disposal).
+fn lower_using_decl(using: &mut UsingDecl, hygiene: Hygiene) -> Stmt {
+    let register = if using.is_await { "a" } else { "u" };
+
+    let decls = using
+        .decls
+        .drain(..)
+        .map(|mut decl| {
+            if let Some(init) = decl.init.take() {
+                decl.init = Some(Box::new(call_expr(
+                    member_expr(Expr::Ident(hygiene.private_ident(CTX_NAME)), register),
+                    vec![*init],
+                )));
+            }
+            decl
+        })
+        .collect();
+
+    Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        span: using.span,
+        ctxt: SyntaxContext::empty(),
+        kind: VarDeclKind::Const,
+        declare: false,
+        decls,
+    })))
+}
+
+/// Build the try/catch/finally that records errors and disposes resources.
+fn guarded_try(stmts: Vec<Stmt>, has_async: bool, hygiene: Hygiene) -> Stmt {
+    // _usingCtx.e = _;
+    let record_error = expr_stmt(Expr::Assign(AssignExpr {
+        span: DUMMY_SP,
+        op: AssignOp::Assign,
+        left: AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(Expr::Ident(hygiene.private_ident(CTX_NAME))),
+            prop: MemberProp::Ident(IdentName {
+                span: DUMMY_SP,
+                sym: atom!("e"),
+            }),
+        })),
+        right: Box::new(Expr::Ident(hygiene.private_ident(atom!("_")))),
+    }));
+
+    // [await] _usingCtx.d();
+    let dispose = call_expr(
+        member_expr(Expr::Ident(hygiene.private_ident(CTX_NAME)), atom!("d")),
+        vec![],
+    );
+    let dispose = if has_async {
+        Expr::Await(AwaitExpr {
+            span: DUMMY_SP,
+            arg: Box::new(dispose),
+        })
+    } else {
+        dispose
+    };
+
+    Stmt::Try(Box::new(TryStmt {
+        span: DUMMY_SP,
+        block: block(stmts),
+        handler: Some(CatchClause {
+            span: DUMMY_SP,
+            param: Some(Pat::Ident(binding_ident(hygiene.private_ident(atom!("_"))))),
+            body: block(vec![record_error]),
+        }),
+        finalizer: Some(block(vec![expr_stmt(dispose)])),
+    }))
+}
@@ -3,16 +3,42 @@
 //! This module contains the primary visitor that traverses the AST and
 //! coordinates the transformation of all async function types.
 
-use swc_core::ecma::{
-    ast::*,
-    visit::{noop_visit_mut_type, VisitMut, VisitMutWith},
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    panic::{self, AssertUnwindSafe},
 };
 
+use regex_lite::Regex;
+use swc_core::{
+    common::{
+        comments::Comments, util::take::Take, BytePos, Mark, Span, Spanned, SyntaxContext, DUMMY_SP,
+    },
+    ecma::{
+        ast::*,
+        atoms::Atom,
+        visit::{noop_visit_mut_type, Fold, VisitMut, VisitMutWith},
+    },
+};
+
+use crate::ast_builders::{
+    arguments_capture, assign_expr, block, expr_stmt, this_capture, uninitialized_var_decl,
+    with_span, Hygiene,
+};
+use crate::config::{AwaitlessFunctions, Compat, Config, HoistPosition, LIFECYCLE_HOOKS};
+use crate::diagnostics;
+use crate::filter::{EnclosingClass, FunctionInfo};
+use crate::pragma::{has_leading_pragma, FILE_PRAGMA, IGNORE_PRAGMA};
+use crate::report::{FileSkipReason, FunctionKind, SkipReason, TransformReport};
 use crate::transforms::{
-    transform_fn_decl,
-    transform_arrow_fn,
-    transform_fn_expr,
-    method::{transform_class_method, transform_object_method},
+    bails_on_nesting_depth, generated_decl_kind, inject_runtime, inject_shared_wrapper,
+    is_directive, lower_generators, mark_generated_code,
+    method::{transform_class_method, transform_object_method, transform_private_method},
+    provides_runtime, resolve_program, strict_generated_functions, tag_generated_functions,
+    transform_arrow_fn, transform_awaiter_function, transform_fn_decl, transform_fn_expr,
+    transform_immediate_arrow, transform_immediate_fn_expr, transform_trivial_arrow,
+    trivial_arrow_facts, warn_unsupported_program, AlreadyTransformedVisitor, AsyncCodeVisitor,
+    CaptureIdents, LexicalCaptures, TransformedFn, UsedNamesCollector,
 };
 
 // ============================================================================
@@ -22,32 +48,163 @@ use crate::transforms::{
 /// Counter for generating unique reference identifiers.
 ///
 /// Used to create unique variable names like `_ref`, `_ref1`, `_ref2`, etc.
-/// for async arrow functions and function expressions.
+/// (with the configured prefix) for async arrow functions and function
+/// expressions. Names that already appear in the file are skipped, so
+/// generated refs never shadow user code.
+///
+/// With `hashed_refs` the names are `_ref_1a2b3c` instead, see
+/// [`RefCounter::next_hashed`].
 struct RefCounter {
+    prefix: String,
     count: usize,
+    /// Number of hashed refs named after each function name so far
+    counts_by_name: HashMap<Atom, usize>,
 }
 
 impl RefCounter {
-    fn new() -> Self {
-        Self { count: 0 }
+    fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            count: 0,
+            counts_by_name: HashMap::new(),
+        }
     }
 
-    /// Generate the next unique reference name.
-    fn next(&mut self) -> String {
-        let name = if self.count == 0 {
-            "_ref".to_string()
-        } else {
-            format!("_ref{}", self.count)
-        };
-        self.count += 1;
+    /// Generate the ref name for the next function named after `name`,
+    /// from a hash of `name` and the number of refs named after it before.
+    /// Only functions of the same name renumber each other. Names taken in
+    /// `taken`, or by a hash collision, get a numeric suffix.
+    fn next_hashed(&mut self, name: &Atom, taken: &HashSet<Atom>) -> String {
+        let position = self.counts_by_name.entry(name.clone()).or_default();
+        let hash = fnv1a(format!("{}#{}", name, position).as_bytes());
+        *position += 1;
+        let base = format!("{}_{:06x}", self.prefix, hash & 0xff_ffff);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while taken.contains(&Atom::from(name.as_str())) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
         name
     }
+
+    /// Generate the next unique reference name not contained in `taken`.
+    fn next(&mut self, taken: &HashSet<Atom>) -> String {
+        loop {
+            let name = if self.count == 0 {
+                self.prefix.clone()
+            } else {
+                format!("{}{}", self.prefix, self.count)
+            };
+            self.count += 1;
+            if !taken.contains(&Atom::from(name.as_str())) {
+                return name;
+            }
+        }
+    }
+}
+
+/// 32-bit FNV-1a, a hash that stays the same across platforms and Rust
+/// releases, unlike the one of `std`.
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(*byte)).wrapping_mul(0x0100_0193)
+    })
 }
 
 // ============================================================================
 // Scope Management
 // ============================================================================
 
+/// A statement hoisted out of the item at `index` of its statement list.
+struct Hoisted {
+    index: usize,
+    stmt: Stmt,
+}
+
+/// One level of the scope stack.
+#[derive(Default)]
+struct Scope {
+    /// Index of the statement currently being visited
+    index: usize,
+    /// Helper declarations to insert at the hoist position
+    hoisted: Vec<Hoisted>,
+    /// `_ref` / capture declarations to insert right before the statement
+    /// they were hoisted out of
+    before: Vec<Hoisted>,
+    /// Positions of the `_ref` declarations in `before`
+    refs: Vec<usize>,
+    /// `this` / `arguments` captures already declared at this level
+    captured: LexicalCaptures,
+}
+
+impl Scope {
+    /// Declare the refs of a scope with more than one together, right
+    /// before the first of them, and turn their declarations into
+    /// assignments:
+    ///
+    /// ```javascript
+    /// var _ref, _ref1;
+    /// _ref = _ngAsyncToGenerator(function* () { ... });
+    /// const load = function load() { ... };
+    /// _ref1 = _ngAsyncToGenerator(function* () { ... });
+    /// ```
+    ///
+    /// The refs are left as they are unless each is declared as a single
+    /// initialized binding, as the transform declares them.
+    fn consolidate_refs(&mut self, kind: VarDeclKind) {
+        let Some(&first) = self.refs.first() else {
+            return;
+        };
+        if self.refs.len() < 2 || !self.refs.iter().all(|&i| is_ref_decl(&self.before[i].stmt)) {
+            return;
+        }
+
+        let mut idents = Vec::new();
+        for &i in &self.refs {
+            let stmt = &mut self.before[i].stmt;
+            if let Stmt::Decl(Decl::Var(var)) = stmt {
+                if let [VarDeclarator {
+                    name: Pat::Ident(binding),
+                    init: Some(init),
+                    ..
+                }] = &mut var.decls[..]
+                {
+                    let ident = binding.id.clone();
+                    let init = init.take();
+                    idents.push(ident.clone());
+                    *stmt = expr_stmt(assign_expr(ident, *init));
+                }
+            }
+        }
+
+        let index = self.before[first].index;
+        let pos = self
+            .before
+            .iter()
+            .position(|entry| entry.index == index)
+            .unwrap_or(first);
+        self.before.insert(
+            pos,
+            Hoisted {
+                index,
+                stmt: uninitialized_var_decl(kind, idents),
+            },
+        );
+    }
+}
+
+/// Whether `stmt` declares a single initialized binding, like a `_ref`.
+fn is_ref_decl(stmt: &Stmt) -> bool {
+    matches!(
+        stmt,
+        Stmt::Decl(Decl::Var(var)) if matches!(
+            &var.decls[..],
+            [VarDeclarator { name: Pat::Ident(_), init: Some(_), .. }]
+        )
+    )
+}
+
 /// Manages the scope stack for hoisting helper functions.
 ///
 /// When transforming async function declarations, we generate helper functions
@@ -58,33 +215,79 @@ struct ScopeStack {
     /// Stack of hoisted statements for each scope level.
     /// Each entry represents a scope and contains helper function declarations
     /// that should be inserted at that level.
-    stack: Vec<Vec<Stmt>>,
+    stack: Vec<Scope>,
 }
 
 impl ScopeStack {
     fn new() -> Self {
         Self {
             // Initialize with one scope for the top level
-            stack: vec![Vec::new()],
+            stack: vec![Scope::default()],
         }
     }
 
     /// Enter a new scope (e.g., function body, block).
     fn enter(&mut self) {
-        self.stack.push(Vec::new());
+        self.stack.push(Scope::default());
     }
 
     /// Exit the current scope and return its hoisted statements.
-    fn exit(&mut self) -> Vec<Stmt> {
+    fn exit(&mut self) -> Scope {
         self.stack.pop().unwrap_or_default()
     }
 
+    /// Record that the statement at `index` of the current scope is being
+    /// visited, so statements hoisted from it can be placed next to it.
+    fn set_index(&mut self, index: usize) {
+        if let Some(current) = self.stack.last_mut() {
+            current.index = index;
+        }
+    }
+
     /// Add a statement to be hoisted in the current scope.
     fn push(&mut self, stmt: Stmt) {
         if let Some(current) = self.stack.last_mut() {
-            current.push(stmt);
+            current.hoisted.push(Hoisted {
+                index: current.index,
+                stmt,
+            });
+        }
+    }
+
+    /// Add a statement to be inserted right before the statement currently
+    /// being visited.
+    fn push_before(&mut self, stmt: Stmt) {
+        if let Some(current) = self.stack.last_mut() {
+            current.before.push(Hoisted {
+                index: current.index,
+                stmt,
+            });
         }
     }
+
+    /// Add a `_ref` declaration to be inserted right before the statement
+    /// currently being visited.
+    fn push_ref(&mut self, stmt: Stmt) {
+        if let Some(current) = self.stack.last_mut() {
+            current.refs.push(current.before.len());
+        }
+        self.push_before(stmt);
+    }
+
+    /// Record `captures` as declared in the current scope, returning the
+    /// ones that were not declared yet.
+    fn capture(&mut self, captures: LexicalCaptures) -> LexicalCaptures {
+        let Some(current) = self.stack.last_mut() else {
+            return captures;
+        };
+        let missing = LexicalCaptures {
+            this: captures.this && !current.captured.this,
+            arguments: captures.arguments && !current.captured.arguments,
+        };
+        current.captured.this |= captures.this;
+        current.captured.arguments |= captures.arguments;
+        missing
+    }
 }
 
 // ============================================================================
@@ -101,14 +304,15 @@ impl ScopeStack {
 ///    - Creates a wrapper function and a helper function with the generator
 ///
 /// 2. **Arrow Functions**: `async () => { ... }`
-///    - Wraps in an IIFE with the generator
+///    - Declares the generator as `_ref` before the enclosing statement, or
+///      in an IIFE where that statement sees different bindings
 ///
 /// 3. **Function Expressions**: `async function() { ... }`
-///    - Similar to arrow functions, wrapped in an IIFE
+///    - Similar to arrow functions
 ///
-/// 4. **Methods** (class/object): `async method() { ... }`
+/// 4. **Methods** (class/private/object): `async method() { ... }`
 ///    - Replaces body with immediate generator invocation
-///    - Captures `this` if used
+///    - Captures `this` / `arguments` if used
 ///
 /// ## Scope Handling
 ///
@@ -116,10 +320,68 @@ impl ScopeStack {
 /// should be inserted. This prevents the bug where helper functions were
 /// incorrectly hoisted into nested scopes.
 pub struct AsyncToNgGeneratorVisitor {
+    /// Plugin options
+    config: Config,
+    /// Comments of the program, used for pragmas
+    comments: Option<Box<dyn Comments>>,
+    /// Contexts for generated identifiers, freshly marked per program
+    hygiene: Hygiene,
+    /// Parent of the per-program generated mark
+    top_level_mark: Mark,
+    /// Mark `resolver` gave unresolved references
+    unresolved_mark: Mark,
+    /// Context of unresolved (global) references
+    unresolved_ctxt: SyntaxContext,
     /// Manages scope hierarchy for hoisting
     scopes: ScopeStack,
     /// Generates unique reference names
     ref_counter: RefCounter,
+    /// Every identifier name appearing in the program, used to keep
+    /// generated names collision-free
+    used_names: HashSet<Atom>,
+    /// Name inferred for the anonymous async function about to be visited
+    pending_name: Option<Ident>,
+    /// Name of the innermost named function around the current node, which
+    /// anonymous functions are named after for `hashed_refs`
+    fn_name: Option<Atom>,
+    /// Whether the current node is inside a class matching
+    /// `only_decorated_classes`
+    in_decorated_class: bool,
+    /// Whether the current node is a lifecycle hook or inside one, for
+    /// `lifecycle_hooks_only`
+    in_lifecycle_hook: bool,
+    /// Compiled `exclude_function_names` patterns
+    excluded_names: Vec<Regex>,
+    /// Innermost class around the current node, for the `function_filter`
+    enclosing_class: Option<EnclosingClass>,
+    /// Name of the class declaration or expression about to be visited
+    class_name: Option<Atom>,
+    /// Whether the current node runs once per run of the enclosing statement
+    /// list, with the same bindings in scope, so `_ref` declarations can be
+    /// hoisted right before the statement
+    hoistable: bool,
+    /// Whether the current node is inside a loop of the current function
+    in_loop: bool,
+    /// Number of `this`-capturing boundaries (async methods and arrows)
+    /// enclosing the current node within its `this` scope
+    capture_depth: usize,
+    /// Whether any function in the program was rewritten to use the
+    /// runtime helper
+    transformed: bool,
+    /// Whether an async arrow was given a wrapper of the
+    /// `sharedArrowWrapper` helper, which the program must declare
+    uses_shared_wrapper: bool,
+    /// Whether the runtime helper is already provided in the program,
+    /// either declared by the file itself or injected by this visitor
+    injected: bool,
+    /// Span of the async function being transformed, which internal errors
+    /// are reported at
+    current_fn: Span,
+    /// First internal error met in the program, with the span of the
+    /// function being transformed at the time
+    failure: Option<(Span, String)>,
+    /// What the transform did to the last program
+    report: TransformReport,
 }
 
 impl Default for AsyncToNgGeneratorVisitor {
@@ -129,53 +391,641 @@ impl Default for AsyncToNgGeneratorVisitor {
 }
 
 impl AsyncToNgGeneratorVisitor {
-    /// Create a new visitor instance.
+    /// Create a new visitor instance with the default configuration.
     pub fn new() -> Self {
+        Self::with_config(Config::default())
+    }
+
+    /// Create a new visitor instance with the given configuration.
+    pub fn with_config(config: Config) -> Self {
+        Self::with_marks(config, Mark::root(), Mark::root())
+    }
+
+    /// Create a new visitor instance for a program that already went through
+    /// `resolver(unresolved_mark, top_level_mark, ..)`.
+    ///
+    /// Identifiers moved out of the original functions keep their contexts;
+    /// references to globals the transform adds (`arguments`,
+    /// `_ngAsyncToGenerator`) get the unresolved context so later passes
+    /// resolve them the same way as user-written globals.
+    pub fn with_marks(config: Config, unresolved_mark: Mark, top_level_mark: Mark) -> Self {
+        let unresolved_ctxt = if unresolved_mark == Mark::root() {
+            SyntaxContext::empty()
+        } else {
+            SyntaxContext::empty().apply_mark(unresolved_mark)
+        };
+        let ref_counter = RefCounter::new(&config.ref_prefix);
+        let excluded_names = config.excluded_function_names();
+
         Self {
+            config,
+            comments: None,
+            hygiene: Hygiene::default(),
+            top_level_mark,
+            unresolved_mark,
+            unresolved_ctxt,
             scopes: ScopeStack::new(),
-            ref_counter: RefCounter::new(),
+            ref_counter,
+            used_names: HashSet::new(),
+            pending_name: None,
+            fn_name: None,
+            in_decorated_class: false,
+            in_lifecycle_hook: false,
+            excluded_names,
+            enclosing_class: None,
+            class_name: None,
+            hoistable: false,
+            in_loop: false,
+            capture_depth: 0,
+            transformed: false,
+            uses_shared_wrapper: false,
+            injected: false,
+            current_fn: DUMMY_SP,
+            failure: None,
+            report: TransformReport::default(),
         }
     }
-}
 
-// ============================================================================
-// Hoisting Helpers
-// ============================================================================
+    /// Give the visitor access to the program's comments, enabling the
+    /// comment pragmas.
+    pub fn with_comments(self, comments: impl Comments + 'static) -> Self {
+        self.with_boxed_comments(Box::new(comments))
+    }
 
-/// Insert hoisted statements after the last function declaration in a statement list.
-fn insert_hoisted_stmts(stmts: &mut Vec<Stmt>, hoisted: Vec<Stmt>) {
-    if hoisted.is_empty() {
-        return;
+    /// [`Self::with_comments`] for comments that are already boxed.
+    pub fn with_boxed_comments(mut self, comments: Box<dyn Comments>) -> Self {
+        self.comments = Some(comments);
+        self
+    }
+
+    /// What the transform did to the last program visited: the async
+    /// functions it transformed and left native, or why it skipped the file.
+    pub fn report(&self) -> &TransformReport {
+        &self.report
+    }
+
+    /// Whether the program opts in with a file-level pragma.
+    fn has_file_pragma(&self, program: &Program) -> bool {
+        let Some(comments) = &self.comments else {
+            return false;
+        };
+
+        let first_item = match program {
+            Program::Module(module) => module.body.first().map(|item| item.span_lo()),
+            Program::Script(script) => script.body.first().map(|stmt| stmt.span_lo()),
+        };
+        [Some(program.span_lo()), first_item]
+            .into_iter()
+            .flatten()
+            .any(|pos| has_leading_pragma(&**comments, pos, FILE_PRAGMA))
+    }
+
+    /// Record an internal error at the function being transformed. Only the
+    /// first one is kept, since the whole program is restored anyway.
+    ///
+    /// Failing is only possible where `visit_mut_program` keeps a copy of
+    /// the program to restore.
+    fn fail(&mut self, message: impl Into<String>) {
+        if self.failure.is_none() {
+            self.failure = Some((self.current_fn, message.into()));
+        }
+    }
+
+    /// Why the function starting at `pos` must stay native, if it must:
+    /// a comment opts it out, it lies outside the classes selected by
+    /// `only_decorated_classes` or outside the hooks of
+    /// `lifecycle_hooks_only`.
+    fn native_reason(&self, pos: BytePos) -> Option<SkipReason> {
+        if !self.config.only_decorated_classes.is_empty() && !self.in_decorated_class {
+            Some(SkipReason::OutsideDecoratedClasses)
+        } else if self.config.lifecycle_hooks_only && !self.in_lifecycle_hook {
+            Some(SkipReason::NotLifecycleHook)
+        } else if self.is_ignored(pos) {
+            Some(SkipReason::Ignored)
+        } else {
+            None
+        }
+    }
+
+    /// Why the async function of `kind` at `span` must stay native because
+    /// it is larger than `max_function_size`, its name is excluded by
+    /// `exclude_function_names` or the `function_filter` rejects it, if it
+    /// must.
+    fn filter_reason(
+        &self,
+        kind: FunctionKind,
+        span: Span,
+        name: Option<&Atom>,
+    ) -> Option<SkipReason> {
+        if self
+            .config
+            .max_function_size
+            .is_some_and(|max| function_size(span) > max)
+        {
+            return Some(SkipReason::TooLarge);
+        }
+        if name.is_some_and(|name| self.excluded_names.iter().any(|re| re.is_match(name))) {
+            return Some(SkipReason::ExcludedName);
+        }
+        let filter = self.config.function_filter.as_ref()?;
+        let info = FunctionInfo {
+            kind,
+            name: name.map(|name| &**name),
+            span,
+            class: self.enclosing_class.as_ref(),
+        };
+        (!filter.transforms(&info)).then_some(SkipReason::Filtered)
+    }
+
+    /// Whether an async arrow named `name` must stay native.
+    fn keeps_arrow_native(&self, arrow: &ArrowExpr, name: Option<&Ident>) -> bool {
+        self.arrow_native_reason(arrow, name).is_some()
+    }
+
+    /// Why an async arrow named `name` must stay native, if it must: like
+    /// [`Self::native_reason`], because `transform_arrow_functions` is off,
+    /// or because the `function_filter` rejects it.
+    fn arrow_native_reason(&self, arrow: &ArrowExpr, name: Option<&Ident>) -> Option<SkipReason> {
+        if !arrow.is_async {
+            None
+        } else if !self.config.transform_arrow_functions {
+            Some(SkipReason::ArrowsDisabled)
+        } else {
+            self.native_reason(arrow.span.lo).or_else(|| {
+                self.filter_reason(FunctionKind::Arrow, arrow.span, name.map(|name| &name.sym))
+            })
+        }
+    }
+
+    /// Why an async function expression must stay native, if it must: like
+    /// [`Self::native_reason`], or because the `function_filter` rejects it.
+    /// Anonymous expressions go by their inferred `name`.
+    fn fn_expr_native_reason(&self, fn_expr: &FnExpr, name: Option<&Ident>) -> Option<SkipReason> {
+        if !fn_expr.function.is_async {
+            return None;
+        }
+        let span = fn_expr.function.span;
+        let name = fn_expr.ident.as_ref().or(name).map(|name| &name.sym);
+        self.native_reason(span.lo)
+            .or_else(|| self.filter_reason(FunctionKind::Expression, span, name))
+    }
+
+    /// Count and explain an async function of `kind` the transform was
+    /// applied to: rewritten with the `applied` strategy, only stripped of
+    /// `async` as an awaitless function, or left native as unsupported when
+    /// it is `still_async`.
+    fn record(
+        &mut self,
+        kind: FunctionKind,
+        span: Span,
+        name: Option<&Atom>,
+        applied: Option<&str>,
+        still_async: bool,
+    ) {
+        let strategy = match (applied, self.config.awaitless_functions) {
+            (Some(strategy), _) => strategy,
+            (None, _) if still_async => {
+                self.keep_native(kind, span, name, SkipReason::Unsupported);
+                return;
+            }
+            (None, AwaitlessFunctions::Drop) => "awaitless, `async` dropped",
+            (None, _) => "awaitless, settled with `Promise`",
+        };
+        self.report.transformed_fn(kind);
+        self.explain(span, || {
+            format!("{} transformed: {}", describe(kind, name), strategy)
+        });
+    }
+
+    /// Count and explain an async function of `kind` left native.
+    fn keep_native(
+        &mut self,
+        kind: FunctionKind,
+        span: Span,
+        name: Option<&Atom>,
+        reason: SkipReason,
+    ) {
+        self.report.skipped_fn(kind, span, reason);
+        if let (SkipReason::TooLarge, Some(max)) = (reason, self.config.max_function_size) {
+            let message = format!(
+                "{} left untransformed: {} bytes, more than `maxFunctionSize` ({})",
+                describe(kind, name),
+                function_size(span),
+                max
+            );
+            diagnostics::unsupported(span, &message, &self.config);
+        }
+        self.explain(span, || {
+            format!("{} left native: {}", describe(kind, name), reason)
+        });
+    }
+
+    /// Log a decision of the transform: as a `tracing` event with the
+    /// `debug` feature, and as a note at `span` with the `debug` option.
+    fn explain(&self, span: Span, message: impl FnOnce() -> String) {
+        if cfg!(feature = "debug") || self.config.debug {
+            let message = message();
+            #[cfg(feature = "debug")]
+            tracing::debug!(?span, "{}", message);
+            if self.config.debug {
+                diagnostics::note(span, &message);
+            }
+        }
+    }
+
+    /// Whether a comment directly before `pos` opts the function starting
+    /// there out of the transform.
+    fn is_ignored(&self, pos: BytePos) -> bool {
+        match &self.comments {
+            Some(comments) if !pos.is_dummy() => {
+                has_leading_pragma(&**comments, pos, IGNORE_PRAGMA)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the method named `name` is, or is inside, a lifecycle hook
+    /// selected by `lifecycle_hooks_only`.
+    fn in_lifecycle_hook(&self, name: Option<&Atom>) -> bool {
+        self.in_lifecycle_hook
+            || (self.config.lifecycle_hooks_only
+                && name.is_some_and(|name| LIFECYCLE_HOOKS.contains(&&**name)))
+    }
+
+    /// Whether `class` carries one of the `only_decorated_classes`.
+    fn is_selected_class(&self, class: &Class) -> bool {
+        class.decorators.iter().any(|decorator| {
+            decorator_name(decorator).is_some_and(|name| {
+                self.config
+                    .only_decorated_classes
+                    .iter()
+                    .any(|selected| **selected == **name)
+            })
+        })
     }
 
-    // Find position after the last function declaration
-    let insert_pos = stmts
-        .iter()
-        .enumerate()
-        .filter(|(_, stmt)| matches!(stmt, Stmt::Decl(Decl::Fn(_))))
-        .map(|(i, _)| i + 1)
-        .last()
-        .unwrap_or(0);
+    /// Generate a name based on `base` that is not used anywhere in the file:
+    /// `base` itself, then `base2`, `base3`, ...
+    fn unique_name(&mut self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut suffix = 2;
+        while self.used_names.contains(&Atom::from(name.as_str())) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        self.used_names.insert(Atom::from(name.as_str()));
+        name
+    }
+
+    /// Generate the next `_ref` name, for a function called `name` with
+    /// `hashed_refs`, and reserve it.
+    fn next_ref_name(&mut self, name: Option<&Atom>) -> String {
+        let name = if self.config.hashed_refs {
+            let name = name.or(self.fn_name.as_ref()).cloned();
+            let name = name.unwrap_or_else(|| Atom::from(""));
+            self.ref_counter.next_hashed(&name, &self.used_names)
+        } else {
+            self.ref_counter.next(&self.used_names)
+        };
+        self.used_names.insert(Atom::from(name.as_str()));
+        name
+    }
+
+    /// Make `name` the name of the function whose children are visited
+    /// next, returning the outer one to restore afterwards. Anonymous
+    /// functions keep the outer name.
+    fn enter_fn_name(&mut self, name: Option<&Atom>) -> Option<Atom> {
+        let name = name.cloned().or_else(|| self.fn_name.clone());
+        std::mem::replace(&mut self.fn_name, name)
+    }
 
-    // Insert hoisted functions
-    for (i, func) in hoisted.into_iter().enumerate() {
-        stmts.insert(insert_pos + i, func);
+    /// Visit the function of a method. Methods start a new `this` scope in
+    /// which an async method is itself the outermost capturing boundary,
+    /// unless it is `ignored` and stays native.
+    fn visit_mut_method_function(&mut self, func: &mut Function, ignored: bool) {
+        let is_boundary = func.is_async && !ignored;
+        let depth = std::mem::replace(&mut self.capture_depth, usize::from(is_boundary));
+        self.visit_mut_function_scope(func);
+        self.capture_depth = depth;
+    }
+
+    /// Visit the children of a node whose contents run with other bindings
+    /// or more often than the enclosing statement, so `_ref` declarations
+    /// cannot be hoisted out of it. Statement lists inside it hoist again.
+    fn visit_mut_unhoistable<N: VisitMutWith<Self>>(&mut self, node: &mut N) {
+        let hoistable = std::mem::replace(&mut self.hoistable, false);
+        node.visit_mut_children_with(self);
+        self.hoistable = hoistable;
+    }
+
+    /// Visit the children of a function or class, which start over outside
+    /// of any loop.
+    fn visit_mut_function_scope<N: VisitMutWith<Self>>(&mut self, node: &mut N) {
+        let in_loop = std::mem::replace(&mut self.in_loop, false);
+        self.visit_mut_unhoistable(node);
+        self.in_loop = in_loop;
+    }
+
+    /// Visit the children of a loop. Blocks in its body only get hoisted
+    /// `_ref`s when those are block scoped: a `var` would be shared by the
+    /// wrappers of every iteration.
+    fn visit_mut_loop<N: VisitMutWith<Self>>(&mut self, node: &mut N) {
+        let in_loop = std::mem::replace(&mut self.in_loop, true);
+        self.visit_mut_unhoistable(node);
+        self.in_loop = in_loop;
+    }
+
+    /// Replace an async arrow or function expression with its transformed
+    /// form, declaring a hoisted `_ref` and the captures it reads (once per
+    /// scope) right before the current statement. The replacement takes over
+    /// the span, and so the comments, of the function.
+    ///
+    /// The replacement is never visited again: the original parts it holds
+    /// were visited before the transform, and the generated ones contain
+    /// no async code. Nor are hoisted declarations, which are inserted once
+    /// their statement list has been visited.
+    fn place_transformed(
+        &mut self,
+        expr: &mut Expr,
+        transformed: TransformedFn,
+        capture: Option<&CaptureIdents>,
+    ) {
+        self.transformed = true;
+        let span = expr.span();
+        let replacement = match transformed {
+            TransformedFn::InPlace => return,
+            TransformedFn::Inline(replacement) => replacement,
+            TransformedFn::Hoisted {
+                wrapper,
+                ref_decl,
+                captures,
+            } => {
+                if let Some(capture) = capture {
+                    self.declare_captures(captures, capture);
+                }
+                self.scopes.push_ref(ref_decl);
+                wrapper
+            }
+        };
+        *expr = with_span(replacement, span);
+    }
+
+    /// Declare the `captures` not declared in the current scope yet right
+    /// before the current statement.
+    fn declare_captures(&mut self, captures: LexicalCaptures, capture: &CaptureIdents) {
+        let missing = self.scopes.capture(captures);
+        let kind = generated_decl_kind(&self.config, false);
+        if missing.this {
+            self.scopes
+                .push_before(this_capture(kind, capture.this.clone()));
+        }
+        if missing.arguments {
+            self.scopes.push_before(arguments_capture(
+                kind,
+                capture.arguments.clone(),
+                self.hygiene,
+            ));
+        }
+    }
+
+    /// Visit `(async () => { ... })()` or `(async function () { ... })()`
+    /// and call the wrapped generator directly,
+    /// `_ngAsyncToGenerator(function* () { ... })()`, instead of creating a
+    /// wrapper only to call it once.
+    ///
+    /// Returns false, without visiting anything, for other calls.
+    fn visit_mut_immediate_call(&mut self, call: &mut CallExpr) -> bool {
+        // tsc keeps the function where it is, called as it was
+        if self.config.compat == Compat::Tsc {
+            return false;
+        }
+        let hoist = self.hoistable;
+        let Callee::Expr(callee) = &mut call.callee else {
+            return false;
+        };
+        let mut callee = &mut **callee;
+        while let Expr::Paren(paren) = callee {
+            callee = &mut *paren.expr;
+        }
+
+        let wrapped = match callee {
+            Expr::Arrow(arrow) if arrow.is_async && !self.keeps_arrow_native(arrow, None) => {
+                let span = arrow.span;
+                self.capture_depth += 1;
+                arrow.visit_mut_with(self);
+                self.current_fn = span;
+                let capture = CaptureIdents::new(&self.config, self.hygiene, self.capture_depth);
+                self.capture_depth -= 1;
+                let wrapped = match transform_immediate_arrow(
+                    arrow,
+                    &capture,
+                    hoist,
+                    &self.config,
+                    self.hygiene,
+                ) {
+                    Some((wrapped, captures)) => {
+                        self.declare_captures(captures, &capture);
+                        Some(with_span(wrapped, span))
+                    }
+                    None => None,
+                };
+                let applied = wrapped.as_ref().map(|_| "immediate call");
+                let still_async = wrapped.is_none() && arrow.is_async;
+                self.record(FunctionKind::Arrow, span, None, applied, still_async);
+                wrapped
+            }
+            // Named expressions may refer to themselves, which needs the wrapper
+            Expr::Fn(fn_expr)
+                if fn_expr.function.is_async
+                    && fn_expr.ident.is_none()
+                    && self.fn_expr_native_reason(fn_expr, None).is_none() =>
+            {
+                let span = fn_expr.function.span;
+                fn_expr.visit_mut_with(self);
+                self.current_fn = span;
+                let wrapped = transform_immediate_fn_expr(fn_expr, &self.config, self.hygiene)
+                    .map(|wrapped| with_span(wrapped, span));
+                let applied = wrapped.as_ref().map(|_| "immediate call");
+                let still_async = wrapped.is_none() && fn_expr.function.is_async;
+                self.record(FunctionKind::Expression, span, None, applied, still_async);
+                wrapped
+            }
+            _ => return false,
+        };
+
+        call.args.visit_mut_with(self);
+        if let Some(wrapped) = wrapped {
+            self.transformed = true;
+            call.callee = Callee::Expr(Box::new(wrapped));
+        }
+        true
+    }
+
+    /// Remember `name` if `expr` is an anonymous async function.
+    ///
+    /// JavaScript infers `.name` from the binding an anonymous function is
+    /// assigned to. The generated wrapper is not directly assigned, so the
+    /// name is carried over explicitly. It is minted as a generated binding:
+    /// the function name is a binding of its own, and sharing the outer
+    /// binding's syntax context would make a mangler see one binding
+    /// declared in two scopes. Names that would shadow the identifiers
+    /// captured by the wrapper are skipped.
+    fn infer_name(&mut self, expr: &Expr, name: &Atom) {
+        let is_anonymous_async = match expr {
+            Expr::Arrow(arrow) => arrow.is_async,
+            Expr::Fn(fn_expr) => fn_expr.function.is_async && fn_expr.ident.is_none(),
+            _ => false,
+        };
+
+        if is_anonymous_async
+            && Ident::verify_symbol(name).is_ok()
+            && **name != *self.config.this_var_name
+            && &**name != "arguments"
+        {
+            self.pending_name = Some(self.hygiene.private_ident(name.clone()));
+        }
+    }
+
+    /// Transform the functions of a program that passed the file checks.
+    fn transform_program(&mut self, program: &mut Program) {
+        warn_unsupported_program(program, &self.config);
+        // regenerator tells its own references apart from user bindings by
+        // the unresolved mark, so programs without one get resolved here
+        let (unresolved_mark, unresolved_ctxt) =
+            if self.config.regenerator && self.unresolved_mark == Mark::root() {
+                let mark = resolve_program(program);
+                (mark, SyntaxContext::empty().apply_mark(mark))
+            } else {
+                (self.unresolved_mark, self.unresolved_ctxt)
+            };
+        self.used_names = UsedNamesCollector::collect(program);
+        self.hygiene = Hygiene::fresh(self.top_level_mark, unresolved_ctxt);
+        self.transformed = false;
+        self.uses_shared_wrapper = false;
+        self.injected = provides_runtime(program, &self.config);
+        let header_pos = first_item_pos(program);
+        program.visit_mut_children_with(self);
+        if let Some(pos) = header_pos {
+            keep_header_first(program, pos);
+        }
+
+        if self.uses_shared_wrapper {
+            inject_shared_wrapper(program, self.hygiene);
+        }
+        // However many functions were rewritten, the helper is provided once
+        if self.transformed {
+            match inject_runtime(program, &self.config, !self.injected, self.hygiene) {
+                Ok(injected) => self.injected |= injected,
+                Err(message) => self.fail(message),
+            }
+        }
+        if self.transformed && self.config.regenerator {
+            lower_generators(program, unresolved_mark);
+        }
+        if self.transformed && self.config.strict_generated_code {
+            strict_generated_functions(program);
+        }
+        if let (true, Some(hint), Some(comments)) = (
+            self.transformed,
+            &self.config.coverage_ignore_hint,
+            &self.comments,
+        ) {
+            tag_generated_functions(program, &**comments, hint);
+        }
+        if let (true, Some(marker), Some(comments)) = (
+            self.transformed,
+            &self.config.marker_comment,
+            &self.comments,
+        ) {
+            mark_generated_code(program, &**comments, marker);
+        }
+    }
+
+    /// Reset the per-program state after a failed transform, which may have
+    /// stopped halfway through a scope.
+    fn reset(&mut self) {
+        self.scopes = ScopeStack::new();
+        self.pending_name = None;
+        self.fn_name = None;
+        self.in_decorated_class = false;
+        self.in_lifecycle_hook = false;
+        self.enclosing_class = None;
+        self.class_name = None;
+        self.hoistable = false;
+        self.in_loop = false;
+        self.capture_depth = 0;
+        self.transformed = false;
+        self.uses_shared_wrapper = false;
+        self.current_fn = DUMMY_SP;
     }
 }
 
-/// Insert hoisted statements after the last function declaration in module items.
-fn insert_hoisted_module_items(items: &mut Vec<ModuleItem>, hoisted: Vec<Stmt>) {
-    if hoisted.is_empty() {
-        return;
+/// Name of a method with an identifier key, for debug output.
+fn prop_name(key: &PropName) -> Option<&Atom> {
+    match key {
+        PropName::Ident(ident) => Some(&ident.sym),
+        _ => None,
+    }
+}
+
+/// `async arrow` or ``async method `load` `` for debug output.
+fn describe(kind: FunctionKind, name: Option<&Atom>) -> String {
+    match name {
+        Some(name) => format!("{} `{}`", kind, name),
+        None => kind.to_string(),
+    }
+}
+
+/// Size of the function spanning `span`, in bytes of source, for
+/// `max_function_size`.
+fn function_size(span: Span) -> u32 {
+    span.hi.0.saturating_sub(span.lo.0)
+}
+
+/// Name of a decorator: `Component` for `@Component`, `@Component({...})`,
+/// `@core.Component` and `@core.Component({...})`.
+fn decorator_name(decorator: &Decorator) -> Option<&Atom> {
+    let mut expr = &*decorator.expr;
+    if let Expr::Call(CallExpr {
+        callee: Callee::Expr(callee),
+        ..
+    }) = expr
+    {
+        expr = callee;
+    }
+    match expr {
+        Expr::Ident(ident) => Some(&ident.sym),
+        Expr::Member(MemberExpr {
+            prop: MemberProp::Ident(prop),
+            ..
+        }) => Some(&prop.sym),
+        _ => None,
     }
+}
+
+// ============================================================================
+// Hoisting Helpers
+// ============================================================================
 
-    let hoisted_items: Vec<ModuleItem> = hoisted.into_iter().map(ModuleItem::Stmt).collect();
+/// Insert hoisted statements into a statement list according to `position`.
+fn insert_hoisted_stmts(stmts: &mut Vec<Stmt>, scope: Scope, position: HoistPosition) {
+    insert_hoisted(
+        stmts,
+        scope,
+        position,
+        |stmt| matches!(stmt, Stmt::Decl(Decl::Fn(_))),
+        is_directive,
+        |stmt| stmt,
+    );
+}
 
-    // Find position after the last function declaration
-    let insert_pos = items
-        .iter()
-        .enumerate()
-        .filter(|(_, item)| {
+/// Insert hoisted statements into module items according to `position`.
+fn insert_hoisted_module_items(items: &mut Vec<ModuleItem>, scope: Scope, position: HoistPosition) {
+    insert_hoisted(
+        items,
+        scope,
+        position,
+        |item| {
             matches!(
                 item,
                 ModuleItem::Stmt(Stmt::Decl(Decl::Fn(_)))
@@ -184,14 +1034,132 @@ fn insert_hoisted_module_items(items: &mut Vec<ModuleItem>, hoisted: Vec<Stmt>)
                         ..
                     }))
             )
-        })
-        .map(|(i, _)| i + 1)
-        .last()
-        .unwrap_or(0);
+        },
+        |item| match item {
+            ModuleItem::Stmt(stmt) => is_directive(stmt),
+            ModuleItem::ModuleDecl(decl) => matches!(decl, ModuleDecl::Import(_)),
+        },
+        ModuleItem::Stmt,
+    );
+}
+
+/// Insert the statements hoisted in `scope` into `items`.
+///
+/// `_ref` declarations go right before the item they were hoisted out of.
+/// Helpers go according to `position`:
+///
+/// - `Adjacent`: right after the item each helper was hoisted out of
+/// - `AfterDecls`: after the last function declaration
+/// - `Top`: at the start of the list
+///
+/// Either way they stay behind the leading items `is_prologue` matches (the
+/// directive prologue, and the imports at the start of a module), so
+/// `"use strict"` keeps applying and the import block stays in one piece.
+/// A hashbang is not an item, so it always stays first.
+fn insert_hoisted<T>(
+    items: &mut Vec<T>,
+    scope: Scope,
+    position: HoistPosition,
+    is_fn_decl: impl Fn(&T) -> bool,
+    is_prologue: impl Fn(&T) -> bool,
+    into_item: impl Fn(Stmt) -> T,
+) {
+    let Scope {
+        hoisted, before, ..
+    } = scope;
+    let (adjacent, hoisted) = if position == HoistPosition::Adjacent {
+        (hoisted, Vec::new())
+    } else {
+        (Vec::new(), hoisted)
+    };
+    if before.is_empty() && adjacent.is_empty() && hoisted.is_empty() {
+        return;
+    }
+
+    // Helpers go before the item at `hoist_at`, or at the end
+    let prologue_end = items.iter().take_while(|item| is_prologue(item)).count();
+    let hoist_at = match position {
+        // Find position after the last function declaration
+        HoistPosition::AfterDecls => items
+            .iter()
+            .rposition(|item| is_fn_decl(item))
+            .map_or(0, |i| i + 1),
+        HoistPosition::Adjacent | HoistPosition::Top => 0,
+    }
+    .max(prologue_end);
+
+    // Rebuild the list in one pass instead of inserting entry by entry
+    let capacity = items.len() + before.len() + adjacent.len() + hoisted.len();
+    let mut rebuilt = Vec::with_capacity(capacity);
+    let mut hoisted = hoisted.into_iter().map(|entry| into_item(entry.stmt));
+    let mut before = before.into_iter().peekable();
+    let mut adjacent = adjacent.into_iter().peekable();
+    for (i, item) in std::mem::take(items).into_iter().enumerate() {
+        if i == hoist_at {
+            rebuilt.extend(hoisted.by_ref());
+        }
+        while let Some(entry) = before.next_if(|entry| entry.index == i) {
+            rebuilt.push(into_item(entry.stmt));
+        }
+        rebuilt.push(item);
+        while let Some(entry) = adjacent.next_if(|entry| entry.index == i) {
+            rebuilt.push(into_item(entry.stmt));
+        }
+    }
+    rebuilt.extend(hoisted);
+    *items = rebuilt;
+}
 
-    // Insert hoisted functions
-    for (i, func) in hoisted_items.into_iter().enumerate() {
-        items.insert(insert_pos + i, func);
+/// Message of a caught panic.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unexpected panic".to_string()
+    }
+}
+
+/// Start of the first statement of a program.
+fn first_item_pos(program: &Program) -> Option<BytePos> {
+    let pos = match program {
+        Program::Module(module) => module.body.first().map(|item| item.span_lo()),
+        Program::Script(script) => script.body.first().map(|stmt| stmt.span_lo()),
+    };
+    pos.filter(|pos| !pos.is_dummy())
+}
+
+/// Keep the comments leading the first statement of a file (license headers,
+/// file docs) above the declarations hoisted in front of it, by giving the
+/// first of those the statement's position.
+fn keep_header_first(program: &mut Program, header_pos: BytePos) {
+    let first = match program {
+        Program::Module(module) => match module.body.first_mut() {
+            Some(ModuleItem::Stmt(stmt)) => Some(stmt),
+            _ => None,
+        },
+        Program::Script(script) => script.body.first_mut(),
+    };
+    if let Some(Stmt::Decl(Decl::Var(var))) = first {
+        if var.span.is_dummy() {
+            var.span = Span::new(header_pos, header_pos);
+        }
+    }
+}
+
+/// Wrap an async function declaration used as a single statement
+/// (`if (x) async function f() {}`) in a block.
+///
+/// Such positions have no statement list for the hoisted helper to land in.
+/// A block gives the declaration its own scope, which matches how Annex B
+/// treats function declarations in these positions.
+fn wrap_single_statement_fn_decl(stmt: &mut Stmt) {
+    if let Stmt::Decl(Decl::Fn(fn_decl)) = stmt {
+        if fn_decl.function.is_async {
+            let decl = stmt.take();
+            *stmt = Stmt::Block(block(vec![decl]));
+        }
     }
 }
 
@@ -202,79 +1170,621 @@ fn insert_hoisted_module_items(items: &mut Vec<ModuleItem>, hoisted: Vec<Stmt>)
 impl VisitMut for AsyncToNgGeneratorVisitor {
     noop_visit_mut_type!();
 
+    /// Skip programs without async code, programs that already contain the
+    /// plugin's own output, lack the opt-in pragma when it is required, only
+    /// run on targets with native async functions, or nest deeper than
+    /// `maxNestingDepth`. Make the runtime helper available to programs that
+    /// were changed, and lower their generators when `regenerator` is set.
+    ///
+    /// Generated identifiers are minted under a mark created here, so each
+    /// program gets its own and the `hygiene` pass can resolve conflicts.
+    ///
+    /// An internal error never aborts the compilation: the program is
+    /// restored untransformed and a single warning names the function that
+    /// was being transformed. Panics are handled the same way with
+    /// `catch_panics`. Only these can stop the transform halfway, so the
+    /// program is only copied to restore it from when one is possible.
+    fn visit_mut_program(&mut self, program: &mut Program) {
+        self.report = TransformReport::default();
+        if !AsyncCodeVisitor::check(program) {
+            return;
+        }
+        let skipped_file = if !self.config.force_reprocess
+            && AlreadyTransformedVisitor::check(program, &self.config.wrapper_function_name)
+        {
+            Some(FileSkipReason::AlreadyTransformed)
+        } else if self.config.require_pragma && !self.has_file_pragma(program) {
+            Some(FileSkipReason::MissingPragma)
+        } else if self.config.targets_support_async() {
+            Some(FileSkipReason::NativeTargets)
+        } else if bails_on_nesting_depth(program, &self.config) {
+            Some(FileSkipReason::TooDeeplyNested)
+        } else {
+            None
+        };
+        if let Some(reason) = skipped_file {
+            self.report.skipped_file = Some(reason);
+            self.explain(program.span(), || {
+                format!("file left untransformed: {}", reason)
+            });
+            return;
+        }
+
+        // Inlining the bundled helper is the only step that can fail
+        let recoverable = self.config.catch_panics || self.config.inline_helper;
+        let original = recoverable.then(|| program.clone());
+        if self.config.catch_panics {
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| self.transform_program(program)));
+            if let Err(payload) = outcome {
+                self.fail(panic_message(&*payload));
+            }
+        } else {
+            self.transform_program(program);
+        }
+        if let Some((span, message)) = self.failure.take() {
+            if let Some(original) = original {
+                *program = original;
+            }
+            self.reset();
+            self.report = TransformReport {
+                skipped_file: Some(FileSkipReason::InternalError),
+                ..Default::default()
+            };
+            diagnostics::warn(
+                span,
+                &format!(
+                    "internal error while transforming this async function ({}); \
+                     the file was left untransformed",
+                    message
+                ),
+            );
+        }
+    }
+
     /// Handle module-level items.
+    ///
+    /// TypeScript namespace bodies (`namespace Api { ... }`) are module item
+    /// lists too, so each namespace gets its own hoisting scope and helpers
+    /// land inside the namespace next to the function they belong to.
     fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
         self.scopes.enter();
+        let hoistable = std::mem::replace(&mut self.hoistable, true);
 
-        for item in items.iter_mut() {
+        for (i, item) in items.iter_mut().enumerate() {
+            self.scopes.set_index(i);
             item.visit_mut_with(self);
         }
 
-        let hoisted = self.scopes.exit();
-        insert_hoisted_module_items(items, hoisted);
+        self.hoistable = hoistable;
+        let mut scope = self.scopes.exit();
+        scope.consolidate_refs(generated_decl_kind(&self.config, true));
+        insert_hoisted_module_items(items, scope, self.config.hoist_position);
     }
 
     /// Handle statement blocks (function bodies, if blocks, etc.).
     fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
         self.scopes.enter();
+        let block_scoped = generated_decl_kind(&self.config, false) != VarDeclKind::Var;
+        let hoistable = std::mem::replace(&mut self.hoistable, !self.in_loop || block_scoped);
 
-        for stmt in stmts.iter_mut() {
+        for (i, stmt) in stmts.iter_mut().enumerate() {
+            self.scopes.set_index(i);
             stmt.visit_mut_with(self);
         }
 
-        let hoisted = self.scopes.exit();
-        insert_hoisted_stmts(stmts, hoisted);
+        self.hoistable = hoistable;
+        let mut scope = self.scopes.exit();
+        scope.consolidate_refs(generated_decl_kind(&self.config, true));
+        insert_hoisted_stmts(stmts, scope, self.config.hoist_position);
+    }
+
+    /// Skip ambient namespaces (`declare namespace`) - they contain no runtime code.
+    fn visit_mut_ts_module_decl(&mut self, decl: &mut TsModuleDecl) {
+        if decl.declare {
+            return;
+        }
+        decl.visit_mut_children_with(self);
+    }
+
+    /// Track whether the class body is selected by `only_decorated_classes`.
+    /// Classes nested in a selected class are selected too.
+    ///
+    /// The class is also the enclosing class the `function_filter` sees for
+    /// the functions written in it.
+    fn visit_mut_class(&mut self, class: &mut Class) {
+        let selected = self.in_decorated_class || self.is_selected_class(class);
+        let outer = std::mem::replace(&mut self.in_decorated_class, selected);
+        let enclosing = EnclosingClass {
+            name: self.class_name.take().map(|name| name.to_string()),
+            decorators: class
+                .decorators
+                .iter()
+                .filter_map(decorator_name)
+                .map(|name| name.to_string())
+                .collect(),
+        };
+        let outer_class = std::mem::replace(&mut self.enclosing_class, Some(enclosing));
+        self.visit_mut_function_scope(class);
+        self.enclosing_class = outer_class;
+        self.in_decorated_class = outer;
+    }
+
+    /// Skip ambient classes (`declare class`) - they contain no runtime code.
+    fn visit_mut_class_decl(&mut self, decl: &mut ClassDecl) {
+        if decl.declare {
+            return;
+        }
+        self.class_name = Some(decl.ident.sym.clone());
+        decl.visit_mut_children_with(self);
+    }
+
+    /// Remember the name of `class Name {}` expressions.
+    fn visit_mut_class_expr(&mut self, expr: &mut ClassExpr) {
+        self.class_name = expr.ident.as_ref().map(|ident| ident.sym.clone());
+        expr.visit_mut_children_with(self);
+    }
+
+    /// Loop bodies run once per iteration, with per-iteration bindings.
+    fn visit_mut_for_stmt(&mut self, stmt: &mut ForStmt) {
+        self.visit_mut_loop(stmt);
+    }
+
+    fn visit_mut_for_in_stmt(&mut self, stmt: &mut ForInStmt) {
+        self.visit_mut_loop(stmt);
+    }
+
+    fn visit_mut_for_of_stmt(&mut self, stmt: &mut ForOfStmt) {
+        self.visit_mut_loop(stmt);
+    }
+
+    fn visit_mut_while_stmt(&mut self, stmt: &mut WhileStmt) {
+        self.visit_mut_loop(stmt);
+    }
+
+    fn visit_mut_do_while_stmt(&mut self, stmt: &mut DoWhileStmt) {
+        self.visit_mut_loop(stmt);
+    }
+
+    /// `with` bodies resolve names against the object.
+    fn visit_mut_with_stmt(&mut self, stmt: &mut WithStmt) {
+        self.visit_mut_unhoistable(stmt);
+    }
+
+    /// Normalize `if (x) async function f() {}` before visiting.
+    fn visit_mut_if_stmt(&mut self, stmt: &mut IfStmt) {
+        wrap_single_statement_fn_decl(&mut stmt.cons);
+        if let Some(alt) = &mut stmt.alt {
+            wrap_single_statement_fn_decl(alt);
+        }
+        stmt.visit_mut_children_with(self);
+    }
+
+    /// Normalize `label: async function f() {}` before visiting.
+    fn visit_mut_labeled_stmt(&mut self, stmt: &mut LabeledStmt) {
+        wrap_single_statement_fn_decl(&mut stmt.body);
+        stmt.visit_mut_children_with(self);
     }
 
     /// Transform async function declarations.
     fn visit_mut_fn_decl(&mut self, fn_decl: &mut FnDecl) {
         // First visit children to handle nested async functions
+        let outer_name = self.enter_fn_name(Some(&fn_decl.ident.sym));
         fn_decl.visit_mut_children_with(self);
+        self.fn_name = outer_name;
+
+        if !fn_decl.function.is_async || fn_decl.declare || fn_decl.function.body.is_none() {
+            return;
+        }
+        let span = fn_decl.function.span;
+        let name = Some(&fn_decl.ident.sym);
+        let native = self
+            .native_reason(span.lo)
+            .or_else(|| self.filter_reason(FunctionKind::Declaration, span, name));
+        if let Some(reason) = native {
+            self.keep_native(FunctionKind::Declaration, span, name, reason);
+            return;
+        }
+
+        self.current_fn = span;
+        let mut applied = None;
+        if self.config.compat == Compat::Tsc {
+            let function = &mut fn_decl.function;
+            if transform_awaiter_function(function, &self.config, self.hygiene) {
+                self.transformed = true;
+                applied = Some("tslib `__awaiter`");
+            }
+            let still_async = fn_decl.function.is_async;
+            let name = Some(&fn_decl.ident.sym);
+            self.record(FunctionKind::Declaration, span, name, applied, still_async);
+            return;
+        }
 
         // Transform and hoist the helper function
-        if let Some(helper) = transform_fn_decl(fn_decl) {
-            self.scopes.push(Stmt::Decl(Decl::Fn(helper)));
+        let base = format!("{}{}", self.config.helper_prefix, fn_decl.ident.sym);
+        let helper_name = self.unique_name(&base);
+        if let Some(helper) = transform_fn_decl(fn_decl, &helper_name, &self.config, self.hygiene) {
+            self.transformed = true;
+            self.scopes.push(helper);
+            applied = Some(if self.config.eager_helpers {
+                "eager helper"
+            } else {
+                "self-replacing helper"
+            });
         }
+        let still_async = fn_decl.function.is_async;
+        self.record(
+            FunctionKind::Declaration,
+            span,
+            Some(&fn_decl.ident.sym),
+            applied,
+            still_async,
+        );
+    }
+
+    /// Leave `/* @ng-async-ignore */ export async function f() {}` native.
+    ///
+    /// The comment leads the `export` keyword rather than the function, so
+    /// the declaration is checked here. Nested functions are still visited.
+    fn visit_mut_export_decl(&mut self, export: &mut ExportDecl) {
+        if let Decl::Fn(fn_decl) = &mut export.decl {
+            if fn_decl.function.is_async && self.is_ignored(export.span.lo) {
+                fn_decl.visit_mut_children_with(self);
+                if !fn_decl.declare && fn_decl.function.body.is_some() {
+                    self.keep_native(
+                        FunctionKind::Declaration,
+                        fn_decl.function.span,
+                        Some(&fn_decl.ident.sym),
+                        SkipReason::Ignored,
+                    );
+                }
+                return;
+            }
+        }
+        export.visit_mut_children_with(self);
+    }
+
+    /// Infer names for `const name = async () => {}`.
+    fn visit_mut_var_declarator(&mut self, declarator: &mut VarDeclarator) {
+        if let (Pat::Ident(binding), Some(init)) = (&declarator.name, &declarator.init) {
+            self.infer_name(init, &binding.id.sym);
+        }
+        declarator.visit_mut_children_with(self);
+    }
+
+    /// Infer names for `name = async () => {}`.
+    fn visit_mut_assign_expr(&mut self, assign: &mut AssignExpr) {
+        if let (AssignOp::Assign, AssignTarget::Simple(SimpleAssignTarget::Ident(binding))) =
+            (assign.op, &assign.left)
+        {
+            self.infer_name(&assign.right, &binding.id.sym);
+        }
+        assign.visit_mut_children_with(self);
+    }
+
+    /// Infer names for `{ name: async () => {} }`.
+    fn visit_mut_key_value_prop(&mut self, prop: &mut KeyValueProp) {
+        if let PropName::Ident(key) = &prop.key {
+            self.infer_name(&prop.value, &key.sym);
+        }
+        prop.visit_mut_children_with(self);
     }
 
     /// Transform async expressions (arrow functions and function expressions).
     fn visit_mut_expr(&mut self, expr: &mut Expr) {
-        // First visit children
+        // The inferred name only applies to the expression it was set for
+        let name = self.pending_name.take();
+        let hoist = self.hoistable;
+
+        if let Expr::Call(call) = expr {
+            if self.visit_mut_immediate_call(call) {
+                return;
+            }
+        }
+
+        // Async arrows capture `this` one level deeper than their surroundings
+        let is_async_arrow = matches!(
+            expr,
+            Expr::Arrow(arrow) if arrow.is_async && !self.keeps_arrow_native(arrow, name.as_ref())
+        );
+        if is_async_arrow {
+            self.capture_depth += 1;
+        }
+
+        // First visit children, named after the function they are in
+        let own_name = match &*expr {
+            Expr::Fn(fn_expr) => fn_expr.ident.as_ref().or(name.as_ref()),
+            Expr::Arrow(_) => name.as_ref(),
+            _ => None,
+        };
+        let own_name = own_name.map(|name| name.sym.clone());
+        let outer_name = self.enter_fn_name(own_name.as_ref());
         expr.visit_mut_children_with(self);
+        self.fn_name = outer_name;
+
+        let native = match &*expr {
+            Expr::Arrow(arrow) => self.arrow_native_reason(arrow, name.as_ref()),
+            Expr::Fn(fn_expr) => self.fn_expr_native_reason(fn_expr, name.as_ref()),
+            _ => None,
+        };
+        let trivial = match &*expr {
+            Expr::Arrow(arrow) if arrow.is_async && name.is_none() && native.is_none() => {
+                trivial_arrow_facts(arrow, &self.config)
+            }
+            _ => None,
+        };
+
+        match (&mut *expr, native) {
+            // Opted out, outside the selected classes, arrows disabled or
+            // rejected by the filter
+            (Expr::Arrow(arrow), Some(reason)) => {
+                let name = name.as_ref().map(|name| &name.sym);
+                self.keep_native(FunctionKind::Arrow, arrow.span, name, reason);
+            }
+            (Expr::Fn(fn_expr), Some(reason)) => {
+                let span = fn_expr.function.span;
+                let name = fn_expr
+                    .ident
+                    .as_ref()
+                    .or(name.as_ref())
+                    .map(|name| &name.sym);
+                self.keep_native(FunctionKind::Expression, span, name, reason);
+            }
+
+            // async () => { ... } reading nothing of its surroundings, as
+            // the wrapped generator alone
+            (Expr::Arrow(arrow), None) if trivial.is_some() => {
+                let span = arrow.span;
+                self.current_fn = span;
+                let facts = trivial.unwrap_or_default();
+                let transformed =
+                    transform_trivial_arrow(arrow, &facts, &self.config, self.hygiene);
+                let applied = transformed.as_ref().map(TransformedFn::strategy);
+                let still_async = transformed.is_none() && arrow.is_async;
+                if let Some(transformed) = transformed {
+                    self.place_transformed(expr, transformed, None);
+                }
+                self.record(FunctionKind::Arrow, span, None, applied, still_async);
+            }
 
-        match expr {
             // async () => { ... }
-            Expr::Arrow(arrow) if arrow.is_async => {
-                let ref_name = self.ref_counter.next();
-                if let Some(transformed) = transform_arrow_fn(arrow, &ref_name) {
-                    *expr = transformed;
+            (Expr::Arrow(arrow), None) if arrow.is_async => {
+                let span = arrow.span;
+                self.current_fn = span;
+                let ref_name = self.next_ref_name(own_name.as_ref());
+                let capture = CaptureIdents::new(&self.config, self.hygiene, self.capture_depth);
+                let fn_name = name.as_ref().map(|name| name.sym.clone());
+                let transformed = transform_arrow_fn(
+                    arrow,
+                    &ref_name,
+                    name,
+                    &capture,
+                    hoist,
+                    &self.config,
+                    self.hygiene,
+                );
+                let applied = transformed.as_ref().map(TransformedFn::strategy);
+                let still_async = transformed.is_none() && arrow.is_async;
+                if let Some(transformed) = transformed {
+                    self.uses_shared_wrapper |= self.config.shared_arrow_wrapper;
+                    self.place_transformed(expr, transformed, Some(&capture));
                 }
+                self.record(
+                    FunctionKind::Arrow,
+                    span,
+                    fn_name.as_ref(),
+                    applied,
+                    still_async,
+                );
             }
 
             // async function() { ... }
-            Expr::Fn(fn_expr) if fn_expr.function.is_async => {
-                let ref_name = self.ref_counter.next();
-                if let Some(transformed) = transform_fn_expr(fn_expr, &ref_name) {
-                    *expr = transformed;
+            (Expr::Fn(fn_expr), None) if fn_expr.function.is_async => {
+                let span = fn_expr.function.span;
+                self.current_fn = span;
+                let ref_name = self.next_ref_name(own_name.as_ref());
+                let fn_name = fn_expr
+                    .ident
+                    .as_ref()
+                    .or(name.as_ref())
+                    .map(|name| name.sym.clone());
+                let transformed =
+                    transform_fn_expr(fn_expr, &ref_name, name, hoist, &self.config, self.hygiene);
+                let applied = transformed.as_ref().map(TransformedFn::strategy);
+                let still_async = transformed.is_none() && fn_expr.function.is_async;
+                if let Some(transformed) = transformed {
+                    self.place_transformed(expr, transformed, None);
                 }
+                self.record(
+                    FunctionKind::Expression,
+                    span,
+                    fn_name.as_ref(),
+                    applied,
+                    still_async,
+                );
             }
 
             _ => {}
         }
+
+        if is_async_arrow {
+            self.capture_depth -= 1;
+        }
+    }
+
+    /// Regular functions start a new `this` scope with no captures.
+    fn visit_mut_function(&mut self, func: &mut Function) {
+        let depth = std::mem::take(&mut self.capture_depth);
+        self.visit_mut_function_scope(func);
+        self.capture_depth = depth;
+    }
+
+    /// Arrow params and expression bodies run once per call.
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        self.visit_mut_function_scope(arrow);
     }
 
     /// Transform async class methods.
     fn visit_mut_class_method(&mut self, method: &mut ClassMethod) {
-        method.visit_mut_children_with(self);
-        transform_class_method(method);
+        method.key.visit_mut_with(self);
+        let hook = self.in_lifecycle_hook(prop_name(&method.key));
+        let outer_hook = std::mem::replace(&mut self.in_lifecycle_hook, hook);
+        let native = self.native_reason(method.span.lo).or_else(|| {
+            let kind = FunctionKind::ClassMethod;
+            let name = prop_name(&method.key);
+            method
+                .function
+                .is_async
+                .then(|| self.filter_reason(kind, method.span, name))?
+        });
+        let outer_name = self.enter_fn_name(prop_name(&method.key));
+        self.visit_mut_method_function(&mut method.function, native.is_some());
+        self.fn_name = outer_name;
+        self.in_lifecycle_hook = outer_hook;
+        if !method.function.is_async {
+            return;
+        }
+        let name = prop_name(&method.key);
+        if let Some(reason) = native {
+            self.keep_native(FunctionKind::ClassMethod, method.span, name, reason);
+            return;
+        }
+        self.current_fn = method.span;
+        let transformed = transform_class_method(method, &self.config, self.hygiene);
+        self.transformed |= transformed;
+        let applied = transformed.then_some("immediate generator");
+        let still_async = method.function.is_async;
+        let name = prop_name(&method.key);
+        self.record(
+            FunctionKind::ClassMethod,
+            method.span,
+            name,
+            applied,
+            still_async,
+        );
+    }
+
+    /// Transform async private class methods.
+    fn visit_mut_private_method(&mut self, method: &mut PrivateMethod) {
+        method.key.visit_mut_with(self);
+        let native = self.native_reason(method.span.lo).or_else(|| {
+            let kind = FunctionKind::ClassMethod;
+            let name = Some(&method.key.name);
+            method
+                .function
+                .is_async
+                .then(|| self.filter_reason(kind, method.span, name))?
+        });
+        let outer_name = self.enter_fn_name(Some(&method.key.name));
+        self.visit_mut_method_function(&mut method.function, native.is_some());
+        self.fn_name = outer_name;
+        if !method.function.is_async {
+            return;
+        }
+        if let Some(reason) = native {
+            self.keep_native(
+                FunctionKind::ClassMethod,
+                method.span,
+                Some(&method.key.name),
+                reason,
+            );
+            return;
+        }
+        self.current_fn = method.span;
+        let transformed = transform_private_method(method, &self.config, self.hygiene);
+        self.transformed |= transformed;
+        let applied = transformed.then_some("immediate generator");
+        let still_async = method.function.is_async;
+        let name = Some(&method.key.name);
+        self.record(
+            FunctionKind::ClassMethod,
+            method.span,
+            name,
+            applied,
+            still_async,
+        );
+    }
+
+    /// Constructors start a new `this` scope with no captures.
+    fn visit_mut_constructor(&mut self, ctor: &mut Constructor) {
+        let depth = std::mem::take(&mut self.capture_depth);
+        self.visit_mut_function_scope(ctor);
+        self.capture_depth = depth;
     }
 
     /// Transform async object method properties.
     fn visit_mut_prop(&mut self, prop: &mut Prop) {
-        prop.visit_mut_children_with(self);
+        match prop {
+            Prop::Method(method_prop) => {
+                method_prop.key.visit_mut_with(self);
+                let hook = self.in_lifecycle_hook(prop_name(&method_prop.key));
+                let outer_hook = std::mem::replace(&mut self.in_lifecycle_hook, hook);
+                // The comment leads `async`, which the key span does not cover
+                let span = method_prop.function.span;
+                let native = self
+                    .native_reason(span.lo)
+                    .or_else(|| self.native_reason(method_prop.key.span_lo()))
+                    .or_else(|| {
+                        let kind = FunctionKind::ObjectMethod;
+                        let name = prop_name(&method_prop.key);
+                        let is_async = method_prop.function.is_async;
+                        is_async.then(|| self.filter_reason(kind, span, name))?
+                    });
+                let outer_name = self.enter_fn_name(prop_name(&method_prop.key));
+                self.visit_mut_method_function(&mut method_prop.function, native.is_some());
+                self.fn_name = outer_name;
+                self.in_lifecycle_hook = outer_hook;
+                if !method_prop.function.is_async {
+                    return;
+                }
+                if let Some(reason) = native {
+                    let name = prop_name(&method_prop.key);
+                    self.keep_native(FunctionKind::ObjectMethod, span, name, reason);
+                    return;
+                }
+                self.current_fn = span;
+                let transformed = transform_object_method(method_prop, &self.config, self.hygiene);
+                self.transformed |= transformed;
+                let applied = transformed.then_some("immediate generator");
+                let still_async = method_prop.function.is_async;
+                let name = prop_name(&method_prop.key);
+                self.record(FunctionKind::ObjectMethod, span, name, applied, still_async);
+            }
+            // Accessors start a new `this` scope with no captures
+            Prop::Getter(_) | Prop::Setter(_) => {
+                let depth = std::mem::take(&mut self.capture_depth);
+                self.visit_mut_function_scope(prop);
+                self.capture_depth = depth;
+            }
+            _ => prop.visit_mut_children_with(self),
+        }
+    }
+}
+
+// ============================================================================
+// Fold Implementation
+// ============================================================================
+
+/// Adapter for fold-based pipelines. The transform works on a whole program
+/// in place, so folding a program, module or script moves it through
+/// [`VisitMut::visit_mut_program`]. Like there, the AST is only copied to
+/// restore it from with `catch_panics` or `inlineHelper`.
+impl Fold for AsyncToNgGeneratorVisitor {
+    fn fold_program(&mut self, mut program: Program) -> Program {
+        self.visit_mut_program(&mut program);
+        program
+    }
+
+    fn fold_module(&mut self, module: Module) -> Module {
+        match self.fold_program(Program::Module(module)) {
+            Program::Module(module) => module,
+            Program::Script(_) => unreachable!("the transform keeps the program kind"),
+        }
+    }
 
-        if let Prop::Method(method_prop) = prop {
-            transform_object_method(method_prop);
+    fn fold_script(&mut self, script: Script) -> Script {
+        match self.fold_program(Program::Script(script)) {
+            Program::Script(script) => script,
+            Program::Module(_) => unreachable!("the transform keeps the program kind"),
         }
     }
 }
@@ -0,0 +1,128 @@
+//! Async function declarations in single-statement positions.
+//!
+//! `if (x) async function f() {}` and `label: async function f() {}` are
+//! early errors in the spec, yet lenient parsers and other passes can hand
+//! over such a `FnDecl`, with no statement list around it for the hoisted
+//! `_f` helper. The inputs are written with a block and parsed, then each
+//! single-statement block is unwrapped into the bare declaration.
+
+use swc_core::{
+    common::{sync::Lrc, FileName, Globals, SourceMap, GLOBALS},
+    ecma::{
+        ast::*,
+        codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter},
+        parser::{parse_file_as_program, EsSyntax, Syntax},
+        visit::{VisitMut, VisitMutWith},
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::{AsyncToNgGeneratorVisitor, Config};
+
+/// Visitor replacing `if` branches and labeled bodies that are a block of a
+/// single function declaration with the declaration.
+struct UnwrapFnDecls;
+
+impl UnwrapFnDecls {
+    fn unwrap(stmt: &mut Stmt) {
+        if let Stmt::Block(BlockStmt { stmts, .. }) = stmt {
+            if let [Stmt::Decl(Decl::Fn(_))] = stmts.as_slice() {
+                *stmt = stmts.pop().unwrap();
+            }
+        }
+    }
+}
+
+impl VisitMut for UnwrapFnDecls {
+    fn visit_mut_if_stmt(&mut self, stmt: &mut IfStmt) {
+        stmt.visit_mut_children_with(self);
+        Self::unwrap(&mut stmt.cons);
+        if let Some(alt) = &mut stmt.alt {
+            Self::unwrap(alt);
+        }
+    }
+
+    fn visit_mut_labeled_stmt(&mut self, stmt: &mut LabeledStmt) {
+        stmt.visit_mut_children_with(self);
+        Self::unwrap(&mut stmt.body);
+    }
+}
+
+fn parse(cm: &Lrc<SourceMap>, source: &str) -> Program {
+    let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+    let syntax = Syntax::Es(EsSyntax::default());
+    parse_file_as_program(&fm, syntax, EsVersion::latest(), None, &mut vec![]).unwrap()
+}
+
+fn print(cm: &Lrc<SourceMap>, program: &Program) -> String {
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: CodegenConfig::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
+        emitter.emit_program(program).unwrap();
+    }
+    String::from_utf8(buf).unwrap()
+}
+
+/// Transform `input` with its single-statement blocks unwrapped and check
+/// that it prints like `expected`.
+fn check(input: &str, expected: &str) {
+    GLOBALS.set(&Globals::new(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let mut program = parse(&cm, input);
+        program.visit_mut_with(&mut UnwrapFnDecls);
+        program.visit_mut_with(&mut AsyncToNgGeneratorVisitor::with_config(
+            Config::default(),
+        ));
+        assert_eq!(print(&cm, &program), print(&cm, &parse(&cm, expected)));
+    });
+}
+
+#[test]
+fn wraps_if_branches_in_blocks() {
+    check(
+        "if (ready) { async function start() { await boot(); } }
+         else { async function stop() { await halt(); } }",
+        "if (ready) {
+             function start() {
+                 return _start.apply(this, arguments);
+             }
+             function _start() {
+                 _start = _ngAsyncToGenerator(function*() {
+                     yield boot();
+                 });
+                 return _start.apply(this, arguments);
+             }
+         } else {
+             function stop() {
+                 return _stop.apply(this, arguments);
+             }
+             function _stop() {
+                 _stop = _ngAsyncToGenerator(function*() {
+                     yield halt();
+                 });
+                 return _stop.apply(this, arguments);
+             }
+         }",
+    );
+}
+
+#[test]
+fn wraps_labeled_declarations_in_blocks() {
+    check(
+        "setup: { async function init() { await boot(); } }",
+        "setup: {
+             function init() {
+                 return _init.apply(this, arguments);
+             }
+             function _init() {
+                 _init = _ngAsyncToGenerator(function*() {
+                     yield boot();
+                 });
+                 return _init.apply(this, arguments);
+             }
+         }",
+    );
+}
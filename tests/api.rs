@@ -0,0 +1,209 @@
+//! The plugin-free Rust API.
+
+use swc_core::{
+    common::{sync::Lrc, FileName, Globals, Mark, SourceMap, GLOBALS},
+    ecma::{
+        ast::{
+            CallExpr, Callee, EsVersion, ExportNamedSpecifier, ExportSpecifier, Expr, ExprOrSpread,
+            Lit, ModuleDecl, ModuleExportName, ModuleItem, Program, Str,
+        },
+        codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter},
+        parser::{parse_file_as_program, EsSyntax, Syntax},
+        transforms::base::{hygiene::hygiene, resolver},
+        visit::FoldWith,
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::{
+    async_to_ng_generator, runtime_module, transform_program, transform_program_with,
+    AsyncToNgGeneratorVisitor, Config, FunctionKind, TransformContext, WrapperContext,
+    WrapperEmitter,
+};
+
+const SOURCE: &str = "async function load() { return await fetch('/a'); }";
+
+/// Parse `SOURCE`, run `transform` on it and print the result.
+fn run(transform: impl FnOnce(Program) -> Program) -> String {
+    GLOBALS.set(&Globals::new(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let fm = cm.new_source_file(Lrc::new(FileName::Anon), SOURCE.to_string());
+        let program = parse_file_as_program(
+            &fm,
+            Syntax::Es(EsSyntax::default()),
+            EsVersion::latest(),
+            None,
+            &mut vec![],
+        )
+        .unwrap();
+        let program = transform(program);
+
+        let mut buf = vec![];
+        {
+            let mut emitter = Emitter {
+                cfg: CodegenConfig::default().with_minify(true),
+                cm: cm.clone(),
+                comments: None,
+                wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+            };
+            emitter.emit_program(&program).unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    })
+}
+
+#[test]
+fn transforms_a_program() {
+    let output = run(|program| transform_program(program, Config::default()));
+    assert!(
+        output.contains("_ngAsyncToGenerator(function*"),
+        "{}",
+        output
+    );
+    assert!(!output.contains("async"), "{}", output);
+}
+
+#[test]
+fn lowers_generators_of_a_program_without_resolver() {
+    let config = Config::builder().regenerator(true).build().unwrap();
+    let output = run(|program| transform_program(program, config));
+    assert!(output.contains("_ngAsyncToGenerator("), "{}", output);
+    assert!(!output.contains("function*"), "{}", output);
+}
+
+#[test]
+fn applies_the_file_filters() {
+    let config = Config::builder().exclude(["vendor/**"]).build().unwrap();
+    let context = TransformContext {
+        filename: Some("vendor/lib.js".into()),
+        ..Default::default()
+    };
+    let output = run(|program| transform_program_with(program, config, context));
+    assert!(output.starts_with("async function load()"), "{}", output);
+}
+
+#[test]
+fn chains_as_a_pass() {
+    let output = run(|program| {
+        let unresolved_mark = Mark::new();
+        let top_level_mark = Mark::new();
+        program.apply((
+            resolver(unresolved_mark, top_level_mark, false),
+            async_to_ng_generator(Config::default(), unresolved_mark),
+            hygiene(),
+        ))
+    });
+    assert!(
+        output.contains("_ngAsyncToGenerator(function*"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn folds_a_script_or_module() {
+    let output = run(|program| {
+        let mut visitor = AsyncToNgGeneratorVisitor::new();
+        match program {
+            Program::Module(module) => Program::Module(module.fold_with(&mut visitor)),
+            Program::Script(script) => Program::Script(script.fold_with(&mut visitor)),
+        }
+    });
+    assert!(
+        output.contains("_ngAsyncToGenerator(function*"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn exports_the_runtime_helpers() {
+    let module = runtime_module(&Config::default());
+    let Some(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export))) = module.body.last() else {
+        panic!("the runtime module ends with an export");
+    };
+    let exported: Vec<&str> = export
+        .specifiers
+        .iter()
+        .map(|specifier| {
+            let ExportSpecifier::Named(ExportNamedSpecifier {
+                orig: ModuleExportName::Ident(orig),
+                exported: None,
+                ..
+            }) = specifier
+            else {
+                panic!("the runtime module exports its bindings under their names");
+            };
+            &*orig.sym
+        })
+        .collect();
+    assert_eq!(exported, ["_ngAsyncToGenerator", "_ngUsingCtx"]);
+}
+
+/// Wraps the helper call of declarations in `traced("name", ...)`.
+#[derive(Debug)]
+struct Traced;
+
+impl WrapperEmitter for Traced {
+    fn wrap_generator(&self, generator: Expr, context: &WrapperContext<'_>) -> Expr {
+        let wrapped = context.default_wrapper(generator);
+        if context.kind != FunctionKind::Declaration {
+            return wrapped;
+        }
+        let name = Lit::Str(Str::from(context.name.unwrap_or_default()));
+        Expr::Call(CallExpr {
+            callee: Callee::Expr(Box::new(Expr::Ident(context.global_ident("traced")))),
+            args: vec![
+                ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Lit(name)),
+                },
+                ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(wrapped),
+                },
+            ],
+            ..Default::default()
+        })
+    }
+}
+
+/// Emitter that panics, standing in for an internal error.
+#[derive(Debug)]
+struct Panicking;
+
+impl WrapperEmitter for Panicking {
+    fn wrap_generator(&self, _: Expr, _: &WrapperContext<'_>) -> Expr {
+        panic!("emitter failed");
+    }
+}
+
+#[test]
+fn emits_wrappers_with_a_custom_emitter() {
+    let config = Config::builder().wrapper_emitter(Traced).build().unwrap();
+    let output = run(|program| transform_program(program, config));
+    assert!(
+        output.contains("=traced(\"load\",_ngAsyncToGenerator(function*"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn restores_the_program_after_an_internal_error() {
+    let config = Config::builder()
+        .wrapper_emitter(Panicking)
+        .catch_panics(true)
+        .build()
+        .unwrap();
+    let output = run(|program| transform_program(program, config));
+    assert!(output.starts_with("async function load()"), "{}", output);
+}
+
+#[test]
+#[should_panic(expected = "emitter failed")]
+fn passes_panics_on_by_default() {
+    let config = Config::builder()
+        .wrapper_emitter(Panicking)
+        .build()
+        .unwrap();
+    run(|program| transform_program(program, config));
+}
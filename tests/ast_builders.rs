@@ -0,0 +1,116 @@
+//! The public AST builders.
+
+use swc_core::{
+    common::{
+        sync::Lrc, BytePos, Globals, Mark, SourceMap, Span, SyntaxContext, DUMMY_SP, GLOBALS,
+    },
+    ecma::{
+        ast::{Expr, Program, Script, Stmt, ThisExpr, VarDeclKind},
+        codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter},
+        transforms::base::fixer::fixer,
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::ast_builders::*;
+
+/// Print `stmts` as a minified script, after `fixer`.
+fn print(stmts: Vec<Stmt>) -> String {
+    GLOBALS.set(&Globals::new(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let script = Script {
+            span: DUMMY_SP,
+            body: stmts,
+            shebang: None,
+        };
+        let program = Program::Script(script).apply(fixer(None));
+
+        let mut buf = vec![];
+        {
+            let mut emitter = Emitter {
+                cfg: CodegenConfig::default().with_minify(true),
+                cm: cm.clone(),
+                comments: None,
+                wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+            };
+            emitter.emit_program(&program).unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    })
+}
+
+fn print_expr(expr: Expr) -> String {
+    print(vec![expr_stmt(expr)])
+}
+
+fn wrapper() -> Expr {
+    Expr::Ident(ident("_ref"))
+}
+
+#[test]
+fn builds_delegating_calls() {
+    let hygiene = Hygiene::default();
+    assert!(print_expr(apply_call(wrapper(), hygiene)).contains("_ref.apply(this,arguments)"));
+    assert!(print_expr(delegate_call(wrapper(), false, hygiene)).contains("_ref.call(this)"));
+    let captured = apply_call_with_captured_this(wrapper(), ident("_this"), hygiene);
+    assert!(print_expr(captured).contains("_ref.apply(_this,arguments)"));
+}
+
+#[test]
+fn wraps_a_generator_in_the_runtime_helper() {
+    let generator = fn_expr(None, vec![], block(vec![]), true);
+    let wrapped = ng_async_wrapper(generator, NG_ASYNC_WRAPPER, Hygiene::default());
+    let decl = var_decl_with_kind(VarDeclKind::Var, ident("_ref"), wrapped);
+    let output = print(vec![decl]);
+    assert!(
+        output.contains("var _ref=_ngAsyncToGenerator(function*(){})"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn builds_iifes() {
+    let output = print_expr(iife(vec![return_stmt(wrapper())]));
+    assert!(output.contains("(function(){return _ref})()"), "{}", output);
+
+    let captures = vec![(ident("_this"), Expr::This(ThisExpr { span: DUMMY_SP }))];
+    let output = print_expr(iife_with_captures(captures, vec![]));
+    assert!(output.contains("(function(_this){})(this)"), "{}", output);
+}
+
+#[test]
+fn builds_self_replacing_helpers() {
+    let helper = ident("_load");
+    let wrapped = immediate_call(Expr::Ident(ident("make")));
+    let delegate = apply_call(Expr::Ident(helper.clone()), Hygiene::default());
+    let output = print(vec![self_replacing_fn_decl(helper, wrapped, delegate)]);
+    assert!(
+        output.starts_with("function _load(){_load=make();"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("return _load.apply(this,arguments)"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn mints_generated_and_global_identifiers() {
+    GLOBALS.set(&Globals::new(), || {
+        let unresolved = SyntaxContext::empty().apply_mark(Mark::new());
+        let hygiene = Hygiene::fresh(Mark::root(), unresolved);
+        assert_eq!(hygiene.private_ident("_ref").ctxt, hygiene.generated);
+        assert_eq!(hygiene.global_ident("arguments").ctxt, unresolved);
+        assert_ne!(hygiene.generated, SyntaxContext::empty());
+    });
+}
+
+#[test]
+fn moves_generated_code_to_the_original_span() {
+    let span = Span::new(BytePos(1), BytePos(9));
+    let Expr::Call(call) = with_span(immediate_call(wrapper()), span) else {
+        panic!("an immediate call is a call");
+    };
+    assert_eq!(call.span, span);
+}
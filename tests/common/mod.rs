@@ -0,0 +1,97 @@
+//! Helpers shared by the integration tests: the options of a test input and
+//! the parse → transform → print harness.
+
+#![allow(dead_code)]
+
+use std::{fs, path::Path};
+
+use swc_core::{
+    common::{
+        comments::{Comments, SingleThreadedComments},
+        sync::Lrc,
+        FileName, Globals, Mark, SourceMap, GLOBALS,
+    },
+    ecma::{
+        ast::EsVersion,
+        codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter},
+        parser::{parse_file_as_program, EsSyntax, Syntax},
+        transforms::base::{fixer::fixer, hygiene::hygiene, resolver},
+        visit::visit_mut_pass,
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::{AsyncToNgGeneratorVisitor, Config};
+
+/// Options of the test at `input`: the plugin options in a `config.json`
+/// next to it, or the defaults. Invalid options fail the test.
+pub fn input_config(input: &Path) -> Config {
+    let path = input.with_file_name("config.json");
+    if !path.exists() {
+        return Config::default();
+    }
+    let json = fs::read_to_string(&path).unwrap();
+    let config = Config::from_json(&json)
+        .unwrap_or_else(|error| panic!("invalid {}: {}", path.display(), error));
+    if let Err(error) = config.validate() {
+        panic!("invalid {}: {}", path.display(), error);
+    }
+    config
+}
+
+/// Run the plugin alone on `source` and print the result.
+pub fn transform(source: &str, config: Config) -> String {
+    print_transformed(source, config, false)
+}
+
+/// Run `source` through the passes of a build (`resolver`, the plugin,
+/// `hygiene` and `fixer`) and print the result.
+pub fn build(source: &str, config: Config) -> String {
+    print_transformed(source, config, true)
+}
+
+/// Parse `source` as JavaScript, transform it like [`build`] or, unless
+/// `build`, like [`transform`], and print the result with its comments.
+fn print_transformed(source: &str, config: Config, build: bool) -> String {
+    GLOBALS.set(&Globals::new(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+        let comments = SingleThreadedComments::default();
+        let program = parse_file_as_program(
+            &fm,
+            Syntax::Es(EsSyntax::default()),
+            EsVersion::latest(),
+            Some(&comments),
+            &mut vec![],
+        )
+        .expect("test input is valid JavaScript");
+
+        let program = if build {
+            let unresolved_mark = Mark::new();
+            let top_level_mark = Mark::new();
+            let visitor =
+                AsyncToNgGeneratorVisitor::with_marks(config, unresolved_mark, top_level_mark)
+                    .with_comments(comments.clone());
+            program.apply((
+                resolver(unresolved_mark, top_level_mark, false),
+                visit_mut_pass(visitor),
+                hygiene(),
+                fixer(Some(&comments)),
+            ))
+        } else {
+            let visitor =
+                AsyncToNgGeneratorVisitor::with_config(config).with_comments(comments.clone());
+            program.apply(visit_mut_pass(visitor))
+        };
+
+        let mut buf = vec![];
+        {
+            let mut emitter = Emitter {
+                cfg: CodegenConfig::default(),
+                cm: cm.clone(),
+                comments: Some(&comments as &dyn Comments),
+                wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+            };
+            emitter.emit_program(&program).unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    })
+}
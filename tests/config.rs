@@ -0,0 +1,212 @@
+use swc_plugin_transform_async_to_ng_generator::{
+    AwaitlessFunctions, Compat, Config, DeclarationKind, ImportStyle,
+};
+
+#[test]
+fn empty_options_use_defaults() {
+    let config = Config::from_json("{}").unwrap();
+    assert_eq!(config.wrapper_function_name, "_ngAsyncToGenerator");
+    assert_eq!(config.this_var_name, "_this");
+    assert!(config.transform_arrow_functions);
+    assert_eq!(config.awaitless_functions, AwaitlessFunctions::Wrap);
+    assert!(config.import_helper.is_none());
+    assert!(config.import_style.is_none());
+}
+
+#[test]
+fn import_style_is_lower_case() {
+    let config = Config::from_json(r#"{ "importStyle": "cjs" }"#).unwrap();
+    assert_eq!(config.import_style, Some(ImportStyle::Cjs));
+    assert!(Config::from_json(r#"{ "importStyle": "commonjs" }"#).is_err());
+}
+
+#[test]
+fn options_are_camel_case() {
+    let config = Config::from_json(
+        r#"{
+            "wrapperFunctionName": "_wrap",
+            "thisVarName": "self",
+            "transformArrowFunctions": false,
+            "awaitlessFunctions": "promise",
+            "declarationKind": "const",
+            "importHelper": { "module": "@app/ng-async" }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(config.wrapper_function_name, "_wrap");
+    assert_eq!(config.this_var_name, "self");
+    assert!(!config.transform_arrow_functions);
+    assert_eq!(config.awaitless_functions, AwaitlessFunctions::Promise);
+    assert_eq!(config.declaration_kind, Some(DeclarationKind::Const));
+    assert_eq!(config.import_helper.unwrap().module, "@app/ng-async");
+}
+
+#[test]
+fn invalid_options_are_rejected() {
+    assert!(Config::from_json(r#"{ "awaitlessFunctions": "sometimes" }"#).is_err());
+    assert!(Config::from_json(r#"{ "lazyRefs": "yes" }"#).is_err());
+}
+
+#[test]
+fn env_overrides_apply_to_their_environment() {
+    let json = r#"{
+        "thisVarName": "self",
+        "zone": "Zone.current",
+        "env": {
+            "development": { "zone": "NgZone.current", "lazyRefs": true },
+            "production": { "loose": true }
+        }
+    }"#;
+
+    let development = Config::from_json_for_env(json, Some("development")).unwrap();
+    assert_eq!(development.zone.as_deref(), Some("NgZone.current"));
+    assert!(development.lazy_refs);
+    assert!(!development.loose);
+    assert_eq!(development.this_var_name, "self");
+
+    let production = Config::from_json_for_env(json, Some("production")).unwrap();
+    assert_eq!(production.zone.as_deref(), Some("Zone.current"));
+    assert!(production.loose);
+
+    let unknown = Config::from_json_for_env(json, Some("test")).unwrap();
+    assert_eq!(unknown.zone.as_deref(), Some("Zone.current"));
+    assert!(!unknown.lazy_refs && !unknown.loose);
+
+    assert!(Config::from_json(json).is_ok());
+}
+
+#[test]
+fn invalid_env_overrides_are_rejected() {
+    assert!(Config::from_json(r#"{ "env": ["development"] }"#).is_err());
+    assert!(Config::from_json_for_env(
+        r#"{ "env": { "development": true } }"#,
+        Some("development")
+    )
+    .is_err());
+    assert!(Config::from_json_for_env(
+        r#"{ "env": { "development": { "loose": "yes" } } }"#,
+        Some("development"),
+    )
+    .is_err());
+}
+
+#[test]
+fn builder_starts_from_the_defaults() {
+    let config = Config::builder()
+        .wrapper_function_name("ngAsync")
+        .transform_arrow_functions(false)
+        .zone("Zone.current")
+        .build()
+        .unwrap();
+    assert_eq!(config.wrapper_function_name, "ngAsync");
+    assert!(!config.transform_arrow_functions);
+    assert_eq!(config.zone.as_deref(), Some("Zone.current"));
+    assert_eq!(config.this_var_name, "_this");
+}
+
+#[test]
+fn builder_rejects_names_that_are_not_identifiers() {
+    let error = Config::builder()
+        .this_var_name("my-this")
+        .build()
+        .unwrap_err();
+    assert_eq!(error.option, "thisVarName");
+
+    let error = Config::builder().zone("Zone..current").build().unwrap_err();
+    assert_eq!(error.option, "zone");
+    assert_eq!(
+        error.to_string(),
+        "invalid `zone` option: `Zone..current` is not a dotted path such as `Zone.current`"
+    );
+}
+
+#[test]
+fn tsc_compat_rejects_other_helper_shapes() {
+    let config = Config::from_json(r#"{ "compat": "tsc" }"#).unwrap();
+    assert_eq!(config.compat, Compat::Tsc);
+    assert_eq!(config.helper_name(), "__awaiter");
+
+    let error = Config::builder()
+        .compat(Compat::Tsc)
+        .inline_helper(true)
+        .build()
+        .unwrap_err();
+    assert_eq!(error.option, "compat");
+    assert_eq!(
+        error.to_string(),
+        "invalid `compat` option: `\"tsc\"` cannot be combined with `inlineHelper`"
+    );
+}
+
+#[test]
+fn shared_arrow_wrapper_rejects_lazy_refs() {
+    let json = r#"{ "sharedArrowWrapper": true, "lazyRefs": true }"#;
+    let error = Config::from_json(json).unwrap().validate().unwrap_err();
+    assert_eq!(error.option, "sharedArrowWrapper");
+    assert_eq!(
+        error.to_string(),
+        "invalid `sharedArrowWrapper` option: cannot be combined with `lazyRefs`"
+    );
+}
+
+#[test]
+fn regenerator_rejects_passes_after_it() {
+    let json = r#"{ "regenerator": true, "markerComment": "ng-async-generated" }"#;
+    let error = Config::from_json(json).unwrap().validate().unwrap_err();
+    assert_eq!(error.option, "regenerator");
+    assert_eq!(
+        error.to_string(),
+        "invalid `regenerator` option: cannot be combined with `markerComment`"
+    );
+
+    let error = Config::builder()
+        .regenerator(true)
+        .strict_generated_code(true)
+        .build()
+        .unwrap_err();
+    assert_eq!(error.option, "regenerator");
+}
+
+#[test]
+fn helper_names_rename_the_runtime_helpers() {
+    let json = r#"{
+        "wrapperFunctionName": "ngAsync",
+        "helperNames": { "_ngAsyncToGenerator": "appAsync", "_ngUsingCtx": "appUsing" }
+    }"#;
+    let config = Config::from_json(json).unwrap();
+    assert_eq!(config.wrapper_function_name, "appAsync");
+    assert_eq!(config.using_helper_name, "appUsing");
+
+    let error = Config::from_json(r#"{ "helperNames": { "_ngAsync": "x" } }"#).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("unknown helper `_ngAsync` in `helperNames`"));
+}
+
+#[test]
+fn invalid_excluded_function_names_are_rejected() {
+    let error = Config::builder()
+        .exclude_function_names(["^test(", "Mock$"])
+        .build()
+        .unwrap_err();
+    assert_eq!(error.option, "excludeFunctionNames");
+    assert!(error
+        .to_string()
+        .contains("`^test(` is not a regular expression"));
+}
+
+#[test]
+fn plugin_options_are_validated() {
+    let parse = |json| Config::from_options_for_env(json, None);
+    assert!(parse(r#"{ "wrapperFunctionName": "a-b" }"#)
+        .unwrap_err()
+        .to_string()
+        .starts_with("invalid `wrapperFunctionName` option"));
+    assert!(parse(r#"{ "maxNestingDepth": 0 }"#).is_err());
+    assert!(parse(r#"{ "compat": "tsc", "inlineHelper": true }"#).is_err());
+    assert!(parse(r#"{ "sharedArrowWrapper": true, "lazyRefs": true }"#).is_err());
+    assert!(parse(r#"{ "excludeFunctionNames": ["^test("] }"#).is_err());
+
+    let config = Config::from_options_for_env(r#"{ "loose": true }"#, Some("development"));
+    assert_eq!(config.unwrap().named_generators, Some(true));
+}
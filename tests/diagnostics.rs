@@ -0,0 +1,202 @@
+use swc_core::{
+    common::{
+        errors::{Diagnostic, Level, HANDLER},
+        sync::Lrc,
+        FileName,
+    },
+    ecma::{
+        ast::{EsVersion, Expr},
+        parser::{parse_file_as_program, EsSyntax, Syntax},
+        visit::visit_mut_pass,
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::{
+    AsyncToNgGeneratorVisitor, AwaitlessFunctions, Config, WrapperContext, WrapperEmitter,
+};
+use testing::Tester;
+
+/// Diagnostics emitted while transforming `source`.
+fn emitted(source: &str, config: Config) -> Vec<Diagnostic> {
+    let result = Tester::new().errors(|cm, handler| {
+        let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+        let mut program = parse_file_as_program(
+            &fm,
+            Syntax::Es(EsSyntax::default()),
+            EsVersion::latest(),
+            None,
+            &mut vec![],
+        )
+        .unwrap();
+        HANDLER.set(&handler, || {
+            program.mutate(visit_mut_pass(AsyncToNgGeneratorVisitor::with_config(
+                config,
+            )));
+        });
+        Err::<(), _>(())
+    });
+    result.unwrap_err()
+}
+
+/// Messages of the diagnostics emitted while transforming `source`.
+fn diagnostics(source: &str, config: Config) -> Vec<String> {
+    emitted(source, config)
+        .iter()
+        .map(|diagnostic| diagnostic.message())
+        .collect()
+}
+
+#[test]
+fn supported_code_is_silent() {
+    let source = "async function load() { return await fetch('/a'); }";
+    let messages = diagnostics(source, Config::default());
+    assert!(messages.is_empty(), "{:?}", messages);
+}
+
+#[test]
+fn async_generators_are_reported() {
+    let source = "async function* stream() { yield await next(); }";
+    let messages = diagnostics(source, Config::default());
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].starts_with("async generator left untransformed"));
+}
+
+#[test]
+fn super_in_async_methods_is_reported() {
+    let source = "class A extends B { async load() { return await super.load(); } }";
+    let messages = diagnostics(source, Config::default());
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].contains("`super`"));
+}
+
+#[test]
+fn for_await_in_async_functions_is_reported() {
+    let source = "async function drain(s) { for await (const x of s) use(x); }
+                  const all = async (s) => { for await (const x of s) use(x); };";
+    let messages = diagnostics(source, Config::default());
+    assert_eq!(messages.len(), 2);
+    assert!(messages
+        .iter()
+        .all(|message| message.contains("`for await`")));
+
+    // Without another await the bodies are not awaitless either
+    let config = Config {
+        awaitless_functions: AwaitlessFunctions::Drop,
+        ..Config::default()
+    };
+    assert_eq!(diagnostics(source, config).len(), 2);
+}
+
+#[test]
+fn top_level_await_is_reported() {
+    let source = "export const data = await fetch('/a');";
+    let messages = diagnostics(source, Config::default());
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].starts_with("top-level `await`"));
+}
+
+#[test]
+fn local_helper_bindings_are_reported() {
+    let source = "function f(_ngAsyncToGenerator) { return async () => await g(); }";
+    let messages = diagnostics(source, Config::default());
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].contains("shadows the runtime helper"));
+}
+
+#[test]
+fn top_level_helper_declarations_are_not_reported() {
+    let source = "import { _ngAsyncToGenerator } from 'x'; async function f() { await g(); }";
+    let messages = diagnostics(source, Config::default());
+    assert!(messages.is_empty(), "{:?}", messages);
+}
+
+#[test]
+fn unsupported_code_is_an_error_with_error_on_unsupported() {
+    let source = "class A extends B { async load() { return await super.load(); } }";
+    let config = Config::builder()
+        .error_on_unsupported(true)
+        .build()
+        .unwrap();
+    let levels: Vec<Level> = emitted(source, config).iter().map(|d| d.level).collect();
+    assert_eq!(levels, vec![Level::Error]);
+}
+
+#[test]
+fn shadowed_helpers_stay_warnings_with_error_on_unsupported() {
+    let source = "function f(_ngAsyncToGenerator) { return async () => await g(); }";
+    let config = Config::builder()
+        .error_on_unsupported(true)
+        .build()
+        .unwrap();
+    let levels: Vec<Level> = emitted(source, config).iter().map(|d| d.level).collect();
+    assert_eq!(levels, vec![Level::Warning]);
+}
+
+#[test]
+fn deeply_nested_files_are_reported() {
+    let source = format!(
+        "async function f() {{ return await g({}1{}); }}",
+        "[".repeat(50),
+        "]".repeat(50)
+    );
+    let config = Config::builder().max_nesting_depth(20).build().unwrap();
+    let messages = diagnostics(&source, config);
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].contains("nested deeper than 20 levels"));
+}
+
+#[test]
+fn functions_larger_than_max_function_size_are_reported() {
+    let source = "async function small() { await a(); }\n\
+                  async function large() { await b(); await c(); await d(); }";
+    let config = Config::builder().max_function_size(40).build().unwrap();
+    let messages = diagnostics(source, config);
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].starts_with("async function `large` left untransformed"));
+    assert!(messages[0].ends_with("more than `maxFunctionSize` (40)"));
+}
+
+#[test]
+fn debug_explains_every_async_function() {
+    let source = "async function load() { await get(); }\nclass A { async *items() {} }";
+    let config = Config::builder().debug(true).build().unwrap();
+    let notes: Vec<String> = emitted(source, config)
+        .iter()
+        .filter(|diagnostic| diagnostic.level == Level::Note)
+        .map(|diagnostic| diagnostic.message())
+        .collect();
+    assert_eq!(
+        notes,
+        vec![
+            "async function `load` transformed: self-replacing helper",
+            "async class method `items` left native: not supported by the transform",
+        ]
+    );
+}
+
+/// Emitter that panics, standing in for an internal error.
+#[derive(Debug)]
+struct Panicking;
+
+impl WrapperEmitter for Panicking {
+    fn wrap_generator(&self, _: Expr, _: &WrapperContext<'_>) -> Expr {
+        panic!("emitter failed");
+    }
+}
+
+#[test]
+fn internal_errors_leave_the_file_untransformed() {
+    let source = "async function load() { await get(); }\nasync function save() { await put(); }";
+    let config = Config::builder()
+        .wrapper_emitter(Panicking)
+        .catch_panics(true)
+        .build()
+        .unwrap();
+    let emitted = emitted(source, config);
+    let levels: Vec<Level> = emitted.iter().map(|d| d.level).collect();
+    assert_eq!(levels, vec![Level::Warning]);
+    assert_eq!(
+        emitted[0].message(),
+        "internal error while transforming this async function (emitter failed); the file was \
+         left untransformed"
+    );
+}
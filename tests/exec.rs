@@ -0,0 +1,87 @@
+//! Execution tests: every `tests/exec/**/input.js` must print the same
+//! under Node once transformed, with the reference runtime helper of
+//! `ngAsyncToGenerator.js`, as it prints untransformed.
+//!
+//! Snapshots only show what the output looks like; these scripts log
+//! resolved values, rejections, `this` bindings and the order their steps
+//! run in, so they check that it behaves like native async functions.
+//! A `config.json` next to a script holds the plugin options to transform
+//! it with.
+//!
+//! Without a `node` executable the tests fail, so a CI job without Node
+//! cannot pass them unnoticed; set `SKIP_EXEC=1` to skip them instead.
+
+use std::{
+    env, fs,
+    io::{ErrorKind, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+mod common;
+
+/// The runtime helper the transformed scripts run with.
+const RUNTIME: &str = include_str!("../ngAsyncToGenerator.js");
+
+#[testing::fixture("tests/exec/**/input.js")]
+fn exec(input: PathBuf) {
+    let source = fs::read_to_string(&input).unwrap();
+    let Some(expected) = run_node(&source) else {
+        assert!(
+            skip_exec(),
+            "node is needed to run {}; install it, or set SKIP_EXEC=1 to skip the execution \
+             tests",
+            input.display()
+        );
+        eprintln!("skipping {}: node is not available", input.display());
+        return;
+    };
+
+    let config = common::input_config(&input);
+    let regenerator = config.regenerator;
+    let transformed = common::build(&source, config);
+    assert!(
+        transformed.contains("_ngAsyncToGenerator("),
+        "{}",
+        transformed
+    );
+    if regenerator {
+        assert!(!transformed.contains("function*"), "{}", transformed);
+    }
+    let actual = run_node(&format!("{}\n{}", RUNTIME, transformed)).unwrap();
+    assert_eq!(actual, expected, "transformed script:\n{}", transformed);
+}
+
+/// Whether the tests may be skipped when there is no `node`.
+fn skip_exec() -> bool {
+    env::var_os("SKIP_EXEC").is_some_and(|value| value != "0")
+}
+
+/// Run `script` with `node` and return what it printed, or `None` when
+/// there is no `node` to run it with.
+fn run_node(script: &str) -> Option<String> {
+    let mut child = match Command::new("node")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(error) if error.kind() == ErrorKind::NotFound => return None,
+        Err(error) => panic!("failed to run node: {}", error),
+    };
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "node failed on:\n{}\n{}",
+        script,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Some(String::from_utf8(output.stdout).unwrap())
+}
@@ -0,0 +1,33 @@
+const log = [];
+
+async function task(name, steps) {
+    log.push(name + " start");
+    for (let i = 0; i < steps; i++) {
+        await null;
+        log.push(name + " step " + i);
+    }
+    log.push(name + " end");
+    return name;
+}
+
+const arrowTask = async (name) => {
+    log.push(name + " start");
+    await Promise.resolve();
+    log.push(name + " end");
+};
+
+async function main() {
+    log.push("main start");
+    const a = task("a", 3);
+    const b = task("b", 2);
+    const c = arrowTask("c");
+    log.push("main sync end");
+    Promise.resolve().then(() => log.push("microtask"));
+    await Promise.all([a, b, c]);
+    log.push("main end");
+    for (const entry of log) {
+        console.log(entry);
+    }
+}
+
+main();
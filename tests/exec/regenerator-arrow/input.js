@@ -0,0 +1,18 @@
+function Timer(label) {
+    this.label = label;
+    this.tick = async (count) => {
+        const ticks = [];
+        for (let i = 0; i < count; i++) {
+            ticks.push(this.label + (await i));
+        }
+        return ticks;
+    };
+}
+
+const twice = async (value) => (await value) * 2;
+
+new Timer("t")
+    .tick(3)
+    .then((ticks) => console.log("ticks", ticks.join(",")))
+    .then(() => twice(Promise.resolve(21)))
+    .then((value) => console.log("twice", value));
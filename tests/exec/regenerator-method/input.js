@@ -0,0 +1,34 @@
+class Queue {
+    constructor() {
+        this.items = [];
+    }
+
+    async push(item) {
+        await null;
+        this.items.push(item);
+        return this.items.length;
+    }
+
+    async drain() {
+        const drained = [];
+        while (this.items.length) {
+            drained.push(await this.items.shift());
+        }
+        return drained;
+    }
+}
+
+const queue = new Queue();
+const cache = {
+    hits: 0,
+    async get(key) {
+        this.hits += 1;
+        return (await queue.push(key)) + ":" + arguments.length;
+    },
+};
+
+cache
+    .get("a")
+    .then((value) => console.log("get", value, cache.hits))
+    .then(() => queue.drain())
+    .then((drained) => console.log("drained", drained.join(",")));
@@ -0,0 +1,25 @@
+async function sum(values) {
+    let total = 0;
+    for (const value of values) {
+        total += await value;
+    }
+    return total;
+}
+
+async function fail(message) {
+    await null;
+    throw new Error(message);
+}
+
+async function recover() {
+    try {
+        await fail("boom");
+    } catch (error) {
+        return "recovered from " + error.message;
+    } finally {
+        console.log("finally");
+    }
+}
+
+sum([1, Promise.resolve(2), 3]).then((total) => console.log("sum", total));
+recover().then((message) => console.log(message));
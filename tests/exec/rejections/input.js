@@ -0,0 +1,42 @@
+async function fail(message) {
+    await null;
+    throw new Error(message);
+}
+
+async function failSynchronously() {
+    throw new Error("before any await");
+}
+
+async function recover() {
+    try {
+        await fail("caught");
+    } catch (error) {
+        return "recovered from " + error.message;
+    } finally {
+        console.log("finally runs");
+    }
+}
+
+const rethrow = async () => {
+    try {
+        await Promise.reject(new Error("inner"));
+    } catch (error) {
+        throw new Error("outer after " + error.message);
+    }
+};
+
+async function main() {
+    const pending = failSynchronously();
+    console.log("failSynchronously returned", pending instanceof Promise);
+    await pending.catch((error) => console.log("rejected:", error.message));
+    await fail("uncaught").catch((error) => console.log("rejected:", error.message));
+    console.log(await recover());
+    await rethrow().catch((error) => console.log("rejected:", error.message));
+    try {
+        await fail("awaited");
+    } catch (error) {
+        console.log("caught in caller:", error.message);
+    }
+}
+
+main();
@@ -0,0 +1,33 @@
+async function double(value) {
+    return (await value) * 2;
+}
+
+const add = async (a, b) => (await a) + (await b);
+
+const total = async function (...values) {
+    let sum = 0;
+    for (const value of values) {
+        sum += await double(value);
+    }
+    return sum;
+};
+
+const nested = async () => {
+    const inner = async () => [await Promise.resolve(1), await 2];
+    return (await inner()).concat(await (async () => 3)());
+};
+
+async function returnsPromise() {
+    return Promise.resolve("adopted");
+}
+
+async function main() {
+    console.log(await double(21));
+    console.log(await add(Promise.resolve(1), 2));
+    console.log(await total(1, Promise.resolve(2), 3));
+    console.log(JSON.stringify(await nested()));
+    console.log(await returnsPromise());
+    console.log(typeof double(1).then, double.length, add.length, total.length);
+}
+
+main();
@@ -0,0 +1,21 @@
+let failures = 2;
+
+const retry = async function attempt(n) {
+    await null;
+    if (failures-- > 0 && n > 0) {
+        return attempt(n - 1);
+    }
+    return n;
+};
+
+const countdown = async function tick(n, self = tick) {
+    await null;
+    return n === 0 ? [self === tick] : [n, ...(await tick(n - 1))];
+};
+
+async function main() {
+    console.log(await retry(5), retry.name, retry.length);
+    console.log(JSON.stringify(await countdown(3)), countdown.name, countdown.length);
+}
+
+main();
@@ -0,0 +1,44 @@
+function fail(message) {
+    return Promise.reject(new Error(message));
+}
+
+async function plain() {
+    return await fail("plain");
+}
+
+async function inTry() {
+    try {
+        return await fail("try");
+    } catch (error) {
+        return "caught " + error.message;
+    }
+}
+
+async function inCatch() {
+    try {
+        await fail("first");
+    } catch (error) {
+        return await fail("catch");
+    } finally {
+        console.log("finally after catch");
+    }
+}
+
+async function inFinally() {
+    try {
+        return "try";
+    } finally {
+        console.log("finally runs first");
+        return await Promise.resolve("finally");
+    }
+}
+
+// One at a time: dropping an `await` may change how the calls interleave
+async function main() {
+    await plain().catch((error) => console.log("rejected", error.message));
+    console.log(await inTry());
+    await inCatch().catch((error) => console.log("rejected", error.message));
+    console.log(await inFinally());
+}
+
+main();
@@ -0,0 +1,49 @@
+class Counter {
+    constructor() {
+        this.count = 0;
+    }
+
+    async increment(by = 1) {
+        await null;
+        this.count += by;
+        return this.count;
+    }
+
+    async incrementLater() {
+        const step = async () => {
+            await null;
+            return this.increment(arguments.length);
+        };
+        return step();
+    }
+
+    static async create() {
+        await null;
+        return new this();
+    }
+}
+
+const store = {
+    name: "store",
+    async describe(suffix) {
+        await null;
+        return this.name + suffix;
+    },
+};
+
+async function whoAmI() {
+    await null;
+    return this && this.name;
+}
+
+async function main() {
+    const counter = await Counter.create();
+    console.log(counter instanceof Counter);
+    console.log(await counter.increment(2));
+    console.log(await counter.incrementLater("a", "b"));
+    console.log(await store.describe("!"));
+    console.log(await whoAmI.call({ name: "bound" }));
+    console.log(await whoAmI.apply({ name: "applied" }, []));
+}
+
+main();
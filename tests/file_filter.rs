@@ -0,0 +1,55 @@
+use swc_plugin_transform_async_to_ng_generator::Config;
+
+fn config(include: &[&str], exclude: &[&str]) -> Config {
+    Config::builder()
+        .include(include.iter().copied())
+        .exclude(exclude.iter().copied())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn transforms_everything_by_default() {
+    let config = Config::default();
+    assert!(config.should_transform_file(Some("/repo/src/app.js")));
+    assert!(config.should_transform_file(None));
+}
+
+#[test]
+fn include_restricts_files() {
+    let config = config(&["src/**/*.js"], &[]);
+    assert!(config.should_transform_file(Some("/repo/src/app.js")));
+    assert!(config.should_transform_file(Some("/repo/src/pages/home/page.js")));
+    assert!(!config.should_transform_file(Some("/repo/scripts/build.js")));
+    assert!(!config.should_transform_file(None));
+}
+
+#[test]
+fn exclude_wins_over_include() {
+    let config = config(&["**/*.js"], &["node_modules/**", "vendor/*.min.js"]);
+    assert!(config.should_transform_file(Some("/repo/src/app.js")));
+    assert!(!config.should_transform_file(Some("/repo/node_modules/lib/index.js")));
+    assert!(!config.should_transform_file(Some("/repo/vendor/angular.min.js")));
+    assert!(config.should_transform_file(Some("/repo/vendor/nested/angular.min.js")));
+}
+
+#[test]
+fn windows_paths_match() {
+    let config = config(&[], &["node_modules/**"]);
+    assert!(!config.should_transform_file(Some(r"C:\repo\node_modules\lib\index.js")));
+    assert!(config.should_transform_file(Some(r"C:\repo\src\app.js")));
+}
+
+#[test]
+fn skip_test_files_skips_specs_and_tests() {
+    let config = Config::builder().skip_test_files(true).build().unwrap();
+    assert!(!config.should_transform_file(Some("/repo/src/app.spec.ts")));
+    assert!(!config.should_transform_file(Some("/repo/src/app.test.js")));
+    assert!(!config.should_transform_file(Some("/repo/src/__tests__/app.js")));
+    assert!(config.should_transform_file(Some("/repo/src/app.js")));
+    assert!(config.should_transform_file(Some("/repo/src/inspect.js")));
+    assert!(config.should_transform_file(None));
+
+    let config = Config::default();
+    assert!(config.should_transform_file(Some("/repo/src/app.spec.ts")));
+}
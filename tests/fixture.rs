@@ -1,19 +1,96 @@
-use std::path::PathBuf;
-use swc_core::ecma::{
-    parser::{EsSyntax, Syntax},
-    transforms::testing::test_fixture,
-    visit::visit_mut_pass,
+use std::path::{Path, PathBuf};
+
+use swc_core::{
+    common::Mark,
+    ecma::{
+        parser::{EsSyntax, Syntax, TsSyntax},
+        transforms::{base::resolver, testing::test_fixture},
+        visit::visit_mut_pass,
+    },
 };
 use swc_plugin_transform_async_to_ng_generator::AsyncToNgGeneratorVisitor;
 
-#[testing::fixture("tests/fixture/**/input.js")]
+mod common;
+
+/// Every fixture is a directory of its own directly under `tests/fixture/`,
+/// e.g. `tests/fixture/loose/`, run with the options of the `config.json`
+/// next to its input.
+#[testing::fixture("tests/fixture/*/input.js")]
 fn fixture(input: PathBuf) {
-    let output = input.with_file_name("output.js");
+    run_fixture(&input, &input.with_file_name("output.js"), es_syntax(false));
+}
+
+/// Running `resolver` first must not change the output.
+#[testing::fixture("tests/fixture/*/input.js")]
+fn fixture_with_resolver(input: PathBuf) {
+    let config = common::input_config(&input);
     test_fixture(
-        Syntax::Es(EsSyntax::default()),
-        &|_| visit_mut_pass(AsyncToNgGeneratorVisitor::new()),
+        es_syntax(false),
+        &|t| {
+            let unresolved_mark = Mark::new();
+            let top_level_mark = Mark::new();
+            (
+                resolver(unresolved_mark, top_level_mark, false),
+                visit_mut_pass(
+                    AsyncToNgGeneratorVisitor::with_marks(
+                        config.clone(),
+                        unresolved_mark,
+                        top_level_mark,
+                    )
+                    .with_comments((*t.comments).clone()),
+                ),
+            )
+        },
         &input,
-        &output,
+        &input.with_file_name("output.js"),
+        Default::default(),
+    );
+}
+
+#[testing::fixture("tests/fixture/*/input.jsx")]
+fn fixture_jsx(input: PathBuf) {
+    run_fixture(&input, &input.with_file_name("output.jsx"), es_syntax(true));
+}
+
+#[testing::fixture("tests/fixture/*/input.ts")]
+fn fixture_ts(input: PathBuf) {
+    run_fixture(&input, &input.with_file_name("output.ts"), ts_syntax(false));
+}
+
+#[testing::fixture("tests/fixture/*/input.tsx")]
+fn fixture_tsx(input: PathBuf) {
+    run_fixture(&input, &input.with_file_name("output.tsx"), ts_syntax(true));
+}
+
+fn es_syntax(jsx: bool) -> Syntax {
+    Syntax::Es(EsSyntax {
+        jsx,
+        decorators: true,
+        explicit_resource_management: true,
+        ..Default::default()
+    })
+}
+
+fn ts_syntax(tsx: bool) -> Syntax {
+    Syntax::Typescript(TsSyntax {
+        tsx,
+        decorators: true,
+        ..Default::default()
+    })
+}
+
+fn run_fixture(input: &Path, output: &Path, syntax: Syntax) {
+    let config = common::input_config(input);
+    test_fixture(
+        syntax,
+        &|t| {
+            visit_mut_pass(
+                AsyncToNgGeneratorVisitor::with_config(config.clone())
+                    .with_comments((*t.comments).clone()),
+            )
+        },
+        input,
+        output,
         Default::default(),
     );
 }
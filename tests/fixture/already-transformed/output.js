@@ -0,0 +1,13 @@
+// Test: files that already contain generated wrappers are left alone
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch('/api');
+    });
+    return _load.apply(this, arguments);
+}
+async function untouched() {
+    await other();
+}
@@ -0,0 +1,13 @@
+// Test: arguments inside async methods and arrows refers to the outer function
+class Logger {
+    async log() {
+        await this.ready;
+        return this.write(arguments);
+    }
+}
+
+function retryAll() {
+    return tasks.map(async (task) => {
+        await task.run(...arguments);
+    });
+}
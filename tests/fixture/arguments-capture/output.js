@@ -0,0 +1,20 @@
+// Test: arguments inside async methods and arrows refers to the outer function
+class Logger {
+    log() {
+        var _this = this;
+        var _arguments = arguments;
+        return _ngAsyncToGenerator(function*() {
+            yield _this.ready;
+            return _this.write(_arguments);
+        })();
+    }
+}
+function retryAll() {
+    var _arguments = arguments;
+    var _ref = _ngAsyncToGenerator(function*(task) {
+        yield task.run(..._arguments);
+    });
+    return tasks.map(function(_x) {
+        return _ref.apply(this, arguments);
+    });
+}
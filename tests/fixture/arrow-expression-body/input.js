@@ -0,0 +1,6 @@
+// Test: awaits nested inside arrow expression bodies
+const sum = async (a, b) => (await a) + b;
+
+const wrap = async x => f(await x);
+
+const direct = async () => await load();
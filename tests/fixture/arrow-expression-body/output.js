@@ -0,0 +1,20 @@
+// Test: awaits nested inside arrow expression bodies
+var _ref, _ref1, _ref2;
+_ref = _ngAsyncToGenerator(function*(a, b) {
+    return (yield a) + b;
+});
+const sum = function sum(_x, _x2) {
+    return _ref.apply(this, arguments);
+};
+_ref1 = _ngAsyncToGenerator(function*(x) {
+    return f(yield x);
+});
+const wrap = function wrap(_x) {
+    return _ref1.apply(this, arguments);
+};
+_ref2 = _ngAsyncToGenerator(function*() {
+    return yield load();
+});
+const direct = function direct() {
+    return _ref2.apply(this, arguments);
+};
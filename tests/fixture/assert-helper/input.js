@@ -0,0 +1,4 @@
+// Test: assertHelper checks that the global helper exists
+async function load() {
+    return await fetch('/api');
+}
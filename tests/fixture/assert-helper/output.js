@@ -0,0 +1,13 @@
+if (typeof _ngAsyncToGenerator !== "function") {
+    throw new Error("`_ngAsyncToGenerator` is not defined: the async functions of this file were compiled by swc-plugin-transform-async-to-ng-generator and need its runtime helper. Load `ngAsyncToGenerator.js` before this file, or set the `importHelper` or `inlineHelper` option of the plugin.");
+}
+// Test: assertHelper checks that the global helper exists
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch('/api');
+    });
+    return _load.apply(this, arguments);
+}
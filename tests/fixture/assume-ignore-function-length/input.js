@@ -0,0 +1,8 @@
+// Test: assuming ignoreFunctionLength, wrappers get no placeholder params
+async function load(id, options) {
+    return await fetch(id, options);
+}
+
+const remove = async (id) => {
+    await api.delete(id);
+};
@@ -0,0 +1,16 @@
+// Test: assuming ignoreFunctionLength, wrappers get no placeholder params
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*(id, options) {
+        return yield fetch(id, options);
+    });
+    return _load.apply(this, arguments);
+}
+var _ref = _ngAsyncToGenerator(function*(id) {
+    yield api.delete(id);
+});
+const remove = function remove() {
+    return _ref.apply(this, arguments);
+};
@@ -1,4 +1,4 @@
-// Test: async function without await
+// Test: async function without await still returns a promise
 async function noAwait(arg) {
     console.log('sync code');
     window._test = { init: true };
@@ -1,8 +1,14 @@
-// Test: async function without await
-function noAwait(arg) {
-    console.log('sync code');
-    window._test = {
-        init: true
-    };
-    return arg;
+// Test: async function without await still returns a promise
+function noAwait(_x) {
+    return _noAwait.apply(this, arguments);
+}
+function _noAwait() {
+    _noAwait = _ngAsyncToGenerator(function*(arg) {
+        console.log('sync code');
+        window._test = {
+            init: true
+        };
+        return arg;
+    });
+    return _noAwait.apply(this, arguments);
 }
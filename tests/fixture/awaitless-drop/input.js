@@ -0,0 +1,10 @@
+// Test: awaitlessFunctions "drop" only removes the async keyword
+async function noAwait(arg) {
+    return arg;
+}
+const arrow = async () => 1;
+class Service {
+    async load() {
+        return await this.fetch();
+    }
+}
@@ -0,0 +1,13 @@
+// Test: awaitlessFunctions "drop" only removes the async keyword
+function noAwait(arg) {
+    return arg;
+}
+const arrow = ()=>1;
+class Service {
+    load() {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            return yield _this.fetch();
+        })();
+    }
+}
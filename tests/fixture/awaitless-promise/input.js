@@ -0,0 +1,14 @@
+// Test: awaitlessFunctions "promise" settles a promise around the body
+async function parse(text) {
+    "use strict";
+    return JSON.parse(text);
+}
+const double = async (x) => x * 2;
+class Child extends Base {
+    async read() {
+        return super.read(this.id);
+    }
+    async load() {
+        return await this.read();
+    }
+}
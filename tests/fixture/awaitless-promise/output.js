@@ -0,0 +1,38 @@
+// Test: awaitlessFunctions "promise" settles a promise around the body
+function parse(text) {
+    "use strict";
+    try {
+        return Promise.resolve((()=>{
+            "use strict";
+            return JSON.parse(text);
+        })());
+    } catch (e) {
+        return Promise.reject(e);
+    }
+}
+const double = (x)=>{
+    try {
+        return Promise.resolve((()=>{
+            return x * 2;
+        })());
+    } catch (e) {
+        return Promise.reject(e);
+    }
+};
+class Child extends Base {
+    read() {
+        try {
+            return Promise.resolve((()=>{
+                return super.read(this.id);
+            })());
+        } catch (e) {
+            return Promise.reject(e);
+        }
+    }
+    load() {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            return yield _this.read();
+        })();
+    }
+}
@@ -0,0 +1,27 @@
+// Test: one walk of the body decides on forwarding, return awaits and eval
+async function latest() {
+    const sources = () => arguments;
+    return await fetchAll(sources());
+}
+
+async function sized() {
+    const view = {
+        get size() {
+            return arguments.length;
+        },
+    };
+    return await view.size;
+}
+
+async function guarded() {
+    try {
+        return await load();
+    } catch (error) {
+        return await fallback(error);
+    }
+}
+
+async function evaluate() {
+    const run = (code) => eval(code);
+    return await run("ready");
+}
@@ -0,0 +1,42 @@
+// Test: one walk of the body decides on forwarding, return awaits and eval
+function latest() {
+    return _latest.apply(this, arguments);
+}
+function sized() {
+    return _sized.call(this);
+}
+function guarded() {
+    return _guarded.call(this);
+}
+async function evaluate() {
+    const run = (code)=>eval(code);
+    return await run("ready");
+}
+function _latest() {
+    _latest = _ngAsyncToGenerator(function*() {
+        const sources = ()=>arguments;
+        return fetchAll(sources());
+    });
+    return _latest.apply(this, arguments);
+}
+function _sized() {
+    _sized = _ngAsyncToGenerator(function*() {
+        const view = {
+            get size () {
+                return arguments.length;
+            }
+        };
+        return view.size;
+    });
+    return _sized.call(this);
+}
+function _guarded() {
+    _guarded = _ngAsyncToGenerator(function*() {
+        try {
+            return yield load();
+        } catch (error) {
+            return yield fallback(error);
+        }
+    });
+    return _guarded.call(this);
+}
@@ -0,0 +1,16 @@
+// Test: callStyle "call" changes how wrappers delegate
+async function load(id, options) {
+    return await fetch(id, options);
+}
+
+async function search(query, page = 1) {
+    return await find(query, page);
+}
+
+async function log(message) {
+    await send(message, arguments.length);
+}
+
+const remove = async (id) => {
+    await api.delete(id);
+};
@@ -0,0 +1,34 @@
+// Test: callStyle "spread" changes how wrappers delegate
+function load(_x, _x2, ...args) {
+    return _load.call(this, _x, _x2, ...args);
+}
+function search(_x, ...args) {
+    return _search.call(this, _x, ...args);
+}
+function log(_x, ...args) {
+    return _log.call(this, _x, ...args);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*(id, options) {
+        return yield fetch(id, options);
+    });
+    return _load.apply(this, arguments);
+}
+function _search() {
+    _search = _ngAsyncToGenerator(function*(query, page = 1) {
+        return yield find(query, page);
+    });
+    return _search.apply(this, arguments);
+}
+function _log() {
+    _log = _ngAsyncToGenerator(function*(message) {
+        yield send(message, arguments.length);
+    });
+    return _log.apply(this, arguments);
+}
+var _ref = _ngAsyncToGenerator(function*(id) {
+    yield api.delete(id);
+});
+const remove = function remove(_x, ...args) {
+    return _ref.call(this, _x, ...args);
+};
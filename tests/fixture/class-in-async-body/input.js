@@ -0,0 +1,31 @@
+// Test: classes and accessors in an async body keep their own `this`
+class Registry {
+    async define(name) {
+        const owner = this;
+        class Entry {
+            static registry = this;
+            label = this.constructor.name;
+            #id = this.label;
+            [this.key] = name;
+            constructor() {
+                this.owner = owner;
+            }
+            static {
+                this.entries = [];
+            }
+        }
+        const view = {
+            get entry() {
+                return this;
+            },
+        };
+        return await this.store(name, Entry, view);
+    }
+}
+
+setTimeout(async () => {
+    class Box {
+        owner = this;
+    }
+    await build(Box);
+});
@@ -0,0 +1,33 @@
+// Test: classes and accessors in an async body keep their own `this`
+class Registry {
+    define(name) {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            const owner = _this;
+            class Entry {
+                static registry = this;
+                label = this.constructor.name;
+                #id = this.label;
+                [_this.key] = name;
+                constructor(){
+                    this.owner = owner;
+                }
+                static{
+                    this.entries = [];
+                }
+            }
+            const view = {
+                get entry () {
+                    return this;
+                }
+            };
+            return yield _this.store(name, Entry, view);
+        })();
+    }
+}
+setTimeout(_ngAsyncToGenerator(function*() {
+    class Box {
+        owner = this;
+    }
+    yield build(Box);
+}));
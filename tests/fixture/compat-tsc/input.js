@@ -0,0 +1,14 @@
+// Test: compat "tsc" rewrites async functions in place to tslib's __awaiter
+export async function load(id) {
+    return await fetch(`/api/${id}`, arguments);
+}
+export const refresh = async () => await load(1);
+export class Store {
+    async save(item) {
+        "use strict";
+        this.items = await post(item);
+    }
+}
+export const remove = async function remove(id) {
+    await del(id);
+};
@@ -0,0 +1,24 @@
+import { __awaiter } from "tslib";
+// Test: compat "tsc" rewrites async functions in place to tslib's __awaiter
+export function load(id) {
+    return __awaiter(this, arguments, void 0, function*() {
+        return yield fetch(`/api/${id}`, arguments);
+    });
+}
+export const refresh = ()=>__awaiter(this, void 0, void 0, function*() {
+    return yield load(1);
+});
+export class Store {
+    save(item) {
+        "use strict";
+        return __awaiter(this, void 0, void 0, function*() {
+            "use strict";
+            this.items = yield post(item);
+        });
+    }
+}
+export const remove = function remove(id) {
+    return __awaiter(this, void 0, void 0, function*() {
+        yield del(id);
+    });
+};
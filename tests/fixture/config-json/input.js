@@ -0,0 +1,9 @@
+// Test: options from the fixture's config.json
+async function refresh() {
+    await reload();
+}
+class View {
+    render() {
+        return async () => await this.refresh();
+    }
+}
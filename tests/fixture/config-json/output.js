@@ -0,0 +1,21 @@
+// Test: options from the fixture's config.json
+function refresh() {
+    return _refresh.call(this);
+}
+function _refresh() {
+    _refresh = ngAsync(function*() {
+        yield reload();
+    });
+    return _refresh.call(this);
+}
+class View {
+    render() {
+        var self = this;
+        var _ref = ngAsync(function*() {
+            return yield self.refresh();
+        });
+        return function() {
+            return _ref.call(self);
+        };
+    }
+}
@@ -0,0 +1,7 @@
+// Test: coverageIgnoreHint tags generated functions without original code
+async function load() {
+    return await fetch("/a");
+}
+for (const id of ids) {
+    tasks.push(async (retry) => await load(id, retry));
+}
@@ -0,0 +1,24 @@
+// Test: coverageIgnoreHint tags generated functions without original code
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch("/a");
+    }, /* istanbul ignore next */ function() {
+        return Zone.current;
+    });
+    return _load.apply(this, arguments);
+}
+for (const id of ids){
+    tasks.push(function() {
+        var _ref = _ngAsyncToGenerator(function*(retry) {
+            return yield load(id, retry);
+        }, /* istanbul ignore next */ function() {
+            return Zone.current;
+        });
+        return /* istanbul ignore next */ function(_x) {
+            return _ref.apply(this, arguments);
+        };
+    }());
+}
@@ -0,0 +1,18 @@
+// Test: custom prefixes skip names already used in the file
+var asyncRef = 1;
+
+function impl_load() {
+    return cache;
+}
+
+async function load() {
+    return await fetch(impl_load());
+}
+
+const save = async (item) => {
+    await store(item, asyncRef);
+};
+
+const remove = async function (item) {
+    await drop(item);
+};
@@ -0,0 +1,27 @@
+// Test: custom prefixes skip names already used in the file
+var asyncRef = 1;
+function impl_load() {
+    return cache;
+}
+function load() {
+    return impl_load2.apply(this, arguments);
+}
+function impl_load2() {
+    impl_load2 = _ngAsyncToGenerator(function*() {
+        return yield fetch(impl_load());
+    });
+    return impl_load2.apply(this, arguments);
+}
+var asyncRef1, asyncRef2;
+asyncRef1 = _ngAsyncToGenerator(function*(item) {
+    yield store(item, asyncRef);
+});
+const save = function save(_x) {
+    return asyncRef1.apply(this, arguments);
+};
+asyncRef2 = _ngAsyncToGenerator(function*(item) {
+    yield drop(item);
+});
+const remove = function remove(_x) {
+    return asyncRef2.apply(this, arguments);
+};
@@ -0,0 +1,7 @@
+// Test: declarationKind "let" applies to every generated binding
+const load = async () => await fetch("/a");
+class View {
+    async render() {
+        return await this.draw(arguments);
+    }
+}
@@ -0,0 +1,16 @@
+// Test: declarationKind "let" applies to every generated binding
+let _ref = _ngAsyncToGenerator(function*() {
+    return yield fetch("/a");
+});
+const load = function load() {
+    return _ref.apply(this, arguments);
+};
+class View {
+    render() {
+        let _this = this;
+        let _arguments = arguments;
+        return _ngAsyncToGenerator(function*() {
+            return yield _this.draw(_arguments);
+        })();
+    }
+}
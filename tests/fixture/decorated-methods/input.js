@@ -0,0 +1,13 @@
+// Test: decorators stay on the transformed method, in their original order
+class Widget {
+    @HostListener('click')
+    async onClick() {
+        await this.refresh();
+    }
+
+    @Log()
+    @Debounce(100)
+    async #load(id) {
+        return await this.api.get(id);
+    }
+}
@@ -0,0 +1,18 @@
+// Test: decorators stay on the transformed method, in their original order
+class Widget {
+    @HostListener('click')
+    onClick() {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            yield _this.refresh();
+        })();
+    }
+    @Log()
+    @Debounce(100)
+    #load(id) {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            return yield _this.api.get(id);
+        })();
+    }
+}
@@ -1,5 +1,5 @@
 // Test: async function with default arguments
-function fetchData() {
+function fetchData(_x) {
     return _fetchData.apply(this, arguments);
 }
 function _fetchData() {
@@ -1,8 +1,8 @@
 // Test: async function with destructuring parameters
-function processUser() {
+function processUser(_x) {
     return _processUser.apply(this, arguments);
 }
-function handleRequest() {
+function handleRequest(_x, _x2) {
     return _handleRequest.apply(this, arguments);
 }
 function _processUser() {
@@ -0,0 +1,17 @@
+// Test: directive prologues are kept on both the outer function and the generator
+async function strict(a) {
+    "use strict";
+    return await a;
+}
+
+const arrow = async () => {
+    'use strict';
+    await tick();
+};
+
+const obj = {
+    async method() {
+        "use strict";
+        await this.run();
+    }
+};
@@ -0,0 +1,30 @@
+// Test: directive prologues are kept on both the outer function and the generator
+function strict(_x) {
+    "use strict";
+    return _strict.apply(this, arguments);
+}
+function _strict() {
+    _strict = _ngAsyncToGenerator(function*(a) {
+        "use strict";
+        return yield a;
+    });
+    return _strict.apply(this, arguments);
+}
+var _ref = _ngAsyncToGenerator(function*() {
+    'use strict';
+    yield tick();
+});
+const arrow = function arrow() {
+    'use strict';
+    return _ref.apply(this, arguments);
+};
+const obj = {
+    method () {
+        "use strict";
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            "use strict";
+            yield _this.run();
+        })();
+    }
+};
@@ -0,0 +1,7 @@
+// Test: eagerHelpers creates the generator wrappers up front
+async function load(id) {
+    return await fetch(id);
+}
+export async function save() {
+    await store();
+}
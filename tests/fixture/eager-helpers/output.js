@@ -0,0 +1,13 @@
+// Test: eagerHelpers creates the generator wrappers up front
+function load(_x) {
+    return _load.apply(this, arguments);
+}
+export function save() {
+    return _save.apply(this, arguments);
+}
+var _load = _ngAsyncToGenerator(function*(id) {
+    return yield fetch(id);
+});
+var _save = _ngAsyncToGenerator(function*() {
+    yield store();
+});
@@ -0,0 +1,28 @@
+// Test: esnextOutput uses const bindings and arrow wrappers
+let _ref, _ref2;
+_ref = _ngAsyncToGenerator(function*(id, options) {
+    return yield fetch(id, options);
+});
+const load = (_x, _x2, ...args)=>_ref(_x, _x2, ...args);
+class View {
+    render() {
+        const _this = this;
+        const _arguments = arguments;
+        return _ngAsyncToGenerator(function*() {
+            return yield _this.draw(_arguments);
+        })();
+    }
+    handler() {
+        const _this = this;
+        const _ref1 = _ngAsyncToGenerator(function*() {
+            return yield _this.refresh();
+        });
+        return (...args)=>_ref1(...args);
+    }
+}
+_ref2 = _ngAsyncToGenerator(function*(item) {
+    yield store(item);
+});
+const save = function save(_x) {
+    return _ref2.apply(this, arguments);
+};
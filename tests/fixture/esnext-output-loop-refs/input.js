@@ -0,0 +1,4 @@
+// Test: block-scoped refs are hoisted into loop bodies
+for (let i = 0; i < n; i++) {
+    tasks.push(async (attempt) => await step(i, attempt));
+}
@@ -0,0 +1,7 @@
+// Test: block-scoped refs are hoisted into loop bodies
+for(let i = 0; i < n; i++){
+    const _ref = _ngAsyncToGenerator(function*(attempt) {
+        return yield step(i, attempt);
+    });
+    tasks.push((_x, ...args)=>_ref(_x, ...args));
+}
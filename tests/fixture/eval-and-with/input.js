@@ -0,0 +1,10 @@
+// Test: async functions using direct eval stay native
+async function run(code) {
+    await ready();
+    return eval(code);
+}
+
+async function indirect(code) {
+    await ready();
+    return (0, eval)(code);
+}
@@ -0,0 +1,15 @@
+// Test: async functions using direct eval stay native
+async function run(code) {
+    await ready();
+    return eval(code);
+}
+function indirect(_x) {
+    return _indirect.apply(this, arguments);
+}
+function _indirect() {
+    _indirect = _ngAsyncToGenerator(function*(code) {
+        yield ready();
+        return (0, eval)(code);
+    });
+    return _indirect.apply(this, arguments);
+}
@@ -0,0 +1,18 @@
+// Test: excludeFunctionNames keeps functions with matching names native
+async function testLoad() {
+    return await fetch('/a');
+}
+
+const ApiMock = {
+    async loadMock() {
+        return await fixture();
+    }
+};
+
+const testSave = async () => {
+    await store();
+};
+
+async function load() {
+    return await fetch('/b');
+}
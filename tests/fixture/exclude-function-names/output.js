@@ -0,0 +1,21 @@
+// Test: excludeFunctionNames keeps functions with matching names native
+async function testLoad() {
+    return await fetch('/a');
+}
+const ApiMock = {
+    async loadMock () {
+        return await fixture();
+    }
+};
+const testSave = async ()=>{
+    await store();
+};
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch('/b');
+    });
+    return _load.apply(this, arguments);
+}
@@ -0,0 +1,25 @@
+// Test: async functions with a for await loop stay native
+async function drain(stream) {
+    for await (const chunk of stream) {
+        write(chunk);
+    }
+}
+class Reader {
+    async readAll(stream) {
+        const chunks = [];
+        for await (const chunk of stream) chunks.push(chunk);
+        return chunks;
+    }
+}
+const collect = async (stream) => {
+    for await (const item of stream) {
+        await store(item);
+    }
+};
+async function pipe(source) {
+    const stream = await source.open();
+    const consume = async () => {
+        for await (const chunk of stream) write(chunk);
+    };
+    return consume;
+}
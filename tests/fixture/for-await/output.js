@@ -0,0 +1,31 @@
+// Test: async functions with a for await loop stay native
+async function drain(stream) {
+    for await (const chunk of stream) {
+        write(chunk);
+    }
+}
+class Reader {
+    async readAll(stream) {
+        const chunks = [];
+        for await (const chunk of stream) chunks.push(chunk);
+        return chunks;
+    }
+}
+const collect = async (stream) => {
+    for await (const item of stream) {
+        await store(item);
+    }
+};
+function pipe(_x) {
+    return _pipe.apply(this, arguments);
+}
+function _pipe() {
+    _pipe = _ngAsyncToGenerator(function*(source) {
+        const stream = yield source.open();
+        const consume = async () => {
+            for await (const chunk of stream) write(chunk);
+        };
+        return consume;
+    });
+    return _pipe.apply(this, arguments);
+}
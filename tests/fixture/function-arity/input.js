@@ -0,0 +1,16 @@
+// Test: delegating wrappers keep the original function length
+async function load(id, options) {
+    return await fetch(id, options);
+}
+
+async function search(query, page = 1, ...filters) {
+    return await find(query, page, filters);
+}
+
+const remove = async (id) => {
+    await api.delete(id);
+};
+
+const update = async function (id, { patch }, ...rest) {
+    return await api.patch(id, patch, rest);
+};
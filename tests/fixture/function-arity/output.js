@@ -0,0 +1,32 @@
+// Test: delegating wrappers keep the original function length
+function load(_x, _x2) {
+    return _load.apply(this, arguments);
+}
+function search(_x) {
+    return _search.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*(id, options) {
+        return yield fetch(id, options);
+    });
+    return _load.apply(this, arguments);
+}
+function _search() {
+    _search = _ngAsyncToGenerator(function*(query, page = 1, ...filters) {
+        return yield find(query, page, filters);
+    });
+    return _search.apply(this, arguments);
+}
+var _ref, _ref1;
+_ref = _ngAsyncToGenerator(function*(id) {
+    yield api.delete(id);
+});
+const remove = function remove(_x) {
+    return _ref.apply(this, arguments);
+};
+_ref1 = _ngAsyncToGenerator(function*(id, { patch }, ...rest) {
+    return yield api.patch(id, patch, rest);
+});
+const update = function update(_x, _x2) {
+    return _ref1.apply(this, arguments);
+};
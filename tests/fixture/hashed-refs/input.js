@@ -0,0 +1,12 @@
+// Test: hashedRefs names refs after their function and its position
+const load = async () => await fetch('/a');
+
+function retryAll() {
+    return tasks.map(async (task) => {
+        await task.run();
+    });
+}
+
+const save = async function () {
+    await store();
+};
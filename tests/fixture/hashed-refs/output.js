@@ -0,0 +1,22 @@
+// Test: hashedRefs names refs after their function and its position
+var _ref_61cc4a, _ref_df8c53;
+_ref_61cc4a = _ngAsyncToGenerator(function*() {
+    return yield fetch('/a');
+});
+const load = function load() {
+    return _ref_61cc4a.apply(this, arguments);
+};
+function retryAll() {
+    var _ref_a865eb = _ngAsyncToGenerator(function*(task) {
+        yield task.run();
+    });
+    return tasks.map(function(_x) {
+        return _ref_a865eb.apply(this, arguments);
+    });
+}
+_ref_df8c53 = _ngAsyncToGenerator(function*() {
+    yield store();
+});
+const save = function save() {
+    return _ref_df8c53.apply(this, arguments);
+};
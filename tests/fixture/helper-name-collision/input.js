@@ -0,0 +1,8 @@
+// Test: hoisted helpers skip names already used in the file
+function _load() {
+    return cache;
+}
+
+async function load() {
+    return await fetch(_load());
+}
@@ -0,0 +1,13 @@
+// Test: hoisted helpers skip names already used in the file
+function _load() {
+    return cache;
+}
+function load() {
+    return _load2.apply(this, arguments);
+}
+function _load2() {
+    _load2 = _ngAsyncToGenerator(function*() {
+        return yield fetch(_load());
+    });
+    return _load2.apply(this, arguments);
+}
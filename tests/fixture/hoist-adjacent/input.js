@@ -0,0 +1,16 @@
+// Test: hoistPosition "adjacent" keeps each helper next to its wrapper
+async function load() {
+    return await fetch("/a");
+}
+function render() {
+    draw();
+}
+export async function save(item) {
+    await store(item);
+}
+function setup() {
+    async function init() {
+        await ready();
+    }
+    return init;
+}
@@ -0,0 +1,34 @@
+// Test: hoistPosition "adjacent" keeps each helper next to its wrapper
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch("/a");
+    });
+    return _load.apply(this, arguments);
+}
+function render() {
+    draw();
+}
+export function save(_x) {
+    return _save.apply(this, arguments);
+}
+function _save() {
+    _save = _ngAsyncToGenerator(function*(item) {
+        yield store(item);
+    });
+    return _save.apply(this, arguments);
+}
+function setup() {
+    function init() {
+        return _init.apply(this, arguments);
+    }
+    function _init() {
+        _init = _ngAsyncToGenerator(function*() {
+            yield ready();
+        });
+        return _init.apply(this, arguments);
+    }
+    return init;
+}
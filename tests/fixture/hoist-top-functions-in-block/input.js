@@ -0,0 +1,8 @@
+// Test: hoistPosition "top" puts helpers at the start of the block
+function setup() {
+    const ready = prepare();
+    async function init() {
+        await ready;
+    }
+    return init;
+}
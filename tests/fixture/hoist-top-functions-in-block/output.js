@@ -0,0 +1,14 @@
+// Test: hoistPosition "top" puts helpers at the start of the block
+function setup() {
+    function _init() {
+        _init = _ngAsyncToGenerator(function*() {
+            yield ready;
+        });
+        return _init.apply(this, arguments);
+    }
+    const ready = prepare();
+    function init() {
+        return _init.apply(this, arguments);
+    }
+    return init;
+}
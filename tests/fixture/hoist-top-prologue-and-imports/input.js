@@ -0,0 +1,16 @@
+#!/usr/bin/env node
+// Test: helpers go after the hashbang, directives and imports
+"use strict";
+import { fetch } from "./api";
+import { store } from "./store";
+save();
+async function save() {
+    await store(await fetch("/a"));
+}
+function setup() {
+    "use strict";
+    return init;
+    async function init() {
+        await fetch("/init");
+    }
+}
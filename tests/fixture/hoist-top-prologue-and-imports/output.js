@@ -0,0 +1,28 @@
+#!/usr/bin/env node
+// Test: helpers go after the hashbang, directives and imports
+"use strict";
+import { fetch } from "./api";
+import { store } from "./store";
+function _save() {
+    _save = _ngAsyncToGenerator(function*() {
+        yield store(yield fetch("/a"));
+    });
+    return _save.apply(this, arguments);
+}
+save();
+function save() {
+    return _save.apply(this, arguments);
+}
+function setup() {
+    "use strict";
+    function _init() {
+        _init = _ngAsyncToGenerator(function*() {
+            yield fetch("/init");
+        });
+        return _init.apply(this, arguments);
+    }
+    return init;
+    function init() {
+        return _init.apply(this, arguments);
+    }
+}
@@ -0,0 +1,6 @@
+// Test: generated bindings are renamed instead of shadowing user bindings
+var _this = "outer";
+
+const report = async () => {
+    await send(this, _this);
+};
@@ -0,0 +1,9 @@
+// Test: generated bindings are renamed instead of shadowing user bindings
+var _this = "outer";
+var _this1 = this;
+var _ref = _ngAsyncToGenerator(function*() {
+    yield send(_this1, _this);
+});
+const report = function report() {
+    return _ref.apply(_this1, arguments);
+};
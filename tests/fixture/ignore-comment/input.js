@@ -0,0 +1,21 @@
+// Test: @ng-async-ignore keeps a function native
+// @ng-async-ignore
+async function native() {
+    return await fetch("/native");
+}
+async function load() {
+    return await fetch("/load");
+}
+class Service {
+    // @ng-async-ignore
+    async refresh() {
+        const run = async () => await this.fetch();
+        return await run();
+    }
+}
+const api = {
+    // @ng-async-ignore
+    async get() {
+        return await fetch("/get");
+    }
+};
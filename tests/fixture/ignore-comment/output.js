@@ -0,0 +1,33 @@
+// Test: @ng-async-ignore keeps a function native
+// @ng-async-ignore
+async function native() {
+    return await fetch("/native");
+}
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch("/load");
+    });
+    return _load.apply(this, arguments);
+}
+class Service {
+    // @ng-async-ignore
+    async refresh() {
+        var _this = this;
+        var _ref = _ngAsyncToGenerator(function*() {
+            return yield _this.fetch();
+        });
+        const run = function run() {
+            return _ref.apply(_this, arguments);
+        };
+        return await run();
+    }
+}
+const api = {
+    // @ng-async-ignore
+    async get () {
+        return await fetch("/get");
+    }
+};
@@ -0,0 +1,15 @@
+// Test: immediately invoked async functions call the wrapped generator directly
+(async () => {
+    await init();
+})();
+(async function (config) {
+    await setup(config);
+})(options);
+class App {
+    start() {
+        (async () => {
+            await this.boot(arguments);
+        })();
+    }
+    ready = (async () => await this.reload())();
+}
@@ -0,0 +1,21 @@
+// Test: immediately invoked async functions call the wrapped generator directly
+_ngAsyncToGenerator(function*() {
+    yield init();
+})();
+_ngAsyncToGenerator(function*(config) {
+    yield setup(config);
+})(options);
+class App {
+    start() {
+        var _this = this;
+        var _arguments = arguments;
+        _ngAsyncToGenerator(function*() {
+            yield _this.boot(_arguments);
+        })();
+    }
+    ready = function(_this) {
+        return _ngAsyncToGenerator(function*() {
+            return yield _this.reload();
+        });
+    }(this)();
+}
@@ -0,0 +1,6 @@
+// Test: an existing import of the helper is not duplicated
+import { _ngAsyncToGenerator } from "ng-async";
+
+export async function load() {
+    return await fetch("/a");
+}
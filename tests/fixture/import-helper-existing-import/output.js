@@ -0,0 +1,11 @@
+// Test: an existing import of the helper is not duplicated
+import { _ngAsyncToGenerator } from "ng-async";
+export function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch("/a");
+    });
+    return _load.apply(this, arguments);
+}
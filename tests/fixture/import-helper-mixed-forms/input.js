@@ -0,0 +1,14 @@
+// Test: the helper is imported once for files mixing every async form
+export async function load() {
+    return await fetch("/a");
+}
+
+export const save = async (data) => {
+    await post(data);
+};
+
+export class Store {
+    async refresh() {
+        this.items = await load();
+    }
+}
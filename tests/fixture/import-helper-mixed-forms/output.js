@@ -0,0 +1,25 @@
+import { _ngAsyncToGenerator } from "ng-async";
+// Test: the helper is imported once for files mixing every async form
+export function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch("/a");
+    });
+    return _load.apply(this, arguments);
+}
+var _ref = _ngAsyncToGenerator(function*(data) {
+    yield post(data);
+});
+export const save = function save(_x) {
+    return _ref.apply(this, arguments);
+};
+export class Store {
+    refresh() {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            _this.items = yield load();
+        })();
+    }
+}
@@ -0,0 +1,4 @@
+// Test: nothing is imported when no function needed the helper
+export function ping() {
+    return 1;
+}
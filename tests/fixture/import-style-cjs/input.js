@@ -0,0 +1,4 @@
+// Test: importStyle "cjs" requires the helper in modules too
+export async function load() {
+    return await fetch('/api');
+}
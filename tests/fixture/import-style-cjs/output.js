@@ -0,0 +1,11 @@
+var _ngAsyncToGenerator = require("@app/ng-async")._ngAsyncToGenerator;
+// Test: importStyle "cjs" requires the helper in modules too
+export function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch('/api');
+    });
+    return _load.apply(this, arguments);
+}
@@ -0,0 +1,4 @@
+// Test: importStyle "global" uses tslib's global `__awaiter` without importing it
+async function load() {
+    return await fetch('/api');
+}
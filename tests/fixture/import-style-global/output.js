@@ -0,0 +1,6 @@
+// Test: importStyle "global" uses tslib's global `__awaiter` without importing it
+function load() {
+    return __awaiter(this, void 0, void 0, function*() {
+        return yield fetch('/api');
+    });
+}
@@ -0,0 +1,18 @@
+// Test: wrappers keep the name inferred from their binding
+let onSave;
+onSave = async () => {
+    await save();
+};
+
+const handlers = {
+    refresh: async function () {
+        return await reload(this.id);
+    },
+    delete: async () => {
+        await remove();
+    }
+};
+
+const _ref = async () => {
+    await noop();
+};
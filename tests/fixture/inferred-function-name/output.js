@@ -0,0 +1,29 @@
+// Test: wrappers keep the name inferred from their binding
+let onSave;
+var _ref1, _ref2, _ref3, _ref4;
+_ref1 = _ngAsyncToGenerator(function*() {
+    yield save();
+});
+onSave = function onSave() {
+    return _ref1.apply(this, arguments);
+};
+_ref2 = _ngAsyncToGenerator(function*() {
+    return yield reload(this.id);
+});
+_ref3 = _ngAsyncToGenerator(function*() {
+    yield remove();
+});
+const handlers = {
+    refresh: function refresh() {
+        return _ref2.apply(this, arguments);
+    },
+    delete: function() {
+        return _ref3.apply(this, arguments);
+    }
+};
+_ref4 = _ngAsyncToGenerator(function*() {
+    yield noop();
+});
+const _ref = function _ref() {
+    return _ref4.apply(this, arguments);
+};
@@ -0,0 +1,21 @@
+// Test: async functions inside JSX attributes and expression containers
+class Toolbar extends Component {
+    async save() {
+        await this.props.onSave(...arguments);
+    }
+
+    render() {
+        return <button onClick={async () => { await this.save(); }}/>;
+    }
+}
+
+function List({ items }) {
+    return <ul>{items.map(async (item) => await load(item))}</ul>;
+}
+
+function Form(props) {
+    return <form onSubmit={async (event) => {
+        event.preventDefault();
+        await props.submit(arguments[0]);
+    }}/>;
+}
@@ -0,0 +1,41 @@
+// Test: async functions inside JSX attributes and expression containers
+class Toolbar extends Component {
+    save() {
+        var _this = this;
+        var _arguments = arguments;
+        return _ngAsyncToGenerator(function*() {
+            yield _this.props.onSave(..._arguments);
+        })();
+    }
+    render() {
+        return <button onClick={function(_this) {
+            var _ref = _ngAsyncToGenerator(function*() {
+                yield _this.save();
+            });
+            return function() {
+                return _ref.apply(_this, arguments);
+            };
+        }(this)}/>;
+    }
+}
+function List({ items }) {
+    return <ul>{items.map(function() {
+        var _ref1 = _ngAsyncToGenerator(function*(item) {
+            return yield load(item);
+        });
+        return function(_x) {
+            return _ref1.apply(this, arguments);
+        };
+    }())}</ul>;
+}
+function Form(props) {
+    return <form onSubmit={function(_arguments) {
+        var _ref2 = _ngAsyncToGenerator(function*(event) {
+            event.preventDefault();
+            yield props.submit(_arguments[0]);
+        });
+        return function(_x) {
+            return _ref2.apply(this, arguments);
+        };
+    }(arguments)}/>;
+}
@@ -0,0 +1,10 @@
+// Test: lazyRefs creates generator wrappers on the first call
+const load = async (id) => await fetch(id);
+const handler = async function () {
+    await this.save();
+};
+class View {
+    render() {
+        return async () => await this.refresh();
+    }
+}
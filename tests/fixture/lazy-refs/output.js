@@ -0,0 +1,34 @@
+// Test: lazyRefs creates generator wrappers on the first call
+var _ref, _ref1;
+_ref = function() {
+    _ref = _ngAsyncToGenerator(function*(id) {
+        return yield fetch(id);
+    });
+    return _ref.apply(this, arguments);
+};
+const load = function load(_x) {
+    return _ref.apply(this, arguments);
+};
+_ref1 = function() {
+    _ref1 = _ngAsyncToGenerator(function*() {
+        yield this.save();
+    });
+    return _ref1.apply(this, arguments);
+};
+const handler = function handler() {
+    return _ref1.apply(this, arguments);
+};
+class View {
+    render() {
+        var _this = this;
+        var _ref2 = function() {
+            _ref2 = _ngAsyncToGenerator(function*() {
+                return yield _this.refresh();
+            });
+            return _ref2.apply(this, arguments);
+        };
+        return function() {
+            return _ref2.apply(_this, arguments);
+        };
+    }
+}
@@ -0,0 +1,25 @@
+// Test: lifecycleHooksOnly only transforms lifecycle hooks and the functions inside them
+class ListComponent {
+    async ngOnInit() {
+        this.items = await this.api.list();
+    }
+    ngAfterViewInit() {
+        this.sub = this.events.subscribe(async (event) => {
+            await this.handle(event);
+        });
+    }
+    async reload() {
+        await this.api.list();
+    }
+}
+const listController = {
+    async $onInit() {
+        await this.load();
+    },
+    async load() {
+        await fetchList();
+    }
+};
+async function fetchList() {
+    await tick();
+}
@@ -0,0 +1,35 @@
+// Test: lifecycleHooksOnly only transforms lifecycle hooks and the functions inside them
+class ListComponent {
+    ngOnInit() {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            _this.items = yield _this.api.list();
+        })();
+    }
+    ngAfterViewInit() {
+        var _this = this;
+        var _ref = _ngAsyncToGenerator(function*(event) {
+            yield _this.handle(event);
+        });
+        this.sub = this.events.subscribe(function(_x) {
+            return _ref.apply(_this, arguments);
+        });
+    }
+    async reload() {
+        await this.api.list();
+    }
+}
+const listController = {
+    $onInit () {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            yield _this.load();
+        })();
+    },
+    async load () {
+        await fetchList();
+    }
+};
+async function fetchList() {
+    await tick();
+}
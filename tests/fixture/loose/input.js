@@ -0,0 +1,18 @@
+// Test: loose mode skips forwarding arguments when nothing reads them
+async function refresh() {
+    await reload();
+}
+async function log() {
+    await write(arguments);
+}
+async function save(item) {
+    await store(item);
+}
+class View {
+    render() {
+        return async () => await this.refresh();
+    }
+}
+const ping = async function () {
+    await send();
+};
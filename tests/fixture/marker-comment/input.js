@@ -0,0 +1,14 @@
+// Test: markerComment marks the statements the plugin generates
+async function load(id) {
+    return await fetch(id);
+}
+
+class Store {
+    async refresh() {
+        this.items = await load(this.id);
+    }
+}
+
+const remove = async (id) => {
+    await del(id);
+};
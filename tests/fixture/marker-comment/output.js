@@ -0,0 +1,24 @@
+// Test: markerComment marks the statements the plugin generates
+function load(_x) {
+    /* ng-async-generated */ return _load.apply(this, arguments);
+}
+/* ng-async-generated */ function _load() {
+    _load = _ngAsyncToGenerator(function*(id) {
+        return yield fetch(id);
+    });
+    return _load.apply(this, arguments);
+}
+class Store {
+    refresh() {
+        /* ng-async-generated */ var _this = this;
+        /* ng-async-generated */ return _ngAsyncToGenerator(function*() {
+            _this.items = yield load(_this.id);
+        })();
+    }
+}
+/* ng-async-generated */ var _ref = _ngAsyncToGenerator(function*(id) {
+    yield del(id);
+});
+const remove = function remove(_x) {
+    /* ng-async-generated */ return _ref.apply(this, arguments);
+};
@@ -0,0 +1,17 @@
+// Test: async methods keep destructured params with defaults on the outer method
+class Repository {
+    async save({ id, retries = 3 } = {}, [first, second = first] = []) {
+        await this.store(id, first, second);
+        return retries;
+    }
+
+    async #load({ id } = this.defaults) {
+        return await this.fetch(id);
+    }
+}
+
+const cache = {
+    async get(key, { ttl = 60, ...options } = {}) {
+        return await this.read(key, ttl, options);
+    },
+};
@@ -0,0 +1,24 @@
+// Test: async methods keep destructured params with defaults on the outer method
+class Repository {
+    save({ id, retries = 3 } = {}, [first, second = first] = []) {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            yield _this.store(id, first, second);
+            return retries;
+        })();
+    }
+    #load({ id } = this.defaults) {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            return yield _this.fetch(id);
+        })();
+    }
+}
+const cache = {
+    get (key, { ttl = 60, ...options } = {}) {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            return yield _this.read(key, ttl, options);
+        })();
+    }
+};
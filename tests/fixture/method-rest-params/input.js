@@ -0,0 +1,14 @@
+// Test: async methods keep rest and default parameters on the outer method
+class Logger {
+    async log(level, ...messages) {
+        await this.flush();
+        return this.write(level, messages);
+    }
+}
+
+const store = {
+    async merge(target = {}, ...sources) {
+        const loaded = await Promise.all(sources);
+        return Object.assign(target, ...loaded);
+    }
+};
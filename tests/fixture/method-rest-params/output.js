@@ -0,0 +1,18 @@
+// Test: async methods keep rest and default parameters on the outer method
+class Logger {
+    log(level, ...messages) {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            yield _this.flush();
+            return _this.write(level, messages);
+        })();
+    }
+}
+const store = {
+    merge (target = {}, ...sources) {
+        return _ngAsyncToGenerator(function*() {
+            const loaded = yield Promise.all(sources);
+            return Object.assign(target, ...loaded);
+        })();
+    }
+};
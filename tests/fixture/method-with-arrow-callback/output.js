@@ -8,14 +8,13 @@ class Controller {
         var _this = this;
         return _ngAsyncToGenerator(function*() {
             _this.loading = true;
-            yield _this.service.wrapper(function(_this) {
-                var _ref = _ngAsyncToGenerator(function*() {
-                    yield _this.loadData();
-                });
-                return function() {
-                    return _ref.apply(_this, arguments);
-                };
-            }(_this), {
+            var _this2 = _this;
+            var _ref = _ngAsyncToGenerator(function*() {
+                yield _this2.loadData();
+            });
+            yield _this.service.wrapper(function() {
+                return _ref.apply(_this2, arguments);
+            }, {
                 onFinally: ()=>{
                     _this.loading = false;
                 }
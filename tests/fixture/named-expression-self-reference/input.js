@@ -0,0 +1,18 @@
+// Test: named async function expressions calling themselves keep calling the wrapper
+const retry = async function attempt(n) {
+    try {
+        return await load();
+    } catch (error) {
+        if (n > 0) return attempt(n - 1);
+        throw error;
+    }
+};
+const poll = async function poll() {
+    await tick();
+};
+function watch(source) {
+    return source.subscribe(async function next(value, again = next) {
+        await apply(value);
+        return again === next;
+    });
+}
@@ -0,0 +1,33 @@
+// Test: named async function expressions calling themselves keep calling the wrapper
+const retry = function() {
+    var _ref = _ngAsyncToGenerator(function*(n) {
+        try {
+            return yield load();
+        } catch (error) {
+            if (n > 0) return attempt(n - 1);
+            throw error;
+        }
+    });
+    function attempt(_x) {
+        return _ref.apply(this, arguments);
+    }
+    return attempt;
+}();
+var _ref1 = _ngAsyncToGenerator(function*() {
+    yield tick();
+});
+const poll = function poll() {
+    return _ref1.apply(this, arguments);
+};
+function watch(source) {
+    return source.subscribe(function() {
+        var _ref2 = _ngAsyncToGenerator(function*(value, again = next) {
+            yield apply(value);
+            return again === next;
+        });
+        function next(_x) {
+            return _ref2.apply(this, arguments);
+        }
+        return next;
+    }());
+}
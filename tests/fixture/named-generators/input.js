@@ -0,0 +1,13 @@
+// Test: namedGenerators names generators after their function
+async function load() {
+    return await fetch("/a");
+}
+const save = async (item) => {
+    await store(item);
+};
+class Service {
+    async refresh() {
+        this.items = await load();
+    }
+}
+run(async () => await tick());
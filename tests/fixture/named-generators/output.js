@@ -0,0 +1,27 @@
+// Test: namedGenerators names generators after their function
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function* _load$() {
+        return yield fetch("/a");
+    });
+    return _load.apply(this, arguments);
+}
+var _ref = _ngAsyncToGenerator(function* _save$(item) {
+    yield store(item);
+});
+const save = function save(_x) {
+    return _ref.apply(this, arguments);
+};
+class Service {
+    refresh() {
+        var _this = this;
+        return _ngAsyncToGenerator(function* _refresh$() {
+            _this.items = yield load();
+        })();
+    }
+}
+run(_ngAsyncToGenerator(function* _callee$() {
+    return yield tick();
+}));
@@ -0,0 +1,8 @@
+// Test: transformArrowFunctions false leaves async arrows native
+class Service {
+    async load() {
+        const items = await this.fetch();
+        return items.map(async (item) => await this.expand(item));
+    }
+}
+const run = async () => await main();
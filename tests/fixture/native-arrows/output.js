@@ -0,0 +1,11 @@
+// Test: transformArrowFunctions false leaves async arrows native
+class Service {
+    load() {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            const items = yield _this.fetch();
+            return items.map(async (item)=>await _this.expand(item));
+        })();
+    }
+}
+const run = async ()=>await main();
@@ -0,0 +1,12 @@
+// Test: each nested capturing boundary gets its own this/arguments capture
+const outer = async () => {
+    const view = {
+        async refresh() {
+            const reload = async () => {
+                await this.load(arguments);
+            };
+            await reload();
+        }
+    };
+    await this.render(view);
+};
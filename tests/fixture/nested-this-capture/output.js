@@ -0,0 +1,25 @@
+// Test: each nested capturing boundary gets its own this/arguments capture
+var _this = this;
+var _ref1 = _ngAsyncToGenerator(function*() {
+    const view = {
+        refresh () {
+            var _this = this;
+            var _arguments = arguments;
+            return _ngAsyncToGenerator(function*() {
+                var _this2 = _this;
+                var _arguments2 = _arguments;
+                var _ref = _ngAsyncToGenerator(function*() {
+                    yield _this2.load(_arguments2);
+                });
+                const reload = function reload() {
+                    return _ref.apply(_this2, arguments);
+                };
+                yield reload();
+            })();
+        }
+    };
+    yield _this.render(view);
+});
+const outer = function outer() {
+    return _ref1.apply(_this, arguments);
+};
@@ -0,0 +1,9 @@
+// Test: files without async code are left untouched
+function load(id) {
+    return fetch("/items/" + id).then((res) => res.json());
+}
+function* ids() {
+    yield 1;
+}
+const _ngAsyncToGenerator = (fn) => fn;
+export { load, ids };
@@ -0,0 +1,20 @@
+// Test: only classes with a selected decorator are transformed
+async function util() {
+    await tick();
+}
+@Component({ selector: "app-list" })
+class ListComponent {
+    async load() {
+        await this.api.list();
+    }
+}
+@Injectable
+class Api {
+    list = async () => await this.http.get("/list");
+}
+@Pipe({ name: "upper" })
+class UpperPipe {
+    async transform(value) {
+        return await upper(value);
+    }
+}
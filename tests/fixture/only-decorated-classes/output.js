@@ -0,0 +1,34 @@
+// Test: only classes with a selected decorator are transformed
+async function util() {
+    await tick();
+}
+@Component({
+    selector: "app-list"
+})
+class ListComponent {
+    load() {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            yield _this.api.list();
+        })();
+    }
+}
+@Injectable
+class Api {
+    list = function(_this) {
+        var _ref = _ngAsyncToGenerator(function*() {
+            return yield _this.http.get("/list");
+        });
+        return function() {
+            return _ref.apply(_this, arguments);
+        };
+    }(this);
+}
+@Pipe({
+    name: "upper"
+})
+class UpperPipe {
+    async transform(value) {
+        return await upper(value);
+    }
+}
@@ -0,0 +1,12 @@
+// Test: preserveWrapperParams keeps the names of plain params on wrappers
+async function load(id, options) {
+    return await fetch(id, options);
+}
+
+async function search(query, { page }, limit = 10, ...filters) {
+    return await find(query, page, limit, filters);
+}
+
+const remove = async (id) => {
+    await api.delete(id);
+};
@@ -0,0 +1,25 @@
+// Test: preserveWrapperParams keeps the names of plain params on wrappers
+function load(id, options) {
+    return _load.apply(this, arguments);
+}
+function search(query, _x2) {
+    return _search.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*(id, options) {
+        return yield fetch(id, options);
+    });
+    return _load.apply(this, arguments);
+}
+function _search() {
+    _search = _ngAsyncToGenerator(function*(query, { page }, limit = 10, ...filters) {
+        return yield find(query, page, limit, filters);
+    });
+    return _search.apply(this, arguments);
+}
+var _ref = _ngAsyncToGenerator(function*(id) {
+    yield api.delete(id);
+});
+const remove = function remove(id) {
+    return _ref.apply(this, arguments);
+};
@@ -0,0 +1,11 @@
+// Test: comments before async functions stay on the code replacing them
+const retries = 3;
+
+// Loads the current user
+const load = async (id) => {
+    return await fetch(id);
+};
+
+run(/* retried on failure */ async () => {
+    await fetch("/retry");
+});
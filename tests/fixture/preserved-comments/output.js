@@ -0,0 +1,12 @@
+// Test: comments before async functions stay on the code replacing them
+const retries = 3;
+var _ref = _ngAsyncToGenerator(function*(id) {
+    return yield fetch(id);
+});
+// Loads the current user
+const load = function load(_x) {
+    return _ref.apply(this, arguments);
+};
+run(/* retried on failure */ _ngAsyncToGenerator(function*() {
+    yield fetch("/retry");
+}));
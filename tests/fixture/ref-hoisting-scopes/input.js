@@ -0,0 +1,7 @@
+// Test: refs are hoisted into the closest statement list with the same bindings
+for (let i = 0; i < n; i++) {
+    tasks.push(async (attempt) => await step(i, attempt));
+}
+class Poller {
+    tick = async () => await this.poll();
+}
@@ -0,0 +1,21 @@
+// Test: refs are hoisted into the closest statement list with the same bindings
+for(let i = 0; i < n; i++){
+    tasks.push(function() {
+        var _ref = _ngAsyncToGenerator(function*(attempt) {
+            return yield step(i, attempt);
+        });
+        return function(_x) {
+            return _ref.apply(this, arguments);
+        };
+    }());
+}
+class Poller {
+    tick = function(_this) {
+        var _ref1 = _ngAsyncToGenerator(function*() {
+            return yield _this.poll();
+        });
+        return function() {
+            return _ref1.apply(_this, arguments);
+        };
+    }(this);
+}
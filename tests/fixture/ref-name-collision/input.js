@@ -0,0 +1,6 @@
+// Test: generated refs skip names already used in the file
+var _ref = 1, _ref1 = 2;
+
+const load = async () => {
+    return await fetch(_ref + _ref1);
+};
@@ -0,0 +1,8 @@
+// Test: generated refs skip names already used in the file
+var _ref = 1, _ref1 = 2;
+var _ref2 = _ngAsyncToGenerator(function*() {
+    return yield fetch(_ref + _ref1);
+});
+const load = function load() {
+    return _ref2.apply(this, arguments);
+};
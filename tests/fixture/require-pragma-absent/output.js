@@ -0,0 +1,4 @@
+// Test: files without the pragma are left alone
+async function load() {
+    return await fetch("/a");
+}
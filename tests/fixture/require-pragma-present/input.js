@@ -0,0 +1,5 @@
+// @ng-async
+// Test: files with the pragma are transformed
+async function load() {
+    return await fetch("/a");
+}
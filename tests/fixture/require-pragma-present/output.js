@@ -0,0 +1,11 @@
+// @ng-async
+// Test: files with the pragma are transformed
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch("/a");
+    });
+    return _load.apply(this, arguments);
+}
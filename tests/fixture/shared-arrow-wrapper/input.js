@@ -0,0 +1,10 @@
+// Test: sharedArrowWrapper makes the wrappers of async arrows with one helper per file
+const load = async (id) => await fetch(id);
+const save = async (item) => {
+    await store(item);
+};
+class View {
+    render() {
+        return async (event) => await this.handle(event);
+    }
+}
@@ -0,0 +1,24 @@
+function _ngWrap(ref, self) {
+    return function() {
+        return ref.apply(self, arguments);
+    };
+}
+// Test: sharedArrowWrapper makes the wrappers of async arrows with one helper per file
+var _ref, _ref1;
+_ref = _ngAsyncToGenerator(function*(id) {
+    return yield fetch(id);
+});
+const load = _ngWrap(_ref);
+_ref1 = _ngAsyncToGenerator(function*(item) {
+    yield store(item);
+});
+const save = _ngWrap(_ref1);
+class View {
+    render() {
+        var _this = this;
+        var _ref2 = _ngAsyncToGenerator(function*(event) {
+            return yield _this.handle(event);
+        });
+        return _ngWrap(_ref2, _this);
+    }
+}
@@ -0,0 +1,42 @@
+// Test: simplifyReturnAwait leaves returns in try, catch and finally alone
+async function plain(id) {
+    if (!id) {
+        return await fallback();
+    }
+    return await load(id);
+}
+
+async function inTry(id) {
+    try {
+        return await load(id);
+    } catch (error) {
+        report(error);
+    }
+}
+
+async function inCatch(id) {
+    try {
+        await connect();
+    } catch (error) {
+        return await retry(id);
+    }
+}
+
+async function inFinally(id) {
+    try {
+        await connect();
+    } finally {
+        return await disconnect(id);
+    }
+}
+
+async function nested(ids) {
+    try {
+        async function loadOne(id) {
+            return await load(id);
+        }
+        return await Promise.all(ids.map(loadOne));
+    } finally {
+        await close();
+    }
+}
@@ -0,0 +1,74 @@
+// Test: simplifyReturnAwait leaves returns in try, catch and finally alone
+function plain(_x) {
+    return _plain.apply(this, arguments);
+}
+function inTry(_x) {
+    return _inTry.apply(this, arguments);
+}
+function inCatch(_x) {
+    return _inCatch.apply(this, arguments);
+}
+function inFinally(_x) {
+    return _inFinally.apply(this, arguments);
+}
+function nested(_x) {
+    return _nested.apply(this, arguments);
+}
+function _plain() {
+    _plain = _ngAsyncToGenerator(function*(id) {
+        if (!id) {
+            return fallback();
+        }
+        return load(id);
+    });
+    return _plain.apply(this, arguments);
+}
+function _inTry() {
+    _inTry = _ngAsyncToGenerator(function*(id) {
+        try {
+            return yield load(id);
+        } catch (error) {
+            report(error);
+        }
+    });
+    return _inTry.apply(this, arguments);
+}
+function _inCatch() {
+    _inCatch = _ngAsyncToGenerator(function*(id) {
+        try {
+            yield connect();
+        } catch (error) {
+            return yield retry(id);
+        }
+    });
+    return _inCatch.apply(this, arguments);
+}
+function _inFinally() {
+    _inFinally = _ngAsyncToGenerator(function*(id) {
+        try {
+            yield connect();
+        } finally{
+            return yield disconnect(id);
+        }
+    });
+    return _inFinally.apply(this, arguments);
+}
+function _nested() {
+    _nested = _ngAsyncToGenerator(function*(ids) {
+        try {
+            function loadOne(_x) {
+                return _loadOne.apply(this, arguments);
+            }
+            function _loadOne() {
+                _loadOne = _ngAsyncToGenerator(function*(id) {
+                    return load(id);
+                });
+                return _loadOne.apply(this, arguments);
+            }
+            return yield Promise.all(ids.map(loadOne));
+        } finally{
+            yield close();
+        }
+    });
+    return _nested.apply(this, arguments);
+}
@@ -0,0 +1,12 @@
+// Test: strictGeneratedCode starts generated functions in sloppy scripts with "use strict"
+async function load(id) {
+    return await fetch(id);
+}
+
+const save = async ({ id }) => await put(id);
+
+class Store {
+    async refresh() {
+        await load(this.id);
+    }
+}
@@ -0,0 +1,26 @@
+// Test: strictGeneratedCode starts generated functions in sloppy scripts with "use strict"
+function load(_x) {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    "use strict";
+    _load = _ngAsyncToGenerator(function*(id) {
+        return yield fetch(id);
+    });
+    return _load.apply(this, arguments);
+}
+var _ref = _ngAsyncToGenerator(function*({ id }) {
+    return yield put(id);
+});
+const save = function save(_x) {
+    "use strict";
+    return _ref.apply(this, arguments);
+};
+class Store {
+    refresh() {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            yield load(_this.id);
+        })();
+    }
+}
@@ -0,0 +1,9 @@
+// Test: anonymous arrows without params, `this` or `arguments` become their wrapped generator
+setTimeout(async () => {
+    await flush();
+});
+class View {
+    render() {
+        button.on("click", async () => await this.refresh());
+    }
+}
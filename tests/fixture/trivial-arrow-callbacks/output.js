@@ -0,0 +1,15 @@
+// Test: anonymous arrows without params, `this` or `arguments` become their wrapped generator
+setTimeout(_ngAsyncToGenerator(function*() {
+    yield flush();
+}));
+class View {
+    render() {
+        var _this = this;
+        var _ref = _ngAsyncToGenerator(function*() {
+            return yield _this.refresh();
+        });
+        button.on("click", function() {
+            return _ref.apply(_this, arguments);
+        });
+    }
+}
@@ -0,0 +1,17 @@
+// Test: ambient declarations and overload signatures are left untouched
+function load(id: string): Promise<User>;
+function load(ids: string[]): Promise<User[]>;
+async function load(input: string | string[]) {
+    return await fetchUsers(input);
+}
+
+declare namespace Legacy {
+    function sync(): void;
+}
+
+class Repo {
+    find(id: string): Promise<Item>;
+    async find(id: string) {
+        return await this.db.get(id);
+    }
+}
@@ -0,0 +1,24 @@
+// Test: ambient declarations and overload signatures are left untouched
+function load(id: string): Promise<User>;
+function load(ids: string[]): Promise<User[]>;
+function load(_x) {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*(input: string | string[]) {
+        return yield fetchUsers(input);
+    });
+    return _load.apply(this, arguments);
+}
+declare namespace Legacy {
+    function sync(): void;
+}
+class Repo {
+    find(id: string): Promise<Item>;
+    find(id: string) {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            return yield _this.db.get(id);
+        })();
+    }
+}
@@ -0,0 +1,12 @@
+// Test: async functions inside namespaces keep their helpers inside the namespace
+namespace Api {
+    export async function load(id: string) {
+        return await fetch(id);
+    }
+
+    export namespace Admin {
+        export async function reset() {
+            await post('/reset');
+        }
+    }
+}
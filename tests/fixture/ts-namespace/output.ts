@@ -0,0 +1,23 @@
+// Test: async functions inside namespaces keep their helpers inside the namespace
+namespace Api {
+    export function load(_x) {
+        return _load.apply(this, arguments);
+    }
+    function _load() {
+        _load = _ngAsyncToGenerator(function*(id: string) {
+            return yield fetch(id);
+        });
+        return _load.apply(this, arguments);
+    }
+    export namespace Admin {
+        export function reset() {
+            return _reset.apply(this, arguments);
+        }
+        function _reset() {
+            _reset = _ngAsyncToGenerator(function*() {
+                yield post('/reset');
+            });
+            return _reset.apply(this, arguments);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// Test: TypeScript `this` params are kept on wrappers with `typescript`, but not counted
+async function load(this: Service, id: string): Promise<Item> {
+    return await this.fetch(id);
+}
+const save = async function (this: Service, item: Item) {
+    await this.store(item);
+};
@@ -0,0 +1,16 @@
+// Test: TypeScript `this` params are kept on wrappers with `typescript`, but not counted
+function load(this: Service, _x: string): Promise<Item> {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*(this: Service, id: string) {
+        return yield this.fetch(id);
+    });
+    return _load.apply(this, arguments);
+}
+var _ref = _ngAsyncToGenerator(function*(this: Service, item: Item) {
+    yield this.store(item);
+});
+const save = function save(this: Service, _x: Item) {
+    return _ref.apply(this, arguments);
+};
@@ -0,0 +1,7 @@
+// Test: TypeScript `this` params are not arguments
+async function load(this: Service, id: string): Promise<Item> {
+    return await this.fetch(id);
+}
+const save = async function (this: Service, item: Item) {
+    await this.store(item);
+};
@@ -0,0 +1,12 @@
+// Test: async functions in TSX components keep their type annotations
+function Search(props: SearchProps) {
+    return <input onChange={async (event: ChangeEvent) => {
+        await search(props.query, event.target.value);
+    }}/>;
+}
+
+class Loader extends Component<Props> {
+    async load(id: number): Promise<void> {
+        await this.props.fetch(id);
+    }
+}
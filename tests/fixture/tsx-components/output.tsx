@@ -0,0 +1,19 @@
+// Test: async functions in TSX components keep their type annotations
+function Search(props: SearchProps) {
+    return <input onChange={function() {
+        var _ref = _ngAsyncToGenerator(function*(event: ChangeEvent) {
+            yield search(props.query, event.target.value);
+        });
+        return function(_x) {
+            return _ref.apply(this, arguments);
+        };
+    }()}/>;
+}
+class Loader extends Component<Props> {
+    load(id: number): Promise<void> {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            yield _this.props.fetch(id);
+        })();
+    }
+}
@@ -0,0 +1,13 @@
+// Test: async generators and async functions using super stay native
+async function* stream() {
+    yield await fetch("/a");
+}
+class Child extends Base {
+    async load() {
+        return await super.load();
+    }
+    async save() {
+        const run = async () => await super.save();
+        return run();
+    }
+}
@@ -0,0 +1,5 @@
+// Test: assertHelper checks the using helper too
+async function read(path) {
+    using file = openSync(path);
+    return await file.read();
+}
@@ -0,0 +1,24 @@
+if (typeof _ngAsyncToGenerator !== "function") {
+    throw new Error("`_ngAsyncToGenerator` is not defined: the async functions of this file were compiled by swc-plugin-transform-async-to-ng-generator and need its runtime helper. Load `ngAsyncToGenerator.js` before this file, or set the `importHelper` or `inlineHelper` option of the plugin.");
+}
+if (typeof _ngUsingCtx !== "function") {
+    throw new Error("`_ngUsingCtx` is not defined: the async functions of this file were compiled by swc-plugin-transform-async-to-ng-generator and need its runtime helper. Load `ngAsyncToGenerator.js` before this file, or set the `importHelper` or `inlineHelper` option of the plugin.");
+}
+// Test: assertHelper checks the using helper too
+function read(_x) {
+    return _read.apply(this, arguments);
+}
+function _read() {
+    _read = _ngAsyncToGenerator(function*(path) {
+        const _usingCtx = _ngUsingCtx();
+        try {
+            const file = _usingCtx.u(openSync(path));
+            return yield file.read();
+        } catch (_) {
+            _usingCtx.e = _;
+        } finally {
+            _usingCtx.d();
+        }
+    });
+    return _read.apply(this, arguments);
+}
@@ -0,0 +1,10 @@
+// Test: using and await using declarations inside async bodies
+async function run() {
+    await using conn = await open();
+    return query(conn);
+}
+
+async function read(path) {
+    using file = openSync(path);
+    return await file.read();
+}
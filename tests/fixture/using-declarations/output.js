@@ -0,0 +1,35 @@
+// Test: using and await using declarations inside async bodies
+function run() {
+    return _run.apply(this, arguments);
+}
+function read(_x) {
+    return _read.apply(this, arguments);
+}
+function _run() {
+    _run = _ngAsyncToGenerator(function*() {
+        const _usingCtx = _ngUsingCtx();
+        try {
+            const conn = _usingCtx.a(yield open());
+            return query(conn);
+        } catch (_) {
+            _usingCtx.e = _;
+        } finally {
+            yield _usingCtx.d();
+        }
+    });
+    return _run.apply(this, arguments);
+}
+function _read() {
+    _read = _ngAsyncToGenerator(function*(path) {
+        const _usingCtx = _ngUsingCtx();
+        try {
+            const file = _usingCtx.u(openSync(path));
+            return yield file.read();
+        } catch (_) {
+            _usingCtx.e = _;
+        } finally {
+            _usingCtx.d();
+        }
+    });
+    return _read.apply(this, arguments);
+}
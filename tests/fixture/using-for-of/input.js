@@ -0,0 +1,10 @@
+// Test: using and await using declarations in for-of heads
+async function closeAll(connections) {
+    for (await using conn of connections) {
+        await conn.flush();
+    }
+}
+
+async function readAll(paths) {
+    for (using file of paths.map(openSync)) log(await file.read());
+}
@@ -0,0 +1,41 @@
+// Test: using and await using declarations in for-of heads
+function closeAll(_x) {
+    return _closeAll.apply(this, arguments);
+}
+function readAll(_x) {
+    return _readAll.apply(this, arguments);
+}
+function _closeAll() {
+    _closeAll = _ngAsyncToGenerator(function*(connections) {
+        for (const _value of connections) {
+            const _usingCtx = _ngUsingCtx();
+            try {
+                const conn = _usingCtx.a(_value);
+                {
+                    yield conn.flush();
+                }
+            } catch (_) {
+                _usingCtx.e = _;
+            } finally {
+                yield _usingCtx.d();
+            }
+        }
+    });
+    return _closeAll.apply(this, arguments);
+}
+function _readAll() {
+    _readAll = _ngAsyncToGenerator(function*(paths) {
+        for (const _value of paths.map(openSync)) {
+            const _usingCtx = _ngUsingCtx();
+            try {
+                const file = _usingCtx.u(_value);
+                log(yield file.read());
+            } catch (_) {
+                _usingCtx.e = _;
+            } finally {
+                _usingCtx.d();
+            }
+        }
+    });
+    return _readAll.apply(this, arguments);
+}
@@ -0,0 +1,5 @@
+// Test: the using helper is imported with the wrapper
+export async function run() {
+    await using conn = await open();
+    return query(conn);
+}
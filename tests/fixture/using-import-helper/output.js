@@ -0,0 +1,19 @@
+import { _ngAsyncToGenerator, _ngUsingCtx } from "@app/ng-async";
+// Test: the using helper is imported with the wrapper
+export function run() {
+    return _run.apply(this, arguments);
+}
+function _run() {
+    _run = _ngAsyncToGenerator(function*() {
+        const _usingCtx = _ngUsingCtx();
+        try {
+            const conn = _usingCtx.a(yield open());
+            return query(conn);
+        } catch (_) {
+            _usingCtx.e = _;
+        } finally {
+            yield _usingCtx.d();
+        }
+    });
+    return _run.apply(this, arguments);
+}
@@ -0,0 +1,7 @@
+// Test: inlineHelper adds the using helper next to an imported wrapper
+import { _ngAsyncToGenerator } from "@app/ng-async";
+
+export async function read(path) {
+    using file = openSync(path);
+    return await file.read();
+}
@@ -0,0 +1,97 @@
+function _ngUsingCtx() {
+    var empty = {};
+    var stack = [];
+    var hasAsync = false;
+    function register(value, isAwait) {
+        if (value == null) {
+            if (isAwait) {
+                stack.push({ a: true });
+                hasAsync = true;
+            }
+            return value;
+        }
+        if (Object(value) !== value) {
+            throw new TypeError("using declarations can only be used with objects, functions, null, or undefined.");
+        }
+        var dispose;
+        if (isAwait) {
+            dispose = value[Symbol.asyncDispose || Symbol.for("Symbol.asyncDispose")];
+        }
+        if (dispose == null) {
+            dispose = value[Symbol.dispose || Symbol.for("Symbol.dispose")];
+        }
+        if (typeof dispose !== "function") {
+            throw new TypeError("Object is not disposable.");
+        }
+        stack.push({ v: value, d: dispose, a: isAwait });
+        hasAsync = hasAsync || isAwait;
+        return value;
+    }
+    var ctx = {
+        e: empty,
+        u: function (value) {
+            return register(value, false);
+        },
+        a: function (value) {
+            return register(value, true);
+        },
+        d: function () {
+            var error = ctx.e;
+            function fail(err) {
+                if (error === empty) {
+                    error = err;
+                } else if (typeof SuppressedError === "function") {
+                    error = new SuppressedError(err, error, "An error was suppressed during disposal.");
+                } else {
+                    var suppressed = new Error("An error was suppressed during disposal.");
+                    suppressed.name = "SuppressedError";
+                    suppressed.error = err;
+                    suppressed.suppressed = error;
+                    error = suppressed;
+                }
+            }
+            function next() {
+                while (stack.length) {
+                    var resource = stack.pop();
+                    try {
+                        var result = resource.d && resource.d.call(resource.v);
+                        if (resource.a) {
+                            return Promise.resolve(result).then(next, function (err) {
+                                fail(err);
+                                return next();
+                            });
+                        }
+                    } catch (err) {
+                        fail(err);
+                    }
+                }
+                if (error !== empty) {
+                    throw error;
+                }
+            }
+            return hasAsync ? new Promise(function (resolve) {
+                resolve(next());
+            }) : next();
+        }
+    };
+    return ctx;
+}
+// Test: inlineHelper adds the using helper next to an imported wrapper
+import { _ngAsyncToGenerator } from "@app/ng-async";
+export function read(_x) {
+    return _read.apply(this, arguments);
+}
+function _read() {
+    _read = _ngAsyncToGenerator(function*(path) {
+        const _usingCtx = _ngUsingCtx();
+        try {
+            const file = _usingCtx.u(openSync(path));
+            return yield file.read();
+        } catch (_) {
+            _usingCtx.e = _;
+        } finally {
+            _usingCtx.d();
+        }
+    });
+    return _read.apply(this, arguments);
+}
@@ -0,0 +1,9 @@
+// Test: zone passes a zone getter to the runtime helper
+async function load() {
+    return await fetch("/a");
+}
+class Service {
+    async refresh() {
+        this.items = await load();
+    }
+}
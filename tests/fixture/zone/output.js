@@ -0,0 +1,22 @@
+// Test: zone passes a zone getter to the runtime helper
+function load() {
+    return _load.apply(this, arguments);
+}
+function _load() {
+    _load = _ngAsyncToGenerator(function*() {
+        return yield fetch("/a");
+    }, function() {
+        return Zone.current;
+    });
+    return _load.apply(this, arguments);
+}
+class Service {
+    refresh() {
+        var _this = this;
+        return _ngAsyncToGenerator(function*() {
+            _this.items = yield load();
+        }, function() {
+            return Zone.current;
+        })();
+    }
+}
@@ -0,0 +1,85 @@
+//! Golden outputs: the code downstream builds actually emit, pinned to the
+//! `swc_core` version they were generated with.
+//!
+//! The snapshot fixtures compare the plugin's output alone; every
+//! `tests/golden/<name>/input.js` goes through the whole pipeline of a build
+//! instead (`resolver`, the plugin, `hygiene`, `fixer` and the code
+//! generator, comments included) and must print exactly its `output.js`,
+//! with the options of an optional `config.json`. A `swc_core` upgrade that
+//! changes any of these passes shows up here rather than in the diffs of
+//! downstream teams.
+//!
+//! `tests/golden/swc_core.version` records the `swc_core` the outputs were
+//! generated with, and must match the one in `Cargo.lock`, so an upgrade
+//! cannot pass without the goldens being looked at again. When a change of
+//! the output is intended, or after an upgrade, regenerate them with
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test --test golden
+//! ```
+//!
+//! and review the diff. `UPDATE=1` does not touch them.
+
+use std::{env, fs, path::PathBuf};
+
+mod common;
+
+/// File recording the `swc_core` version of the goldens.
+const VERSION_FILE: &str = "tests/golden/swc_core.version";
+
+#[testing::fixture("tests/golden/**/input.js")]
+fn golden(input: PathBuf) {
+    let source = fs::read_to_string(&input).unwrap();
+    let actual = common::build(&source, common::input_config(&input));
+    let expected_path = input.with_file_name("output.js");
+    if update_golden() {
+        fs::write(&expected_path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+    assert!(
+        actual.trim_end() == expected.trim_end(),
+        "the output of {} changed; if that is intended, regenerate the goldens with \
+         `UPDATE_GOLDEN=1 cargo test --test golden` and review the diff\n\
+         expected:\n{}\nactual:\n{}",
+        input.display(),
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn goldens_match_the_locked_swc_core() {
+    // `Cargo.lock` is not committed; without one there is nothing to compare.
+    let Some(locked) = locked_swc_core_version() else {
+        return;
+    };
+    if update_golden() {
+        fs::write(VERSION_FILE, format!("{}\n", locked)).unwrap();
+        return;
+    }
+    let recorded = fs::read_to_string(VERSION_FILE).unwrap();
+    assert_eq!(
+        recorded.trim(),
+        locked,
+        "swc_core changed since the goldens were generated; regenerate them with \
+         `UPDATE_GOLDEN=1 cargo test --test golden` and review the diff"
+    );
+}
+
+/// Whether the goldens are regenerated rather than checked.
+fn update_golden() -> bool {
+    env::var_os("UPDATE_GOLDEN").is_some_and(|value| value != "0")
+}
+
+/// The version of `swc_core` in `Cargo.lock`.
+fn locked_swc_core_version() -> Option<String> {
+    let lock = fs::read_to_string("Cargo.lock").ok()?;
+    let mut lines = lock.lines();
+    lines.find(|line| *line == r#"name = "swc_core""#)?;
+    let version = lines
+        .next()?
+        .strip_prefix("version = \"")?
+        .strip_suffix('"')?;
+    Some(version.to_string())
+}
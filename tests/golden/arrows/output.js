@@ -0,0 +1,16 @@
+// Golden: async arrow functions
+var _ref, _ref1;
+_ref = _ngAsyncToGenerator(function*() {
+    const result = yield fetch('/api');
+    return result;
+});
+const fetchData = function fetchData() {
+    return _ref.apply(this, arguments);
+};
+_ref1 = _ngAsyncToGenerator(function*(url, options) {
+    const result = yield fetch(url, options);
+    return result;
+});
+const fetchWithParams = function fetchWithParams(_x, _x2) {
+    return _ref1.apply(this, arguments);
+};
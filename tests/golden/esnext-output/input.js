@@ -0,0 +1,13 @@
+// Golden: esnextOutput
+const load = async (id, options) => await fetch(id, options);
+class View {
+    async render() {
+        return await this.draw(arguments);
+    }
+    handler() {
+        return async () => await this.refresh();
+    }
+}
+const save = async function (item) {
+    await store(item);
+};
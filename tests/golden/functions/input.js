@@ -0,0 +1,5 @@
+// Golden: async function declarations
+async function fetchData() {
+    const result = await fetch('/api');
+    return result;
+}
@@ -0,0 +1,18 @@
+// Golden: loose mode
+async function refresh() {
+    await reload();
+}
+async function log() {
+    await write(arguments);
+}
+async function save(item) {
+    await store(item);
+}
+class View {
+    render() {
+        return async () => await this.refresh();
+    }
+}
+const ping = async function () {
+    await send();
+};
@@ -0,0 +1,45 @@
+// Golden: loose mode
+function refresh() {
+    return _refresh.call(this);
+}
+function _refresh() {
+    _refresh = _ngAsyncToGenerator(function*() {
+        yield reload();
+    });
+    return _refresh.call(this);
+}
+function log() {
+    return _log.apply(this, arguments);
+}
+function _log() {
+    _log = _ngAsyncToGenerator(function*() {
+        yield write(arguments);
+    });
+    return _log.apply(this, arguments);
+}
+function save(_x) {
+    return _save.apply(this, arguments);
+}
+function _save() {
+    _save = _ngAsyncToGenerator(function*(item) {
+        yield store(item);
+    });
+    return _save.apply(this, arguments);
+}
+class View {
+    render() {
+        var _this = this;
+        var _ref = _ngAsyncToGenerator(function*() {
+            return yield _this.refresh();
+        });
+        return function() {
+            return _ref.call(_this);
+        };
+    }
+}
+var _ref1 = _ngAsyncToGenerator(function*() {
+    yield send();
+});
+const ping = function ping() {
+    return _ref1.call(this);
+};
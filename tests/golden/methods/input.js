@@ -0,0 +1,13 @@
+// Golden: async methods capturing this and arguments
+class Logger {
+    async log() {
+        await this.ready;
+        return this.write(arguments);
+    }
+}
+
+function retryAll() {
+    return tasks.map(async (task) => {
+        await task.run(...arguments);
+    });
+}
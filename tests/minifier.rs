@@ -0,0 +1,130 @@
+//! End-to-end runs of `resolver`, the plugin, `hygiene` and the minifier.
+//!
+//! The mangler tells bindings apart by syntax context, so a generated
+//! identifier with the wrong context gets merged with, or renamed apart
+//! from, the binding it should refer to. Minifying the transformed AST must
+//! therefore give the same code as minifying its printed (and re-parsed)
+//! output, where only the names matter.
+
+use swc_core::{
+    common::{sync::Lrc, FileName, Globals, Mark, SourceMap, GLOBALS},
+    ecma::{
+        ast::{EsVersion, Program},
+        codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter},
+        minifier::{
+            optimize,
+            option::{ExtraOptions, MinifyOptions},
+        },
+        parser::{parse_file_as_program, EsSyntax, Syntax},
+        transforms::base::{fixer::fixer, hygiene::hygiene, resolver},
+        visit::visit_mut_pass,
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::{AsyncToNgGeneratorVisitor, Config};
+
+/// Parse `source` and run `resolver` on it.
+fn parse(cm: &Lrc<SourceMap>, source: &str, marks: (Mark, Mark)) -> Program {
+    let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+    let program = parse_file_as_program(
+        &fm,
+        Syntax::Es(EsSyntax::default()),
+        EsVersion::latest(),
+        None,
+        &mut vec![],
+    )
+    .expect("fixture source is valid JavaScript");
+    program.apply(resolver(marks.0, marks.1, false))
+}
+
+/// Print `program` without whitespace.
+fn print(cm: &Lrc<SourceMap>, program: &Program) -> String {
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: CodegenConfig::default().with_minify(true),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
+        emitter.emit_program(program).unwrap();
+    }
+    String::from_utf8(buf).unwrap()
+}
+
+/// Mangle `program` and print it.
+fn minify(cm: &Lrc<SourceMap>, program: Program, marks: (Mark, Mark)) -> String {
+    let options = MinifyOptions {
+        mangle: Some(Default::default()),
+        ..Default::default()
+    };
+    let extra = ExtraOptions {
+        unresolved_mark: marks.0,
+        top_level_mark: marks.1,
+        mangle_name_cache: None,
+    };
+    let program = optimize(program, cm.clone(), None, None, &options, &extra);
+    print(cm, &program.apply(fixer(None)))
+}
+
+/// Minify `source` after the plugin, both straight from the transformed AST
+/// and from its printed output.
+fn minify_both_ways(source: &str, config: Config) -> (String, String) {
+    GLOBALS.set(&Globals::new(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let marks = (Mark::new(), Mark::new());
+
+        let transformed = parse(&cm, source, marks)
+            .apply(visit_mut_pass(AsyncToNgGeneratorVisitor::with_marks(
+                config, marks.0, marks.1,
+            )))
+            .apply(hygiene());
+        let printed = print(&cm, &transformed);
+
+        let direct = minify(&cm, transformed, marks);
+        let reparse_marks = (Mark::new(), Mark::new());
+        let reparsed = minify(&cm, parse(&cm, &printed, reparse_marks), reparse_marks);
+        (direct, reparsed)
+    })
+}
+
+const SOURCE: &str = r#"
+export function make(_this, _ref) {
+    async function load(id) {
+        return await _ref.fetch(id, _this);
+    }
+    const refresh = async () => {
+        const items = await load(this.id);
+        return items.map(async (item) => await _ref.save(item, arguments));
+    };
+    const handlers = {
+        reload: async function () {
+            return await refresh();
+        },
+    };
+    return { load, refresh, handlers };
+}
+
+export class Service {
+    async load(_arguments) {
+        const run = async () => await this.fetch(_arguments, arguments);
+        return await run();
+    }
+}
+"#;
+
+#[test]
+fn generated_bindings_survive_mangling() {
+    let (direct, reparsed) = minify_both_ways(SOURCE, Config::default());
+    assert_eq!(direct, reparsed);
+}
+
+#[test]
+fn generated_bindings_survive_mangling_with_esnext_output() {
+    let config = Config::builder()
+        .esnext_output(true)
+        .lazy_refs(true)
+        .build()
+        .unwrap();
+    let (direct, reparsed) = minify_both_ways(SOURCE, config);
+    assert_eq!(direct, reparsed);
+}
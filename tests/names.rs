@@ -0,0 +1,307 @@
+//! Property tests of the names the transform generates.
+//!
+//! Programs are generated with user bindings named like the generated ones
+//! (`_this`, `_ref`, `_foo1`, `_ngAsyncToGenerator`, ...) in random scopes,
+//! around and inside async functions that read them. Every read is a
+//! `use(id, name)` call and every binding a `let name = id;`, so after
+//! `resolver` each read can be traced to the binding it refers to. The
+//! transformed and printed output must resolve every read to the same
+//! binding as the input, and must not read globals the input did not,
+//! besides the runtime helper and `arguments`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Write,
+};
+
+use proptest::prelude::*;
+use swc_core::{
+    common::{sync::Lrc, FileName, Globals, Mark, SourceMap, SyntaxContext, GLOBALS},
+    ecma::{
+        ast::*,
+        atoms::Atom,
+        codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter},
+        parser::{parse_file_as_program, EsSyntax, Syntax},
+        transforms::base::{fixer::fixer, hygiene::hygiene, resolver},
+        visit::{visit_mut_pass, Visit, VisitWith},
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::{AsyncToNgGeneratorVisitor, Config};
+
+/// Names of the user bindings, colliding with what the transform generates.
+const NAMES: &[&str] = &[
+    "_this",
+    "_arguments",
+    "_ref",
+    "_ref1",
+    "_foo1",
+    "_foo2",
+    "_x",
+    "args",
+    "_ngAsyncToGenerator",
+];
+
+/// Globals the output may read although the input does not.
+const GENERATED_GLOBALS: &[&str] = &["_ngAsyncToGenerator", "arguments"];
+
+/// A statement of a generated program.
+#[derive(Debug, Clone)]
+enum Node {
+    /// `let name = id;`
+    Decl(usize),
+    /// `use(id, name);`
+    Use(usize),
+    /// `use(id, this, arguments);`
+    UseThis,
+    /// `{ ... }`
+    Block(Vec<Node>),
+    /// `async function fooN() { await 0; ... }`
+    AsyncFn(Vec<Node>),
+    /// `let aN = async () => { await 0; ... };`
+    AsyncArrow(Vec<Node>),
+    /// `let oN = { async m() { await 0; ... } };`
+    AsyncMethod(Vec<Node>),
+    /// `function gN() { ... }`
+    Function(Vec<Node>),
+}
+
+fn node() -> impl Strategy<Value = Node> {
+    let leaf = prop_oneof![
+        (0..NAMES.len()).prop_map(Node::Decl),
+        (0..NAMES.len()).prop_map(Node::Use),
+        Just(Node::UseThis),
+    ];
+    leaf.prop_recursive(4, 48, 6, |inner| {
+        let body = prop::collection::vec(inner, 0..6);
+        prop_oneof![
+            body.clone().prop_map(Node::Block),
+            body.clone().prop_map(Node::AsyncFn),
+            body.clone().prop_map(Node::AsyncArrow),
+            body.clone().prop_map(Node::AsyncMethod),
+            body.prop_map(Node::Function),
+        ]
+    })
+}
+
+/// Prints generated programs, numbering bindings, reads and functions.
+#[derive(Default)]
+struct Renderer {
+    source: String,
+    ids: usize,
+}
+
+impl Renderer {
+    fn render(nodes: &[Node]) -> String {
+        let mut renderer = Renderer::default();
+        renderer.body(nodes);
+        renderer.source
+    }
+
+    fn next_id(&mut self) -> usize {
+        self.ids += 1;
+        self.ids
+    }
+
+    /// Print a statement list, declaring each name once.
+    fn body(&mut self, nodes: &[Node]) {
+        let mut declared = HashSet::new();
+        for node in nodes {
+            self.node(node, &mut declared);
+        }
+    }
+
+    fn node(&mut self, node: &Node, declared: &mut HashSet<usize>) {
+        let id = self.next_id();
+        match node {
+            Node::Decl(name) => {
+                if declared.insert(*name) {
+                    writeln!(self.source, "let {} = {};", NAMES[*name], id).unwrap();
+                }
+            }
+            Node::Use(name) => writeln!(self.source, "use({}, {});", id, NAMES[*name]).unwrap(),
+            Node::UseThis => writeln!(self.source, "use({}, this, arguments);", id).unwrap(),
+            Node::Block(body) => self.wrapped("{", body, "}"),
+            Node::AsyncFn(body) => self.wrapped(
+                &format!("async function foo{}() {{ await 0;", id),
+                body,
+                "}",
+            ),
+            Node::AsyncArrow(body) => self.wrapped(
+                &format!("let a{} = async () => {{ await 0;", id),
+                body,
+                "};",
+            ),
+            Node::AsyncMethod(body) => self.wrapped(
+                &format!("let o{} = {{ async m() {{ await 0;", id),
+                body,
+                "} };",
+            ),
+            Node::Function(body) => self.wrapped(&format!("function g{}() {{", id), body, "}"),
+        }
+    }
+
+    fn wrapped(&mut self, open: &str, body: &[Node], close: &str) {
+        writeln!(self.source, "{}", open).unwrap();
+        self.body(body);
+        writeln!(self.source, "{}", close).unwrap();
+    }
+}
+
+/// What a `use(id, name)` read refers to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Target {
+    /// The user binding `let name = id;`
+    Binding(u32),
+    /// A global
+    Global,
+    /// A binding generated by the transform
+    Generated,
+}
+
+/// Bindings, reads and globals of a program that went through `resolver`.
+struct Scan {
+    unresolved: SyntaxContext,
+    bindings: HashMap<Id, u32>,
+    reads: Vec<(u32, Id)>,
+    globals: BTreeSet<Atom>,
+}
+
+impl Scan {
+    fn new(program: &Program, unresolved_mark: Mark) -> Self {
+        let mut scan = Scan {
+            unresolved: SyntaxContext::empty().apply_mark(unresolved_mark),
+            bindings: HashMap::new(),
+            reads: Vec::new(),
+            globals: BTreeSet::new(),
+        };
+        program.visit_with(&mut scan);
+        scan
+    }
+
+    /// The target of every read, by read id.
+    fn targets(&self) -> BTreeMap<u32, Target> {
+        self.reads
+            .iter()
+            .map(|(id, name)| {
+                let target = match self.bindings.get(name) {
+                    Some(binding) => Target::Binding(*binding),
+                    None if name.1 == self.unresolved => Target::Global,
+                    None => Target::Generated,
+                };
+                (*id, target)
+            })
+            .collect()
+    }
+}
+
+impl Visit for Scan {
+    fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+        if let (Pat::Ident(binding), Some(init)) = (&declarator.name, &declarator.init) {
+            if let Expr::Lit(Lit::Num(id)) = &**init {
+                self.bindings.insert(binding.id.to_id(), id.value as u32);
+            }
+        }
+        declarator.visit_children_with(self);
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let (Callee::Expr(callee), [id, arg, ..]) = (&call.callee, &call.args[..]) {
+            let is_use = matches!(&**callee, Expr::Ident(callee) if &*callee.sym == "use");
+            if let (true, Expr::Lit(Lit::Num(id)), Expr::Ident(name)) =
+                (is_use, &*id.expr, &*arg.expr)
+            {
+                self.reads.push((id.value as u32, name.to_id()));
+            }
+        }
+        call.visit_children_with(self);
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if ident.ctxt == self.unresolved {
+            self.globals.insert(ident.sym.clone());
+        }
+    }
+}
+
+/// Parse `source` and run `resolver` on it.
+fn parse(cm: &Lrc<SourceMap>, source: &str, marks: (Mark, Mark)) -> Program {
+    let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+    let program = parse_file_as_program(
+        &fm,
+        Syntax::Es(EsSyntax::default()),
+        EsVersion::latest(),
+        None,
+        &mut vec![],
+    )
+    .unwrap_or_else(|error| panic!("{:?} in:\n{}", error, source));
+    program.apply(resolver(marks.0, marks.1, false))
+}
+
+fn print(cm: &Lrc<SourceMap>, program: &Program) -> String {
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: CodegenConfig::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
+        emitter.emit_program(program).unwrap();
+    }
+    String::from_utf8(buf).unwrap()
+}
+
+/// Scan `source` before and after the transform.
+fn scan_transformed(source: &str, config: Config) -> (Scan, Scan, String) {
+    GLOBALS.set(&Globals::new(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let marks = (Mark::new(), Mark::new());
+        let program = parse(&cm, source, marks);
+        let before = Scan::new(&program, marks.0);
+
+        let visitor = AsyncToNgGeneratorVisitor::with_marks(config, marks.0, marks.1);
+        let program = program.apply((visit_mut_pass(visitor), hygiene(), fixer(None)));
+        let output = print(&cm, &program);
+
+        let marks = (Mark::new(), Mark::new());
+        let after = Scan::new(&parse(&cm, &output, marks), marks.0);
+        (before, after, output)
+    })
+}
+
+fn check(nodes: &[Node], config: Config) -> Result<(), TestCaseError> {
+    let source = Renderer::render(nodes);
+    let (before, after, output) = scan_transformed(&source, config);
+    prop_assert_eq!(
+        before.targets(),
+        after.targets(),
+        "reads resolve differently after the transform:\n{}\n=>\n{}",
+        source,
+        output
+    );
+    let new_globals: Vec<_> = after
+        .globals
+        .difference(&before.globals)
+        .filter(|name| !GENERATED_GLOBALS.contains(&&***name))
+        .collect();
+    prop_assert!(
+        new_globals.is_empty(),
+        "unbound {:?}:\n{}\n=>\n{}",
+        new_globals,
+        source,
+        output
+    );
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn reads_keep_their_bindings(nodes in prop::collection::vec(node(), 1..8)) {
+        check(&nodes, Config::default())?;
+    }
+
+    #[test]
+    fn reads_keep_their_bindings_in_esnext_output(nodes in prop::collection::vec(node(), 1..8)) {
+        check(&nodes, Config::builder().esnext_output(true).build().unwrap())?;
+    }
+}
@@ -0,0 +1,152 @@
+//! Statistics reported by the visitor.
+
+use swc_core::{
+    common::{comments::SingleThreadedComments, sync::Lrc, FileName, Globals, SourceMap, GLOBALS},
+    ecma::{
+        ast::{EsVersion, Expr},
+        parser::{parse_file_as_program, EsSyntax, Syntax},
+        visit::VisitMutWith,
+    },
+};
+use swc_plugin_transform_async_to_ng_generator::{
+    AsyncToNgGeneratorVisitor, Config, FileSkipReason, FunctionKind, SkipReason, TransformReport,
+    WrapperContext, WrapperEmitter,
+};
+
+/// Transform `source` and return the visitor's report.
+fn report(source: &str, config: Config) -> TransformReport {
+    GLOBALS.set(&Globals::new(), || {
+        let cm: Lrc<SourceMap> = Default::default();
+        let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+        let comments = SingleThreadedComments::default();
+        let mut program = parse_file_as_program(
+            &fm,
+            Syntax::Es(EsSyntax {
+                decorators: true,
+                ..Default::default()
+            }),
+            EsVersion::latest(),
+            Some(&comments),
+            &mut vec![],
+        )
+        .unwrap();
+        let mut visitor = AsyncToNgGeneratorVisitor::with_config(config).with_comments(comments);
+        program.visit_mut_with(&mut visitor);
+        visitor.report().clone()
+    })
+}
+
+#[test]
+fn counts_transformed_functions_by_kind() {
+    let source = "
+        async function load() { await get(); }
+        const refresh = async () => await get();
+        const save = async function () { await put(); };
+        class Service { async run() { await get(); } async #stop() { await put(); } }
+        const store = { async get() { await get(); } };
+    ";
+    let report = report(source, Config::default());
+    assert_eq!(report.declarations, 1);
+    assert_eq!(report.arrows, 1);
+    assert_eq!(report.expressions, 1);
+    assert_eq!(report.class_methods, 2);
+    assert_eq!(report.object_methods, 1);
+    assert_eq!(report.transformed(), 6);
+    assert!(report.skipped.is_empty(), "{:?}", report.skipped);
+    assert_eq!(report.skipped_file, None);
+}
+
+#[test]
+fn lists_skipped_functions_with_their_reason() {
+    let source = "
+        /* @ng-async-ignore */
+        async function legacy() { await get(); }
+        const refresh = async () => await get();
+        class Stream { async *items() { yield await get(); } }
+    ";
+    let config = Config::builder()
+        .transform_arrow_functions(false)
+        .build()
+        .unwrap();
+    let report = report(source, config);
+    let skipped: Vec<_> = report
+        .skipped
+        .iter()
+        .map(|skip| (skip.kind, skip.reason))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            (FunctionKind::Declaration, SkipReason::Ignored),
+            (FunctionKind::Arrow, SkipReason::ArrowsDisabled),
+            (FunctionKind::ClassMethod, SkipReason::Unsupported),
+        ]
+    );
+    assert_eq!(report.transformed(), 0);
+}
+
+#[test]
+fn tells_why_a_file_was_skipped() {
+    let source = "async function load() { await get(); }";
+    let config = Config::builder().require_pragma(true).build().unwrap();
+    assert_eq!(
+        report(source, config).skipped_file,
+        Some(FileSkipReason::MissingPragma)
+    );
+}
+
+#[test]
+fn leaves_functions_rejected_by_the_filter_native() {
+    let source = "
+        async function loadNative() { await get(); }
+        const refresh = async () => await get();
+        @Injectable() class Api { async fetch() { await get(); } }
+        class Cache { async fetch() { await get(); } }
+    ";
+    let config = Config::builder()
+        .function_filter(|info| {
+            let native_name = info.name.is_some_and(|name| name.ends_with("Native"));
+            let undecorated = info.class.is_some_and(|class| class.decorators.is_empty());
+            !native_name && !undecorated
+        })
+        .build()
+        .unwrap();
+    let report = report(source, config);
+    let skipped: Vec<_> = report
+        .skipped
+        .iter()
+        .map(|skip| (skip.kind, skip.reason))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            (FunctionKind::Declaration, SkipReason::Filtered),
+            (FunctionKind::ClassMethod, SkipReason::Filtered),
+        ]
+    );
+    assert_eq!(report.arrows, 1);
+    assert_eq!(report.class_methods, 1);
+}
+
+/// Emitter that panics, standing in for an internal error.
+#[derive(Debug)]
+struct Panicking;
+
+impl WrapperEmitter for Panicking {
+    fn wrap_generator(&self, _: Expr, _: &WrapperContext<'_>) -> Expr {
+        panic!("emitter failed");
+    }
+}
+
+#[test]
+fn internal_errors_reset_the_report() {
+    let source = "const load = async () => await get();\nasync function save() { await put(); }";
+    let config = Config::builder()
+        .wrapper_emitter(Panicking)
+        .catch_panics(true)
+        .build()
+        .unwrap();
+    let report = report(source, config);
+    assert_eq!(report.skipped_file, Some(FileSkipReason::InternalError));
+    assert_eq!(report.transformed(), 0);
+}
@@ -0,0 +1,92 @@
+//! The source-to-source API of the `source` feature.
+#![cfg(feature = "source")]
+
+use swc_plugin_transform_async_to_ng_generator::{
+    runtime_source, transform_source, transform_source_with_map, Config, ImportHelper, SourceError,
+};
+
+#[test]
+fn transforms_source_text() {
+    let output = transform_source(
+        "// Load the data\nconst load = async () => await fetch('/a');\n",
+        Config::default(),
+    )
+    .unwrap();
+    assert!(output.starts_with("// Load the data\n"), "{}", output);
+    assert!(
+        output.contains("_ngAsyncToGenerator(function*"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn builds_a_source_map_of_the_output() {
+    let source = "const load = async () => {\n    return await fetch('/a');\n};\n";
+    let output = transform_source_with_map(source, "src/load.js", Config::default()).unwrap();
+    assert!(
+        output.code.contains("_ngAsyncToGenerator(function*"),
+        "{}",
+        output.code
+    );
+
+    let map: serde_json::Value = serde_json::from_str(&output.map).unwrap();
+    assert_eq!(map["version"], 3);
+    assert_eq!(map["sources"], serde_json::json!(["src/load.js"]));
+    assert!(
+        !map["mappings"].as_str().unwrap().is_empty(),
+        "{}",
+        output.map
+    );
+}
+
+#[test]
+fn parses_typescript_with_typescript() {
+    let config = Config::builder().typescript(true).build().unwrap();
+    let source = "async function f(id: string): Promise<void> { await g(id); }";
+    let output = transform_source(source, config).unwrap();
+    assert!(output.contains("id: string"), "{}", output);
+}
+
+#[test]
+fn reports_parse_errors() {
+    let error = transform_source("async function (", Config::default()).unwrap_err();
+    assert!(matches!(error, SourceError::Parse(_)), "{}", error);
+}
+
+#[test]
+fn fails_on_unsupported_code_with_error_on_unsupported() {
+    let config = Config::builder()
+        .error_on_unsupported(true)
+        .build()
+        .unwrap();
+    let error = transform_source("async function* f() {}", config).unwrap_err();
+    assert!(error.to_string().contains("async generator"), "{}", error);
+}
+
+#[test]
+fn prints_the_runtime_helper_module() {
+    let config = Config::builder()
+        .wrapper_function_name("ngAsync")
+        .import_helper(ImportHelper {
+            module: "@app/ng-async".into(),
+            name: Some("default".into()),
+        })
+        .build()
+        .unwrap();
+    let output = runtime_source(&config);
+    assert!(output.contains("function ngAsync(fn"), "{}", output);
+    assert!(
+        output.contains("function asyncGeneratorStep("),
+        "{}",
+        output
+    );
+    assert!(output.contains("function _ngUsingCtx("), "{}", output);
+    assert!(
+        output
+            .trim_end()
+            .ends_with("export { ngAsync as default, _ngUsingCtx };"),
+        "{}",
+        output
+    );
+}
@@ -0,0 +1,72 @@
+//! Files with thousands of async functions, checking that every hoisted
+//! statement lands in its place.
+
+use swc_plugin_transform_async_to_ng_generator::{Config, HoistPosition};
+
+mod common;
+
+const COUNT: usize = 3000;
+
+/// `COUNT` async function declarations, each followed by a call.
+fn declarations() -> String {
+    (0..COUNT)
+        .map(|i| format!("async function load{i}() {{ return await get({i}); }}\nload{i}();\n"))
+        .collect()
+}
+
+/// Position of `needle` in `output`, which must contain it.
+fn position(output: &str, needle: &str) -> usize {
+    output
+        .find(needle)
+        .unwrap_or_else(|| panic!("`{}` missing from the output", needle))
+}
+
+#[test]
+fn helpers_follow_the_last_declaration() {
+    let output = common::transform(&declarations(), Config::default());
+    let mut last = position(&output, &format!("function load{}(", COUNT - 1));
+    for i in 0..COUNT {
+        let helper = position(&output, &format!("function _load{i}("));
+        assert!(helper > last, "_load{} is out of place", i);
+        last = helper;
+    }
+}
+
+#[test]
+fn adjacent_helpers_follow_their_declaration() {
+    let config = Config::builder()
+        .hoist_position(HoistPosition::Adjacent)
+        .build()
+        .unwrap();
+    let output = common::transform(&declarations(), config);
+    for i in 0..COUNT {
+        let decl = position(&output, &format!("function load{i}("));
+        let helper = position(&output, &format!("function _load{i}("));
+        let call = position(&output, &format!("load{i}();"));
+        assert!(decl < helper && helper < call, "_load{} is out of place", i);
+    }
+}
+
+#[test]
+fn refs_precede_their_statement() {
+    let source: String = (0..COUNT)
+        .map(|i| format!("const get{i} = async () => await fetch({i});\n"))
+        .collect();
+    let output = common::transform(&source, Config::default());
+    let mut last = 0;
+    for i in 0..COUNT {
+        let name = if i == 0 {
+            "_ref".to_string()
+        } else {
+            format!("_ref{i}")
+        };
+        let assignment = position(&output, &format!("{name} = _ngAsyncToGenerator("));
+        let statement = position(&output, &format!("const get{i} = "));
+        assert!(
+            last < assignment && assignment < statement,
+            "{} is out of place",
+            name
+        );
+        last = statement;
+    }
+}
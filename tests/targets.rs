@@ -0,0 +1,54 @@
+use swc_plugin_transform_async_to_ng_generator::{Config, TargetVersion, Targets};
+
+fn config(targets: &[(&str, TargetVersion)]) -> Config {
+    Config::builder()
+        .targets(
+            targets
+                .iter()
+                .map(|(name, version)| (name.to_string(), version.clone()))
+                .collect::<Targets>(),
+        )
+        .build()
+        .unwrap()
+}
+
+fn text(version: &str) -> TargetVersion {
+    TargetVersion::Text(version.to_string())
+}
+
+#[test]
+fn transforms_without_targets() {
+    assert!(!Config::default().targets_support_async());
+    assert!(!config(&[]).targets_support_async());
+}
+
+#[test]
+fn modern_targets_skip_the_transform() {
+    let config = config(&[
+        ("chrome", text("61")),
+        ("firefox", TargetVersion::Number(60.0)),
+        ("node", text("7.6.0")),
+        ("safari", TargetVersion::Number(11.1)),
+    ]);
+    assert!(config.targets_support_async());
+}
+
+#[test]
+fn one_old_target_keeps_the_transform() {
+    let mixed = config(&[("chrome", text("61")), ("safari", text("10.1"))]);
+    assert!(!mixed.targets_support_async());
+    assert!(!config(&[("node", text("7.5"))]).targets_support_async());
+}
+
+#[test]
+fn unknown_targets_keep_the_transform() {
+    assert!(!config(&[("ie", text("11"))]).targets_support_async());
+    assert!(!config(&[("chrome", text("latest"))]).targets_support_async());
+}
+
+#[test]
+fn targets_deserialize_from_strings_and_numbers() {
+    let config: Config =
+        serde_json::from_str(r#"{ "targets": { "chrome": 58, "node": "8.9" } }"#).unwrap();
+    assert!(config.targets_support_async());
+}